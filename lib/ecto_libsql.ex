@@ -71,6 +71,48 @@ defmodule EctoLibSql do
   - `:busy_timeout` - Busy timeout in milliseconds (default: 5000)
                       Controls how long SQLite waits for locks before returning SQLITE_BUSY.
                       Set to 0 to disable (not recommended for production).
+  - `:default_transaction_mode` - Behaviour for transactions begun without an explicit
+                      mode: `:deferred` (default), `:immediate`, `:exclusive`, or `:read_only`.
+                      A `:behavior` passed to `EctoLibSql.Native.begin/2` still takes precedence.
+  - `:read_only` - Open a local database read-only (default: `false`). Writes fail and
+                      state-changing pragmas such as `PRAGMA writable_schema = ON` are
+                      rejected with `{:error, :read_only}`; introspection pragmas like
+                      `PRAGMA table_info(...)` still work. Not supported for remote modes.
+  - `:empty_string_as_null` - Bind empty string parameters (`""`) as `NULL` (default: `false`).
+                      Applies to every statement run on the connection, including
+                      prepared statements, batches and cursors.
+  - `:disable_statement_cache` - Prepare and finalise a fresh statement on every use of a
+                      prepared statement instead of caching it (default: `false`).
+                      Trades re-preparation cost for memory on connections that run
+                      many one-off statements.
+  - `:auto_reconnect` - For remote connections, rebuild a dropped connection and retry
+                      the failed query once (default: `false`). Only queries run
+                      outside a transaction are retried; a retried write may apply
+                      twice if the server received it before the connection dropped.
+  - `:max_sql_length` - Reject statements whose SQL is longer than this many bytes with
+                      `{:error, :sql_too_long}` before they are prepared. Guards against
+                      accidentally sending huge unparameterised SQL (default: unlimited).
+  - `:tag` - Label for the connection, such as a tenant name. Added to the metadata
+                      of `[:ecto_libsql, :query]` telemetry events and returned by
+                      `EctoLibSql.Native.connection_info/1`; filter open connections
+                      with `EctoLibSql.Native.list_connections/1`.
+  - `:optimize_on_close` - Run `PRAGMA optimize` when the connection is closed so the
+                      query planner statistics stay fresh (default: `false`). Not
+                      supported for `read_only` connections.
+                      See `EctoLibSql.Native.optimize/2`.
+  - `:secure_delete` - `:on`, `:off` or `:fast`. Sets `PRAGMA secure_delete` when the
+                      connection opens so deleted rows are overwritten in the file.
+                      See `EctoLibSql.Native.set_secure_delete/2`.
+  - `:threads` - Maximum number of auxiliary threads SQLite may use for large sorts
+                      (`PRAGMA threads`). Must be a non-negative integer.
+                      See `EctoLibSql.Native.set_threads/2`.
+  - `:local_replica` - Internal, for tests only; not a supported deployment mode.
+                      Only accepted when the NIF is built with
+                      `ECTO_LIBSQL_TEST_HOOKS=1`. Opens `:database` as an embedded
+                      replica with no primary (default: `false`), so every write
+                      fails with `{:error, :replica_read_only}`. Not supported with
+                      `:encryption_key`. Use `:uri`, `:auth_token` and `:sync` for
+                      real replicas.
 
   """
   @spec connect(Keyword.t()) :: {:ok, EctoLibSql.State.t()} | {:error, term()}
@@ -82,7 +124,8 @@ defmodule EctoLibSql do
         state = %EctoLibSql.State{
           conn_id: conn_id,
           mode: mode,
-          sync: EctoLibSql.State.detect_sync(opts)
+          sync: EctoLibSql.State.detect_sync(opts),
+          tag: Keyword.get(opts, :tag)
         }
 
         # Set busy_timeout for better concurrency handling
@@ -136,6 +179,53 @@ defmodule EctoLibSql do
   @doc """
   Executes an SQL query, delegating to transactional or non-transactional logic
   depending on the connection state.
+
+  ## Options
+
+  - `:trace_id` - Correlation id for tracing across services
+  - `:label` - Free-form label identifying the call site
+  - `:timing` - When `true`, the result's `:timing` field reports where the time
+    went (default `false`)
+  - `:cancellable` - An operation handle from `EctoLibSql.Native.new_operation/0`;
+    `EctoLibSql.Native.cancel/1` on that handle aborts just this call
+
+  Neither `:trace_id` nor `:label` affects the SQL. Both are attached to the
+  `[:ecto_libsql, :query]` telemetry event emitted for every call, and to the
+  `%EctoLibSql.Error{}` returned when the call fails.
+
+  Calls with `:timing` or `:cancellable` run through a separate native path
+  that never retries a failed statement. The `auto_reconnect` connect option
+  and the `{:backoff, base_ms, max_ms}` busy policy do not apply to them.
+
+  ## Timing
+
+  With `timing: true` the statement is prepared, run and its rows collected as
+  separate timed steps, and `result.timing` is a map of microseconds:
+  `%{prepare_us: _, execute_us: _, fetch_us: _, total_us: _}`. `total_us`
+  covers the whole native call, so the three steps sum to slightly less. SQLite
+  computes rows as they are stepped, so for SELECTs most of the work shows up
+  in `fetch_us`. This is a diagnostic aid; leave it off in normal use.
+
+  ## Cancellation
+
+  A call blocks its process, so the handle is created first and handed to
+  whichever process may cancel it:
+
+      {:ok, op} = EctoLibSql.Native.new_operation()
+      task = Task.async(fn -> EctoLibSql.handle_execute(sql, [], [cancellable: op], state) end)
+      :ok = EctoLibSql.Native.cancel(op)
+      {:error, %EctoLibSql.Error{message: "Operation cancelled"}, _state} = Task.await(task)
+
+  Cancelling before the statement starts (for example while it waits for the
+  connection) stops it from running; cancelling while it runs interrupts it.
+  Other calls on the connection are unaffected. Handles are single-use.
+
+  ## Telemetry
+
+  `[:ecto_libsql, :query]` is emitted after each call with measurements
+  `%{duration: native_time}` and metadata `%{statement: sql, result: :ok | :error,
+  trace_id: trace_id | nil, label: label | nil, tag: tag | nil}`, where `tag` is
+  the connection's `:tag` connect option.
   """
   @spec handle_execute(
           EctoLibSql.Query.t() | String.t(),
@@ -145,13 +235,49 @@ defmodule EctoLibSql do
         ) ::
           {:ok, EctoLibSql.Query.t(), EctoLibSql.Result.t(), EctoLibSql.State.t()}
           | {:error, EctoLibSql.Error.t(), EctoLibSql.State.t()}
-  def handle_execute(query, args, _opts, %EctoLibSql.State{trx_id: trx_id} = state) do
+  def handle_execute(query, args, opts, %EctoLibSql.State{} = state) do
+    start = System.monotonic_time()
+    result = execute_query(query, args, opts, state)
+    duration = System.monotonic_time() - start
+
+    trace_id = Keyword.get(opts, :trace_id)
+    label = Keyword.get(opts, :label)
+
+    :telemetry.execute([:ecto_libsql, :query], %{duration: duration}, %{
+      statement: statement_text(query),
+      result: elem(result, 0),
+      trace_id: trace_id,
+      label: label,
+      tag: state.tag
+    })
+
+    case result do
+      {:error, %EctoLibSql.Error{} = error, state} ->
+        {:error, %{error | trace_id: trace_id, label: label}, state}
+
+      other ->
+        other
+    end
+  end
+
+  defp statement_text(%EctoLibSql.Query{statement: statement}), do: statement
+  defp statement_text(statement) when is_binary(statement), do: statement
+
+  defp execute_query(query, args, opts, %EctoLibSql.State{trx_id: trx_id} = state) do
     query_struct =
       case query do
         %EctoLibSql.Query{} -> query
         query when is_binary(query) -> %EctoLibSql.Query{statement: query}
       end
 
+    if Keyword.get(opts, :timing, false) or Keyword.has_key?(opts, :cancellable) do
+      EctoLibSql.Native.query_with_options(state, query_struct, args, opts)
+    else
+      execute_plain(query_struct, args, trx_id, state)
+    end
+  end
+
+  defp execute_plain(query_struct, args, trx_id, state) do
     # Check if query returns rows (SELECT, EXPLAIN, WITH, RETURNING clauses).
     # If so, route through query path instead of execute path.
     sql = query_struct.statement
@@ -206,6 +332,10 @@ defmodule EctoLibSql do
   # Build an EctoLibSql.Error from various reason formats.
   defp build_error(%EctoLibSql.Error{} = error), do: error
 
+  defp build_error(:read_only), do: EctoLibSql.Error.read_only()
+  defp build_error(:sql_too_long), do: EctoLibSql.Error.sql_too_long()
+  defp build_error(:replica_read_only), do: EctoLibSql.Error.replica_read_only()
+
   defp build_error(reason) when is_binary(reason) do
     %EctoLibSql.Error{message: reason, sqlite: %{code: :error, message: reason}}
   end
@@ -330,10 +460,10 @@ defmodule EctoLibSql do
   """
   def handle_commit(_opts, state) do
     case EctoLibSql.Native.commit(
-           %EctoLibSql.State{conn_id: conn_id, trx_id: _trx_id, mode: mode} = state
+           %EctoLibSql.State{conn_id: conn_id, trx_id: _trx_id, mode: mode, tag: tag} = state
          ) do
       {:ok, _} ->
-        {:ok, %EctoLibSql.Result{}, %EctoLibSql.State{conn_id: conn_id, mode: mode}}
+        {:ok, %EctoLibSql.Result{}, %EctoLibSql.State{conn_id: conn_id, mode: mode, tag: tag}}
 
       {:error, reason} ->
         {:disconnect, reason, state}
@@ -347,10 +477,10 @@ defmodule EctoLibSql do
   Discards all changes made within the transaction and returns the connection
   to autocommit mode.
   """
-  def handle_rollback(_opts, %EctoLibSql.State{conn_id: conn_id, trx_id: _trx_id} = state) do
+  def handle_rollback(_opts, %EctoLibSql.State{conn_id: conn_id, tag: tag} = state) do
     case EctoLibSql.Native.rollback(state) do
       {:ok, _} ->
-        {:ok, %EctoLibSql.Result{}, %EctoLibSql.State{conn_id: conn_id, trx_id: nil}}
+        {:ok, %EctoLibSql.Result{}, %EctoLibSql.State{conn_id: conn_id, trx_id: nil, tag: tag}}
 
       {:error, reason} ->
         {:disconnect, reason, state}
@@ -462,18 +592,45 @@ defmodule EctoLibSql do
 
   Cursors allow you to iterate through large result sets in chunks, which is
   more memory-efficient than loading all rows at once.
+
+  ## Options
+
+  - `:prefetch` - Read up to this many batches of `:max_rows` rows ahead in
+    the background, so a slow consumer finds the next batch ready instead of
+    waiting on the database. Reading pauses while that many batches are
+    waiting, which keeps memory bounded.
+  - `:max_rows` - Batch size used with `:prefetch` (default: 500)
   """
   def handle_declare(
         %EctoLibSql.Query{statement: statement} = query,
         params,
-        _opts,
+        opts,
         %EctoLibSql.State{conn_id: conn_id, trx_id: trx_id} = state
       ) do
     # Use transaction ID if in a transaction, otherwise use connection ID
     id = trx_id || conn_id
     id_type = if trx_id, do: :transaction, else: :connection
 
-    case EctoLibSql.Native.declare_cursor_with_context(conn_id, id, id_type, statement, params) do
+    declared =
+      case Keyword.get(opts, :prefetch) do
+        nil ->
+          EctoLibSql.Native.declare_cursor_with_context(conn_id, id, id_type, statement, params)
+
+        prefetch ->
+          max_rows = Keyword.get(opts, :max_rows, 500)
+
+          EctoLibSql.Native.declare_prefetch_cursor(
+            conn_id,
+            id,
+            id_type,
+            statement,
+            params,
+            max_rows,
+            prefetch
+          )
+      end
+
+    case declared do
       cursor_id when is_binary(cursor_id) ->
         cursor = %{ref: cursor_id}
         {:ok, query, cursor, state}
@@ -9,16 +9,20 @@ defmodule EctoLibSql.Error do
 
   - `:message` - Human-readable error message
   - `:sqlite` - Map containing SQLite-specific error details (`:code`, `:message`)
+  - `:trace_id` - Correlation id passed as the `:trace_id` option of the failing call
+  - `:label` - Label passed as the `:label` option of the failing call
   """
 
-  defexception [:message, :sqlite]
+  defexception [:message, :sqlite, :trace_id, :label]
 
   @type t :: %__MODULE__{
           message: String.t(),
           sqlite: %{
             code: atom(),
             message: String.t()
-          }
+          },
+          trace_id: term() | nil,
+          label: term() | nil
         }
 
   @doc """
@@ -57,4 +61,52 @@ defmodule EctoLibSql.Error do
       _ -> nil
     end
   end
+
+  @doc """
+  Builds the error returned when a statement is rejected on a connection
+  opened with `read_only: true`.
+
+  ## Examples
+
+      iex> EctoLibSql.Error.read_only().sqlite.code
+      :read_only
+
+  """
+  def read_only do
+    message = "statement not allowed on a read-only connection"
+    %__MODULE__{message: message, sqlite: %{code: :read_only, message: message}}
+  end
+
+  @doc """
+  Builds the error returned when a statement is longer than the connection's
+  `max_sql_length`.
+
+  ## Examples
+
+      iex> EctoLibSql.Error.sql_too_long().sqlite.code
+      :sql_too_long
+
+  """
+  def sql_too_long do
+    message = "SQL exceeds the connection's max_sql_length"
+    %__MODULE__{message: message, sqlite: %{code: :sql_too_long, message: message}}
+  end
+
+  @doc """
+  Builds the error returned when a write reaches an embedded replica that has
+  no primary to forward it to.
+
+  ## Examples
+
+      iex> EctoLibSql.Error.replica_read_only().sqlite.code
+      :replica_read_only
+
+  """
+  def replica_read_only do
+    message =
+      "writes are not allowed on a replica without a primary; " <>
+        "connect with :uri and :auth_token to forward writes"
+
+    %__MODULE__{message: message, sqlite: %{code: :replica_read_only, message: message}}
+  end
 end
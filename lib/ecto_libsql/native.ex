@@ -10,26 +10,68 @@ defmodule EctoLibSql.Native do
   The NIF functions are implemented in Rust (`native/ecto_libsql/src/lib.rs`) and
   provide direct access to LibSQL operations:
 
-  - Connection management: `connect/2`, `ping/1`, `close/2`
-  - Query execution: `query_args/5`, `execute_with_transaction/3`
+  - Connection management: `connect/2`, `ping/1`, `close/2`, `connection_info_native/1`,
+    `list_connections_native/1`
+  - Encryption: `verify_encryption_key_native/3`
+  - Query execution: `query_args/5`, `execute_with_transaction/3`,
+    `query_with_options_native/5`
+  - Cancellation: `new_operation_native/0`, `cancel_operation_native/1`
   - Transaction control: `begin_transaction_with_behavior/2`, `commit_or_rollback_transaction/5`
-  - Prepared statements: `prepare_statement/2`, `query_prepared/5`, `execute_prepared/6`
+  - Prepared statements: `prepare_statement/2`, `query_prepared/5`, `execute_prepared/6`,
+    `cached_statement_count/1`
   - Batch operations: `execute_batch/4`, `execute_transactional_batch/4`
-  - Metadata: `last_insert_rowid/1`, `changes/1`, `total_changes/1`, `is_autocommit/1`
-  - Cursors: `declare_cursor/3`, `fetch_cursor/2`
-  - Sync: `do_sync/2`
+  - Pipelines: `query_pipeline_native/2`
+  - Maintenance: `reindex_native/2`
+  - Export: `dump_table_ndjson_native/3`, `materialize_to_db_native/5`
+  - File blobs: `execute_blob_from_file_native/4`
+  - Metadata: `last_insert_rowid/1`, `changes/1`, `total_changes/1`, `is_autocommit/1`, `schema_version/1`
+  - Memory: `memory_used_native/1`, `release_memory_native/1`
+  - Pagination: `keyset_page_native/6`
+  - Reducers: `query_reduce_native/5`
+  - Secure delete: `set_secure_delete_native/2`, `get_secure_delete_native/1`
+  - Busy handling: `set_busy_timeout/2`, `set_busy_handler_native/2`
+  - Threads: `set_threads_native/2`
+  - Optimize: `optimize_native/2`
+  - Cursors: `declare_cursor/3`, `declare_prefetch_cursor/7`, `fetch_cursor/2`, `reap_cursors/1`,
+    `cursor_buffered_rows/2`
+  - Sync: `do_sync/2`, `replica_metrics/1`, `wait_for_frame_native/3`
 
   ## Helper Functions
 
   High-level Elixir wrappers that provide ergonomic interfaces:
 
   - `query/3`, `execute_non_trx/3`, `execute_with_trx/3` - Query execution
-  - `begin/2`, `commit/1`, `rollback/1` - Transaction management
+  - `begin/2`, `begin_snapshot/1`, `commit/1`, `rollback/1` - Transaction management
   - `prepare/2`, `execute_stmt/4`, `query_stmt/3`, `close_stmt/1` - Prepared statements
-  - `batch/2`, `batch_transactional/2` - Batch operations
-  - `get_last_insert_rowid/1`, `get_changes/1`, `get_total_changes/1`, `get_is_autocommit/1` - Metadata
+  - `statement_cache_size/1` - Number of statements a connection has cached
+  - `batch/2`, `batch_transactional/2`, `drop_tables/3`, `truncate/3`,
+    `reset_sequence/3`, `copy_into/6` - Batch operations
+  - `query_pipeline/3` - Run several read queries in one call, results in order
+  - `reindex/2` - Rebuild all indexes, or those of one table, index or collation
+  - `new_operation/0`, `cancel/1` - Cancel a single call by operation handle
+  - `dump_table_ndjson/3` - Export a table as newline-delimited JSON
+  - `materialize_to_db/5` - Snapshot a query result into a new database file
+  - `insert_blob_from_file/5` - Bind a file's contents as a blob without reading it into the BEAM
+  - `count/4`, `query_one/3`, `query_scalar/3` - Typed query helpers
+  - `query_reduce/4` - Sum, min, max or distinct values of a column, reduced in Rust
+  - `affected_keys/3` - Primary keys of rows touched by a bulk write
+  - `keyset_page/6` - Keyset pagination
+  - `table_exists/2`, `table_schema/2`, `list_objects/3` - Schema introspection
+  - `ensure_schema/2` - Idempotently create tables and indexes, reporting what was created
+  - `drop_temp_objects/1` - Drop a connection's `CREATE TEMP` tables, views and triggers
+  - `get_last_insert_rowid/1`, `get_changes/1`, `get_total_changes/1`, `get_is_autocommit/1`,
+    `get_schema_version/1` - Metadata
+  - `memory_used/0`, `release_memory/1` - Process-wide SQLite memory statistics
+  - `busy_timeout/2`, `set_busy_handler/2` - How long to wait when the database is locked
+  - `set_secure_delete/2`, `get_secure_delete/1` - Overwriting deleted content
+  - `set_threads/2` - Auxiliary sorter threads
+  - `optimize/2` - Refresh stale query planner statistics
+  - `reap_stale_cursors/1`, `cursor_buffer_size/2` - Cursor cleanup and inspection
   - `vector/1`, `vector_type/2`, `vector_distance_cos/2` - Vector search helpers
+  - `connection_info/1`, `list_connections/1` - Inspect open connections and their tags
+  - `verify_encryption_key/3` - Check an encryption key before opening connections
   - `sync/1` - Manual replica sync
+  - `wait_for_frame/3` - Wait for a replica to catch up to a frame
 
   ## Thread Safety
 
@@ -39,9 +81,15 @@ defmodule EctoLibSql.Native do
 
   """
 
+  # ECTO_LIBSQL_TEST_HOOKS=1 builds the test-only NIFs (see the `test-hooks` cargo feature)
+  @nif_features if System.get_env("ECTO_LIBSQL_TEST_HOOKS") in ["1", "true"],
+                  do: ["test-hooks"],
+                  else: []
+
   use Rustler,
     otp_app: :ecto_libsql,
-    crate: :ecto_libsql
+    crate: :ecto_libsql,
+    features: @nif_features
 
   # Raw NIF functions - implemented in Rust (native/ecto_libsql/src/lib.rs)
   # These all raise :nif_not_loaded errors until the NIF is loaded
@@ -55,6 +103,16 @@ defmodule EctoLibSql.Native do
   @doc false
   def query_args(_conn, _mode, _query, _args, _sync), do: :erlang.nif_error(:nif_not_loaded)
 
+  @doc false
+  def query_with_options_native(_conn_id, _trx_id, _op_id, _query, _args),
+    do: :erlang.nif_error(:nif_not_loaded)
+
+  @doc false
+  def new_operation_native, do: :erlang.nif_error(:nif_not_loaded)
+
+  @doc false
+  def cancel_operation_native(_op_id), do: :erlang.nif_error(:nif_not_loaded)
+
   @doc false
   def begin_transaction(_conn), do: :erlang.nif_error(:nif_not_loaded)
 
@@ -89,6 +147,9 @@ defmodule EctoLibSql.Native do
   def execute_transactional_batch(_conn, _mode, _sync, _statements),
     do: :erlang.nif_error(:nif_not_loaded)
 
+  @doc false
+  def query_pipeline_native(_conn_id, _queries), do: :erlang.nif_error(:nif_not_loaded)
+
   @doc false
   def prepare_statement(_conn, _sql), do: :erlang.nif_error(:nif_not_loaded)
 
@@ -99,10 +160,17 @@ defmodule EctoLibSql.Native do
   def execute_prepared(_conn, _stmt_id, _mode, _sync, _sql_hint, _args),
     do: :erlang.nif_error(:nif_not_loaded)
 
+  @doc false
+  def cached_statement_count(_conn_id), do: :erlang.nif_error(:nif_not_loaded)
+
   @doc false
   def declare_cursor_with_context(_conn_id, _id, _id_type, _sql, _args),
     do: :erlang.nif_error(:nif_not_loaded)
 
+  @doc false
+  def declare_prefetch_cursor(_conn_id, _id, _id_type, _sql, _args, _batch_size, _prefetch),
+    do: :erlang.nif_error(:nif_not_loaded)
+
   @doc false
   def last_insert_rowid(_conn), do: :erlang.nif_error(:nif_not_loaded)
 
@@ -115,21 +183,50 @@ defmodule EctoLibSql.Native do
   @doc false
   def is_autocommit(_conn), do: :erlang.nif_error(:nif_not_loaded)
 
+  @doc false
+  def schema_version(_conn), do: :erlang.nif_error(:nif_not_loaded)
+
   @doc false
   def declare_cursor(_conn, _sql, _args), do: :erlang.nif_error(:nif_not_loaded)
 
   @doc false
   def fetch_cursor(_conn_id, _cursor_id, _max_rows), do: :erlang.nif_error(:nif_not_loaded)
 
+  @doc false
+  def reap_cursors(_older_than_ms), do: :erlang.nif_error(:nif_not_loaded)
+
+  @doc false
+  def cursor_buffered_rows(_conn_id, _cursor_id), do: :erlang.nif_error(:nif_not_loaded)
+
   @doc false
   def set_busy_timeout(_conn_id, _timeout_ms), do: :erlang.nif_error(:nif_not_loaded)
 
+  @doc false
+  def set_busy_handler_native(_conn_id, _policy), do: :erlang.nif_error(:nif_not_loaded)
+
+  @doc false
+  def set_secure_delete_native(_conn_id, _mode), do: :erlang.nif_error(:nif_not_loaded)
+
+  @doc false
+  def get_secure_delete_native(_conn_id), do: :erlang.nif_error(:nif_not_loaded)
+
+  @doc false
+  def set_threads_native(_conn_id, _limit), do: :erlang.nif_error(:nif_not_loaded)
+
+  @doc false
+  def optimize_native(_conn_id, _mask), do: :erlang.nif_error(:nif_not_loaded)
+
   @doc false
   def reset_connection(_conn_id), do: :erlang.nif_error(:nif_not_loaded)
 
   @doc false
   def interrupt_connection(_conn_id), do: :erlang.nif_error(:nif_not_loaded)
 
+  @doc false
+  # Test support: make a remote connection's next request fail as if the network dropped.
+  # Only loaded when built with ECTO_LIBSQL_TEST_HOOKS=1.
+  def simulate_connection_loss(_conn_id), do: :erlang.nif_error(:nif_not_loaded)
+
   @doc false
   def enable_load_extension(_conn_id, _enabled), do: :erlang.nif_error(:nif_not_loaded)
 
@@ -157,6 +254,51 @@ defmodule EctoLibSql.Native do
   @doc false
   def execute_transactional_batch_native(_conn_id, _sql), do: :erlang.nif_error(:nif_not_loaded)
 
+  @doc false
+  def drop_tables_native(_conn_id, _names, _if_exists), do: :erlang.nif_error(:nif_not_loaded)
+
+  @doc false
+  def truncate_native(_conn_id, _table, _reset_sequence), do: :erlang.nif_error(:nif_not_loaded)
+
+  @doc false
+  def reset_sequence_native(_conn_id, _table, _value), do: :erlang.nif_error(:nif_not_loaded)
+
+  @doc false
+  def reindex_native(_conn_id, _target), do: :erlang.nif_error(:nif_not_loaded)
+
+  @doc false
+  def copy_into_native(_conn_id, _dest_table, _select_sql, _args, _batch_size, _key),
+    do: :erlang.nif_error(:nif_not_loaded)
+
+  @doc false
+  def dump_table_ndjson_native(_conn_id, _table, _dest_path),
+    do: :erlang.nif_error(:nif_not_loaded)
+
+  @doc false
+  def materialize_to_db_native(_conn_id, _sql, _args, _dest_path, _table),
+    do: :erlang.nif_error(:nif_not_loaded)
+
+  @doc false
+  def execute_blob_from_file_native(_conn_id, _sql, _args, _path, _max_bytes),
+    do: :erlang.nif_error(:nif_not_loaded)
+
+  @doc false
+  def memory_used_native(_reset_highwater), do: :erlang.nif_error(:nif_not_loaded)
+
+  @doc false
+  def release_memory_native(_bytes), do: :erlang.nif_error(:nif_not_loaded)
+
+  @doc false
+  def count_native(_conn_id, _table, _where, _args), do: :erlang.nif_error(:nif_not_loaded)
+
+  @doc false
+  def keyset_page_native(_conn_id, _base_sql, _order_col, _last_key, _limit, _args),
+    do: :erlang.nif_error(:nif_not_loaded)
+
+  @doc false
+  def query_reduce_native(_conn_id, _sql, _args, _op, _column),
+    do: :erlang.nif_error(:nif_not_loaded)
+
   @doc false
   def statement_column_count(_conn_id, _stmt_id), do: :erlang.nif_error(:nif_not_loaded)
 
@@ -184,15 +326,35 @@ defmodule EctoLibSql.Native do
   @doc false
   def rollback_to_savepoint(_conn_id, _trx_id, _name), do: :erlang.nif_error(:nif_not_loaded)
 
+  @doc false
+  def savepoint_depth_native(_conn_id, _trx_id), do: :erlang.nif_error(:nif_not_loaded)
+
   @doc false
   def get_frame_number(_conn_id), do: :erlang.nif_error(:nif_not_loaded)
 
   @doc false
   def sync_until(_conn_id, _frame_no), do: :erlang.nif_error(:nif_not_loaded)
 
+  @doc false
+  def wait_for_frame_native(_conn_id, _frame_no, _timeout_ms),
+    do: :erlang.nif_error(:nif_not_loaded)
+
   @doc false
   def flush_replicator(_conn_id), do: :erlang.nif_error(:nif_not_loaded)
 
+  @doc false
+  def replica_metrics(_conn_id), do: :erlang.nif_error(:nif_not_loaded)
+
+  @doc false
+  def connection_info_native(_conn_id), do: :erlang.nif_error(:nif_not_loaded)
+
+  @doc false
+  def list_connections_native(_tag), do: :erlang.nif_error(:nif_not_loaded)
+
+  @doc false
+  def verify_encryption_key_native(_path, _key, _cipher),
+    do: :erlang.nif_error(:nif_not_loaded)
+
   @doc """
   Get the highest frame number from write operations (for read-your-writes consistency).
 
@@ -725,11 +887,68 @@ defmodule EctoLibSql.Native do
 
         {:ok, query, result, state}
 
-      {:error, message} ->
-        {:error, %EctoLibSql.Error{message: message}, state}
+      {:error, reason} ->
+        {:error, native_error_to_exception(reason), state}
+    end
+  end
+
+  @doc false
+  # Run a statement with the `:timing` and `:cancellable` query options, attaching
+  # step timings to the result when asked. Works inside and outside a transaction.
+  def query_with_options(
+        %EctoLibSql.State{conn_id: conn_id, trx_id: trx_id} = state,
+        %EctoLibSql.Query{statement: statement} = query,
+        args,
+        opts
+      ) do
+    case normalise_arguments(conn_id, statement, args) do
+      {:error, reason} ->
+        {:error,
+         %EctoLibSql.Error{
+           message: "Failed to prepare statement for parameter introspection: #{reason}"
+         }, state}
+
+      args_for_execution ->
+        encoded_args = encode_parameters(args_for_execution)
+
+        op_id = Keyword.get(opts, :cancellable)
+
+        case query_with_options_native(conn_id, trx_id, op_id, statement, encoded_args) do
+          %{"columns" => columns, "rows" => rows, "num_rows" => num_rows, "timing" => timing} ->
+            command = detect_command(statement)
+
+            {columns, rows} =
+              if command in [:insert, :update, :delete] and columns == [] and rows == [] do
+                {nil, nil}
+              else
+                {columns, rows}
+              end
+
+            result = %EctoLibSql.Result{
+              command: command,
+              columns: columns,
+              rows: rows,
+              num_rows: num_rows,
+              timing: if(Keyword.get(opts, :timing, false), do: atomize_timing(timing))
+            }
+
+            {:ok, query, result, state}
+
+          {:error, reason} ->
+            {:error, native_error_to_exception(reason), state}
+        end
     end
   end
 
+  defp atomize_timing(timing) do
+    %{
+      prepare_us: timing["prepare_us"],
+      execute_us: timing["execute_us"],
+      fetch_us: timing["fetch_us"],
+      total_us: timing["total_us"]
+    }
+  end
+
   @doc false
   def execute_with_trx(
         %EctoLibSql.State{conn_id: conn_id, trx_id: trx_id} = state,
@@ -791,8 +1010,8 @@ defmodule EctoLibSql.Native do
 
           {:ok, query, result, state}
 
-        {:error, message} ->
-          {:error, %EctoLibSql.Error{message: message}, state}
+        {:error, reason} ->
+          {:error, native_error_to_exception(reason), state}
       end
     else
       # Use execute_with_transaction for INSERT/UPDATE/DELETE without RETURNING
@@ -805,19 +1024,27 @@ defmodule EctoLibSql.Native do
 
           {:ok, query, result, state}
 
-        {:error, message} ->
-          {:error, %EctoLibSql.Error{message: message}, state}
+        {:error, reason} ->
+          {:error, native_error_to_exception(reason), state}
       end
     end
   end
 
+  # Convert the error reason returned by a statement NIF into the exception that
+  # DBConnection callbacks return.
+  defp native_error_to_exception(:read_only), do: EctoLibSql.Error.read_only()
+  defp native_error_to_exception(:sql_too_long), do: EctoLibSql.Error.sql_too_long()
+  defp native_error_to_exception(:replica_read_only), do: EctoLibSql.Error.replica_read_only()
+  defp native_error_to_exception(message), do: %EctoLibSql.Error{message: message}
+
   @doc """
   Begin a new transaction with optional behaviour control.
 
   ## Parameters
     - state: The connection state
     - opts: Options keyword list
-      - `:behavior` - Transaction behaviour (`:deferred`, `:immediate`, or `:exclusive`), defaults to `:deferred`
+      - `:behavior` - Transaction behaviour (`:deferred`, `:immediate`, `:exclusive`, or `:read_only`).
+        Defaults to the connection's `:default_transaction_mode`, which is `:deferred` unless set at connect time
 
   ## Transaction Behaviours
 
@@ -831,12 +1058,17 @@ defmodule EctoLibSql.Native do
   """
   @spec begin(EctoLibSql.State.t(), Keyword.t()) ::
           {:ok, EctoLibSql.State.t()} | {:error, term()}
-  def begin(%EctoLibSql.State{conn_id: conn_id, mode: mode} = _state, opts \\ []) do
-    behavior = Keyword.get(opts, :behavior, :deferred)
+  def begin(%EctoLibSql.State{conn_id: conn_id, mode: mode, tag: tag} = _state, opts \\ []) do
+    result =
+      case Keyword.fetch(opts, :behavior) do
+        {:ok, behavior} -> begin_transaction_with_behavior(conn_id, behavior)
+        # No explicit mode: the NIF applies the connection's default_transaction_mode
+        :error -> begin_transaction(conn_id)
+      end
 
-    case begin_transaction_with_behavior(conn_id, behavior) do
+    case result do
       trx_id when is_binary(trx_id) ->
-        {:ok, %EctoLibSql.State{conn_id: conn_id, trx_id: trx_id, mode: mode}}
+        {:ok, %EctoLibSql.State{conn_id: conn_id, trx_id: trx_id, mode: mode, tag: tag}}
 
       {:error, reason} ->
         {:error, reason}
@@ -879,6 +1111,53 @@ defmodule EctoLibSql.Native do
     commit_or_rollback_transaction(trx_id, conn_id, mode, syncx, "rollback")
   end
 
+  @doc """
+  Begin a read transaction pinned to a stable snapshot of the database.
+
+  Opens a deferred transaction and immediately reads from the schema table,
+  which makes SQLite take its read lock and fix the snapshot at that point.
+  Every query run with the returned state sees the database exactly as it was
+  when this function returned, regardless of later commits from other
+  connections. The snapshot holds until `commit/1` or `rollback/1` is called.
+
+  In WAL mode a held snapshot does not block writers: other connections can keep
+  committing while the report runs. It does, however, stop checkpoints from
+  advancing past the snapshot, so the WAL file grows until the transaction is
+  released. Always end the transaction, even on error.
+
+  In rollback-journal modes the shared lock taken by the snapshot blocks writers
+  from committing, so only use this helper with `journal_mode = WAL`.
+
+  ## Parameters
+    - state: The connection state
+
+  ## Example
+      {:ok, snapshot} = EctoLibSql.Native.begin_snapshot(state)
+
+      try do
+        {:ok, _query, totals, _} = EctoLibSql.handle_execute(report_sql, [], [], snapshot)
+        totals
+      after
+        EctoLibSql.Native.rollback(snapshot)
+      end
+
+  """
+  @spec begin_snapshot(EctoLibSql.State.t()) :: {:ok, EctoLibSql.State.t()} | {:error, term()}
+  def begin_snapshot(%EctoLibSql.State{} = state) do
+    with {:ok, %EctoLibSql.State{conn_id: conn_id, trx_id: trx_id} = trx_state} <-
+           begin(state, behavior: :deferred) do
+      # A deferred BEGIN does not touch the database; the first read does.
+      case query_with_trx_args(trx_id, conn_id, "SELECT COUNT(*) FROM sqlite_master", []) do
+        %{"rows" => _rows} ->
+          {:ok, trx_state}
+
+        {:error, reason} ->
+          rollback(trx_state)
+          {:error, reason}
+      end
+    end
+  end
+
   @doc """
   Detects the SQL command type from a query string.
 
@@ -1120,9 +1399,37 @@ defmodule EctoLibSql.Native do
     close(stmt_id, :stmt_id)
   end
 
+  @doc """
+  Count the prepared statements a connection holds in the statement cache.
+
+  Connections opened with `disable_statement_cache: true` never cache
+  statements, so this is always `0` for them.
+
+  ## Parameters
+    - state: The connection state
+
+  ## Example
+      {:ok, _stmt_id} = EctoLibSql.Native.prepare(state, "SELECT 1")
+      {:ok, 1} = EctoLibSql.Native.statement_cache_size(state)
+  """
+  @spec statement_cache_size(EctoLibSql.State.t()) :: {:ok, non_neg_integer()} | {:error, term()}
+  def statement_cache_size(%EctoLibSql.State{conn_id: conn_id} = _state) do
+    case cached_statement_count(conn_id) do
+      count when is_integer(count) -> {:ok, count}
+      {:error, reason} -> {:error, reason}
+    end
+  end
+
   @doc """
   Get the rowid of the last inserted row.
 
+  The value is per connection and follows SQLite's `last_insert_rowid()`
+  rules: inserts made by triggers only count while the trigger runs, and a
+  statement that inserts nothing (such as `INSERT OR IGNORE` hitting a
+  conflict) leaves the previous value in place. When you need the id of the
+  row a statement inserted, prefer `INSERT ... RETURNING id`; the Ecto
+  adapter already does this for `Repo.insert/2`.
+
   ## Parameters
     - state: The connection state
 
@@ -1174,6 +1481,136 @@ defmodule EctoLibSql.Native do
     is_autocommit(conn_id)
   end
 
+  @doc """
+  Get the current schema version (SQLite's schema cookie).
+
+  SQLite bumps the schema version on every schema change (`CREATE`, `DROP`,
+  `ALTER`, ...). Compare values taken at different times to detect DDL changes,
+  for example to correlate with your own caches.
+
+  Prepared statement introspection (`stmt_column_count/2`, `get_stmt_columns/2`,
+  etc.) uses this internally to re-prepare statements that were compiled against
+  an older schema, so their metadata always reflects the current schema.
+
+  ## Parameters
+    - state: The connection state
+
+  ## Returns
+    - `{:ok, version}` - The current schema version
+    - `{:error, reason}` - If the connection is invalid
+
+  ## Example
+      {:ok, before} = EctoLibSql.Native.get_schema_version(state)
+      # ... run a migration ...
+      {:ok, after_migration} = EctoLibSql.Native.get_schema_version(state)
+      schema_changed? = after_migration != before
+  """
+  @spec get_schema_version(EctoLibSql.State.t()) :: {:ok, integer()} | {:error, term()}
+  def get_schema_version(%EctoLibSql.State{conn_id: conn_id} = _state) do
+    case schema_version(conn_id) do
+      version when is_integer(version) -> {:ok, version}
+      {:error, reason} -> {:error, reason}
+    end
+  end
+
+  @doc """
+  Report SQLite's heap usage in bytes.
+
+  These statistics are **global to the BEAM process**: they cover every
+  connection and database opened through the NIF, not a single connection.
+  Useful for spotting leaks in long-lived nodes.
+
+  ## Options
+    - `:reset_highwater` - Reset the high-water mark to current usage after
+      reading it (default `false`)
+
+  ## Returns
+    - `{:ok, %{used: bytes, highwater: bytes}}` - Current and peak allocation
+
+  ## Example
+      {:ok, %{used: used, highwater: peak}} = EctoLibSql.Native.memory_used()
+  """
+  @spec memory_used(Keyword.t()) ::
+          {:ok, %{used: non_neg_integer(), highwater: non_neg_integer()}}
+  def memory_used(opts \\ []) do
+    {used, highwater} = memory_used_native(Keyword.get(opts, :reset_highwater, false))
+    {:ok, %{used: used, highwater: highwater}}
+  end
+
+  @doc """
+  Ask SQLite to free up to `bytes` of unused heap memory.
+
+  Like `memory_used/1`, this is process-wide. SQLite only releases memory
+  (mostly page cache) when compiled with `SQLITE_ENABLE_MEMORY_MANAGEMENT`;
+  otherwise the call is a harmless no-op that frees 0 bytes.
+
+  ## Parameters
+    - bytes: Upper bound on how much to release (defaults to as much as possible)
+
+  ## Returns
+    - `{:ok, freed}` - The number of bytes actually released
+
+  ## Example
+      {:ok, _freed} = EctoLibSql.Native.release_memory()
+  """
+  @spec release_memory(non_neg_integer()) :: {:ok, non_neg_integer()}
+  def release_memory(bytes \\ 2_147_483_647)
+      when is_integer(bytes) and bytes >= 0 and bytes <= 2_147_483_647 do
+    {:ok, release_memory_native(bytes)}
+  end
+
+  @doc """
+  Close cursors declared more than `older_than_ms` milliseconds ago.
+
+  Cursors buffer their rows in the NIF until the stream finishes. If the
+  process consuming a stream crashes, its cursor is never closed and the rows
+  stay in memory. This reaps such orphans across all connections, so pick a
+  threshold comfortably longer than your slowest legitimate stream.
+
+  ## Parameters
+    - older_than_ms: Minimum age in milliseconds for a cursor to be closed
+
+  ## Returns
+    - `{:ok, count}` - The number of cursors closed
+    - `{:error, reason}` - If the cursor registry could not be accessed
+
+  ## Example
+      # Periodically clean up cursors older than five minutes
+      {:ok, reaped} = EctoLibSql.Native.reap_stale_cursors(:timer.minutes(5))
+  """
+  @spec reap_stale_cursors(non_neg_integer()) :: {:ok, non_neg_integer()} | {:error, term()}
+  def reap_stale_cursors(older_than_ms) when is_integer(older_than_ms) and older_than_ms >= 0 do
+    case reap_cursors(older_than_ms) do
+      count when is_integer(count) -> {:ok, count}
+      {:error, reason} -> {:error, reason}
+    end
+  end
+
+  @doc """
+  Count the rows a cursor currently holds in native memory.
+
+  Local cursors read their whole result set when declared, so this starts at
+  the total row count. Cursors on remote connections read rows from the server
+  as they are fetched and hold at most one batch. Cursors declared with
+  `prefetch: n` hold at most `n + 1` batches: those read ahead plus the one
+  being read.
+
+  ## Parameters
+    - state: The connection state that declared the cursor
+    - cursor_id: The cursor ID
+
+  ## Example
+      {:ok, buffered} = EctoLibSql.Native.cursor_buffer_size(state, cursor.ref)
+  """
+  @spec cursor_buffer_size(EctoLibSql.State.t(), String.t()) ::
+          {:ok, non_neg_integer()} | {:error, term()}
+  def cursor_buffer_size(%EctoLibSql.State{conn_id: conn_id} = _state, cursor_id) do
+    case cursor_buffered_rows(conn_id, cursor_id) do
+      count when is_integer(count) -> {:ok, count}
+      {:error, reason} -> {:error, reason}
+    end
+  end
+
   @doc """
   Create a vector from a list of numbers for use in vector columns.
 
@@ -1278,6 +1715,87 @@ defmodule EctoLibSql.Native do
     |> parse_batch_results()
   end
 
+  @doc """
+  Run several read queries in one call and return their result sets in order.
+
+  Each query runs on its own with its own parameters, one after another on the
+  connection, but the whole pipeline costs a single NIF call. Useful for
+  dashboards and reports that issue many small SELECTs per request.
+
+  By default the first failing query stops the pipeline and its zero-based
+  index is returned alongside the error. With `continue_on_error: true`, a
+  failing query's `{:error, reason}` takes its place in the results and the
+  remaining queries still run.
+
+  ## Parameters
+    - state: The connection state
+    - queries: A list of `{sql, args}` or `{sql, args, opts}` tuples
+    - opts: Options applied to every query
+      - `:continue_on_error` - Keep going after a failing query (default: `false`).
+        A query's own opts take precedence.
+
+  ## Example
+      queries = [
+        {"SELECT count(*) FROM users", []},
+        {"SELECT name FROM users WHERE id = ?", [1]},
+        {"SELECT * FROM audit_log ORDER BY at DESC LIMIT ?", [10], continue_on_error: true}
+      ]
+      {:ok, [users, user, log]} = EctoLibSql.Native.query_pipeline(state, queries)
+
+  ## Returns
+    - `{:ok, results}` - One `%EctoLibSql.Result{}` per query, or `{:error, reason}`
+      in place of a query that failed with `continue_on_error` set
+    - `{:error, {index, reason}}` - The query at `index` failed and stopped the pipeline
+    - `{:error, reason}` - The pipeline could not run (e.g. an invalid connection)
+  """
+  @spec query_pipeline(
+          EctoLibSql.State.t(),
+          list({String.t(), list()} | {String.t(), list(), Keyword.t()}),
+          Keyword.t()
+        ) ::
+          {:ok, list(EctoLibSql.Result.t() | {:error, term()})}
+          | {:error, {non_neg_integer(), term()}}
+          | {:error, term()}
+  def query_pipeline(%EctoLibSql.State{conn_id: conn_id} = _state, queries, opts \\ [])
+      when is_list(queries) do
+    continue_on_error = Keyword.get(opts, :continue_on_error, false)
+
+    with {:ok, pipeline} <- build_pipeline(queries, continue_on_error) do
+      case query_pipeline_native(conn_id, pipeline) do
+        results when is_list(results) -> {:ok, Enum.map(results, &parse_pipeline_result/1)}
+        {:error, reason} -> {:error, reason}
+      end
+    end
+  end
+
+  # Normalise pipeline queries to the `{sql, args, continue_on_error}` tuples the NIF expects.
+  defp build_pipeline(queries, continue_on_error) do
+    queries
+    |> Enum.with_index()
+    |> Enum.reduce_while({:ok, []}, fn
+      {{sql, args}, _index}, {:ok, acc} when is_binary(sql) and is_list(args) ->
+        {:cont, {:ok, [{sql, args, continue_on_error} | acc]}}
+
+      {{sql, args, query_opts}, _index}, {:ok, acc}
+      when is_binary(sql) and is_list(args) and is_list(query_opts) ->
+        continue? = Keyword.get(query_opts, :continue_on_error, continue_on_error)
+        {:cont, {:ok, [{sql, args, continue?} | acc]}}
+
+      {other, index}, _acc ->
+        {:halt, {:error, {index, "Invalid pipeline query: #{inspect(other)}"}}}
+    end)
+    |> case do
+      {:ok, pipeline} -> {:ok, Enum.reverse(pipeline)}
+      error -> error
+    end
+  end
+
+  defp parse_pipeline_result({:ok, %{"columns" => columns, "rows" => rows, "num_rows" => num}}) do
+    %EctoLibSql.Result{command: :select, columns: columns, rows: rows, num_rows: num}
+  end
+
+  defp parse_pipeline_result({:error, reason}), do: {:error, reason}
+
   # Parse batch execution results into EctoLibSql.Result structs.
   @spec parse_batch_results(list(map()) | {:error, term()}) ::
           {:ok, list(EctoLibSql.Result.t())} | {:error, term()}
@@ -1333,43 +1851,321 @@ defmodule EctoLibSql.Native do
   end
 
   @doc """
-  Reset the connection to a clean state.
+  Set how the connection behaves when another connection holds the lock it needs.
 
-  This clears any cached state and resets the connection. Useful for:
-  - Connection pooling (ensuring clean state when returning to pool)
-  - Recovering from errors
-  - Clearing any uncommitted transaction state
+  Lets different parts of an app pick different trade-offs on their own
+  connections: background jobs can wait, request handlers can fail fast.
+  The policy replaces any earlier `busy_timeout/2`, and a later
+  `busy_timeout/2` replaces the policy.
 
   ## Parameters
     - state: The connection state
+    - policy: One of
+      - `{:timeout, ms}` - SQLite retries for up to `ms` milliseconds, like `busy_timeout/2`
+      - `:fail_fast` - Fail at once with "database is locked"
+      - `{:backoff, base_ms, max_ms}` - Wait up to `max_ms` for the lock, retrying
+        plain queries after `base_ms` and doubling the delay each time. `base_ms` must
+        be at least 1
 
   ## Example
 
-      :ok = EctoLibSql.Native.reset(state)
+      # Request handlers: report contention straight away
+      :ok = EctoLibSql.Native.set_busy_handler(request_state, :fail_fast)
+
+      # Background jobs: retry after 10, 20, 40, ... ms for up to 5 seconds
+      :ok = EctoLibSql.Native.set_busy_handler(job_state, {:backoff, 10, 5_000})
+
+  ## Notes
+
+  - libsql does not expose SQLite's busy handler callback, so `:backoff` sets
+    SQLite's busy timeout to `max_ms`. Every statement, including prepared
+    statements, batches, cursors, `query_pipeline/3` and statements inside a
+    transaction, waits up to `max_ms` for the lock.
+  - Plain queries outside a transaction that still fail with "database is
+    locked" before `max_ms` have passed are also retried after `base_ms`,
+    doubling the delay each time. Statements inside a transaction are never
+    retried, since retrying there can deadlock.
+
+  ## Returns
+    - `:ok` - The policy is in effect
+    - `{:error, reason}` - If the policy is invalid or the connection is unknown
 
   """
-  def reset(%EctoLibSql.State{conn_id: conn_id} = _state) do
-    reset_connection(conn_id)
+  @spec set_busy_handler(
+          EctoLibSql.State.t(),
+          {:timeout, non_neg_integer()}
+          | :fail_fast
+          | {:backoff, pos_integer(), non_neg_integer()}
+        ) :: :ok | {:error, term()}
+  def set_busy_handler(%EctoLibSql.State{conn_id: conn_id} = _state, policy) do
+    set_busy_handler_native(conn_id, policy)
   end
 
   @doc """
-  Interrupt any ongoing operation on this connection.
+  Set the `secure_delete` mode for the connection.
 
-  Causes the current database operation to abort and return at the earliest
-  opportunity. Useful for:
-  - Cancelling long-running queries
-  - Implementing query timeouts
-  - Graceful shutdown
+  Controls whether SQLite overwrites deleted content so it cannot be recovered
+  from the database file:
+  - `:on` - Overwrite deleted content with zeros
+  - `:fast` - Overwrite only when it needs no extra I/O (freelist pages may keep data)
+  - `:off` - Leave deleted content in place (SQLite default)
+
+  The same setting can be applied at open time with the `:secure_delete` connect option.
 
   ## Parameters
     - state: The connection state
+    - mode: One of `:on`, `:off`, `:fast`
+
+  ## Returns
+    - `:ok` on success
+    - `{:error, reason}` for an unknown mode or on failure
 
   ## Example
 
-      # From another process, cancel a long query
-      :ok = EctoLibSql.Native.interrupt(state)
+      :ok = EctoLibSql.Native.set_secure_delete(state, :on)
 
-  ## Notes
+  """
+  @spec set_secure_delete(EctoLibSql.State.t(), :on | :off | :fast) :: :ok | {:error, term()}
+  def set_secure_delete(%EctoLibSql.State{conn_id: conn_id} = _state, mode)
+      when mode in [:on, :off, :fast] do
+    set_secure_delete_native(conn_id, mode)
+  end
+
+  def set_secure_delete(%EctoLibSql.State{} = _state, mode) do
+    {:error, "Invalid secure_delete mode: #{inspect(mode)}. Use :on, :off, or :fast"}
+  end
+
+  @doc """
+  Read the current `secure_delete` mode of the connection.
+
+  ## Returns
+    - `{:ok, mode}` where mode is `:on`, `:off` or `:fast`
+    - `{:error, reason}` on failure
+
+  ## Example
+
+      {:ok, :on} = EctoLibSql.Native.get_secure_delete(state)
+
+  """
+  @spec get_secure_delete(EctoLibSql.State.t()) :: {:ok, :on | :off | :fast} | {:error, term()}
+  def get_secure_delete(%EctoLibSql.State{conn_id: conn_id} = _state) do
+    case get_secure_delete_native(conn_id) do
+      mode when mode in [:on, :off, :fast] -> {:ok, mode}
+      {:error, _} = error -> error
+    end
+  end
+
+  @doc """
+  Set the maximum number of auxiliary threads SQLite may use for the connection.
+
+  SQLite can hand parts of large sorts (`ORDER BY`, `CREATE INDEX`) to helper
+  threads. `0` disables them. SQLite caps the limit at its compile-time maximum
+  (`SQLITE_MAX_WORKER_THREADS`), so the applied value may be lower than requested.
+
+  The same setting can be applied at open time with the `:threads` connect option.
+
+  ## Parameters
+    - state: The connection state
+    - limit: Non-negative thread limit
+
+  ## Returns
+    - `{:ok, applied}` - The limit SQLite applied
+    - `{:error, reason}` - For a negative limit or on failure
+
+  ## Example
+
+      {:ok, applied} = EctoLibSql.Native.set_threads(state, 4)
+
+  """
+  @spec set_threads(EctoLibSql.State.t(), non_neg_integer()) ::
+          {:ok, non_neg_integer()} | {:error, term()}
+  def set_threads(%EctoLibSql.State{conn_id: conn_id} = _state, limit)
+      when is_integer(limit) and limit >= 0 do
+    case set_threads_native(conn_id, limit) do
+      applied when is_integer(applied) -> {:ok, applied}
+      {:error, _} = error -> error
+    end
+  end
+
+  def set_threads(%EctoLibSql.State{} = _state, limit) do
+    {:error, "Invalid threads value: #{inspect(limit)}. Must be a non-negative integer"}
+  end
+
+  @doc """
+  Run `PRAGMA optimize` on the connection.
+
+  SQLite recommends running this every few hours on long-lived connections and
+  just before closing a connection. It only re-analyses tables whose statistics
+  look stale, so it is usually cheap. To run it automatically on close, connect
+  with `optimize_on_close: true`.
+
+  ## Parameters
+    - state: The connection state
+    - mask: Optional bitmask selecting which optimisations run (see the SQLite
+      `PRAGMA optimize` documentation), or `nil` for SQLite's default
+
+  ## Returns
+    - `:ok` - On success
+    - `{:error, reason}` - For an invalid mask or on failure
+
+  ## Example
+
+      :ok = EctoLibSql.Native.optimize(state)
+
+      # Also check tables this connection has not queried
+      :ok = EctoLibSql.Native.optimize(state, 0x10002)
+
+  """
+  @spec optimize(EctoLibSql.State.t(), non_neg_integer() | nil) :: :ok | {:error, term()}
+  def optimize(state, mask \\ nil)
+
+  def optimize(%EctoLibSql.State{conn_id: conn_id} = _state, mask)
+      when is_nil(mask) or (is_integer(mask) and mask >= 0) do
+    optimize_native(conn_id, mask)
+  end
+
+  def optimize(%EctoLibSql.State{} = _state, _mask) do
+    {:error, "optimize mask must be a non-negative integer or nil"}
+  end
+
+  @doc """
+  Describe a connection.
+
+  ## Parameters
+    - state: The connection state
+
+  ## Example
+
+      {:ok, %{mode: :local, tag: "tenant-42", read_only: false}} =
+        EctoLibSql.Native.connection_info(state)
+
+  ## Returns
+    - `{:ok, info}` - A map with `:conn_id`, `:mode`, `:tag` (`nil` unless the
+      connection was opened with `:tag`), `:read_only` and `:auto_reconnect`
+    - `{:error, reason}` - If the connection is not open
+
+  """
+  @spec connection_info(EctoLibSql.State.t()) :: {:ok, map()} | {:error, term()}
+  def connection_info(%EctoLibSql.State{conn_id: conn_id} = _state) do
+    case connection_info_native(conn_id) do
+      {mode, tag, read_only, auto_reconnect} ->
+        {:ok,
+         %{
+           conn_id: conn_id,
+           mode: mode,
+           tag: tag,
+           read_only: read_only,
+           auto_reconnect: auto_reconnect
+         }}
+
+      {:error, reason} ->
+        {:error, reason}
+    end
+  end
+
+  @doc """
+  List the open connections of this node.
+
+  Pass `tag:` to list only connections opened with that `:tag`, for example to
+  attribute pool usage to one tenant.
+
+  ## Parameters
+    - opts: `tag: tag` to filter by tag
+
+  ## Example
+
+      {:ok, [%{conn_id: _, tag: "tenant-42"}]} =
+        EctoLibSql.Native.list_connections(tag: "tenant-42")
+
+  ## Returns
+    - `{:ok, connections}` - Maps with `:conn_id` and `:tag`, sorted by `:conn_id`
+    - `{:error, reason}` - If the connection registry could not be read
+
+  """
+  @spec list_connections(keyword()) ::
+          {:ok, [%{conn_id: String.t(), tag: String.t() | nil}]} | {:error, term()}
+  def list_connections(opts \\ []) do
+    case list_connections_native(Keyword.get(opts, :tag)) do
+      connections when is_list(connections) ->
+        {:ok, Enum.map(connections, fn {conn_id, tag} -> %{conn_id: conn_id, tag: tag} end)}
+
+      {:error, reason} ->
+        {:error, reason}
+    end
+  end
+
+  @doc """
+  Check that an encryption key opens an existing encrypted database.
+
+  Opens the file read-only with the key, reads `sqlite_schema` and closes it
+  again. Use it as a cheap preflight before adding connections to a pool, so a
+  wrong key fails fast instead of on the first real query. The file is never
+  created.
+
+  ## Parameters
+    - path: Path to the encrypted local database file
+    - key: The encryption key to try
+    - cipher: The cipher the database uses; only `:aes256cbc` (the cipher used
+      by the `:encryption_key` connect option) is supported
+
+  ## Example
+
+      case EctoLibSql.Native.verify_encryption_key("secure.db", key) do
+        :ok -> EctoLibSql.connect(database: "secure.db", encryption_key: key)
+        {:error, :invalid_encryption_key} -> {:error, :bad_key}
+      end
+
+  ## Returns
+    - `:ok` - The key decrypts the database
+    - `{:error, :invalid_encryption_key}` - The key is wrong, or the file is not encrypted
+    - `{:error, reason}` - The file is missing or cannot be read for another reason
+
+  """
+  @spec verify_encryption_key(String.t(), String.t(), :aes256cbc) ::
+          :ok | {:error, :invalid_encryption_key | term()}
+  def verify_encryption_key(path, key, cipher \\ :aes256cbc)
+      when is_binary(path) and is_binary(key) and is_atom(cipher) do
+    verify_encryption_key_native(path, key, cipher)
+  end
+
+  @doc """
+  Reset the connection to a clean state.
+
+  This clears any cached state and resets the connection. Useful for:
+  - Connection pooling (ensuring clean state when returning to pool)
+  - Recovering from errors
+  - Clearing any uncommitted transaction state
+
+  ## Parameters
+    - state: The connection state
+
+  ## Example
+
+      :ok = EctoLibSql.Native.reset(state)
+
+  """
+  def reset(%EctoLibSql.State{conn_id: conn_id} = _state) do
+    reset_connection(conn_id)
+  end
+
+  @doc """
+  Interrupt any ongoing operation on this connection.
+
+  Causes the current database operation to abort and return at the earliest
+  opportunity. Useful for:
+  - Cancelling long-running queries
+  - Implementing query timeouts
+  - Graceful shutdown
+
+  ## Parameters
+    - state: The connection state
+
+  ## Example
+
+      # From another process, cancel a long query
+      :ok = EctoLibSql.Native.interrupt(state)
+
+  ## Notes
 
   - This is safe to call from any thread/process
   - The interrupted operation will return an error
@@ -1379,6 +2175,52 @@ defmodule EctoLibSql.Native do
     interrupt_connection(conn_id)
   end
 
+  @doc """
+  Create a handle for cancelling a single call.
+
+  Pass the handle as the `:cancellable` option of `EctoLibSql.handle_execute/4`
+  (or `Repo.query/3`) and call `cancel/1` with it from another process to abort
+  just that call, leaving other work on the connection alone. The handle is
+  bound to a connection only when its call starts, so it can be created before
+  a pooled connection is checked out. A handle that is never passed to a call
+  expires after an hour. See the "Cancellation" section of
+  `EctoLibSql.handle_execute/4`.
+
+  ## Returns
+    - `{:ok, handle}` - An opaque, single-use operation handle
+
+  """
+  @spec new_operation() :: {:ok, String.t()} | {:error, term()}
+  def new_operation do
+    case new_operation_native() do
+      handle when is_binary(handle) -> {:ok, handle}
+      {:error, reason} -> {:error, reason}
+    end
+  end
+
+  @doc """
+  Cancel the call running (or about to run) with an operation handle.
+
+  A call that has not started yet will not run; one whose statement is running
+  is interrupted. Either way it returns an `EctoLibSql.Error` with the message
+  `"Operation cancelled"`.
+
+  ## Example
+
+      {:ok, op} = EctoLibSql.Native.new_operation()
+      task = Task.async(fn -> EctoLibSql.handle_execute(sql, [], [cancellable: op], state) end)
+      :ok = EctoLibSql.Native.cancel(op)
+
+  ## Returns
+    - `:ok` - The call was cancelled
+    - `{:error, reason}` - If the handle is unknown or its call has already finished
+
+  """
+  @spec cancel(String.t()) :: :ok | {:error, term()}
+  def cancel(handle) when is_binary(handle) do
+    cancel_operation_native(handle)
+  end
+
   @doc """
   Enable or disable loading of SQLite extensions.
 
@@ -1502,183 +2344,1113 @@ defmodule EctoLibSql.Native do
         end
       end
 
-  2. **Database triggers** - Use SQLite triggers to log changes to a separate table:
+  2. **Database triggers** - Use SQLite triggers to log changes to a separate table:
+
+      CREATE TRIGGER users_audit_insert AFTER INSERT ON users
+      BEGIN
+        INSERT INTO audit_log (action, table_name, row_id, timestamp)
+        VALUES ('insert', 'users', NEW.id, datetime('now'));
+      END;
+
+  3. **Polling-based CDC** - Periodically query for changes using timestamps or version columns
+
+  4. **Phoenix.Tracker** - Track state changes at the application level
+
+  ## Returns
+    - `:unsupported` - Always returns unsupported
+
+  """
+  def add_update_hook(%EctoLibSql.State{} = state, pid \\ self()) do
+    set_update_hook(state.conn_id, pid)
+  end
+
+  @doc """
+  Remove the update hook from a connection.
+
+  **NOT SUPPORTED** - Update hooks are not currently implemented.
+
+  ## Returns
+    - `:unsupported` - Always returns unsupported
+
+  """
+  def remove_update_hook(%EctoLibSql.State{conn_id: conn_id} = _state) do
+    clear_update_hook(conn_id)
+  end
+
+  @doc """
+  Install an authorizer hook for row-level security.
+
+  **NOT SUPPORTED** - Authorizer hooks require synchronous bidirectional communication
+  between Rust and Elixir, which is not feasible with Rustler's threading model.
+
+  ## Why Not Supported
+
+  SQLite's authorizer callback is called synchronously during query compilation and expects
+  an immediate response (Allow/Deny/Ignore). This would require:
+  1. Sending a message from Rust to Elixir
+  2. Blocking the Rust thread waiting for a response
+  3. Receiving the response from Elixir
+
+  This pattern is not safe with Rustler because:
+  - The callback runs on a SQLite thread (potentially holding locks)
+  - Blocking on Erlang scheduler threads can cause deadlocks
+  - No safe way to do synchronous Rust→Elixir→Rust calls
+
+  ## Alternatives
+
+  For row-level security and access control, consider:
+
+  1. **Application-level authorization** - Check permissions in Elixir before queries:
+
+      defmodule MyApp.Auth do
+        def can_access?(user, table, action) do
+          # Check user permissions
+        end
+      end
+
+      def get_user(id, current_user) do
+        if MyApp.Auth.can_access?(current_user, "users", :read) do
+          Repo.get(User, id)
+        else
+          {:error, :unauthorized}
+        end
+      end
+
+  2. **Database views** - Create views with WHERE clauses for different user levels:
+
+      CREATE VIEW user_visible_posts AS
+      SELECT * FROM posts WHERE user_id = current_user_id();
+
+  3. **Query rewriting** - Modify queries in Elixir to include authorization constraints:
+
+      defmodule MyApp.Repo do
+        def all(queryable, current_user) do
+          queryable
+          |> apply_tenant_filter(current_user)
+          |> Ecto.Repo.all()
+        end
+      end
+
+  4. **Connection-level restrictions** - Use different database connections with different privileges
+
+  ## Returns
+    - `:unsupported` - Always returns unsupported
+
+  """
+  def add_authorizer(%EctoLibSql.State{conn_id: conn_id} = _state, pid \\ self()) do
+    set_authorizer(conn_id, pid)
+  end
+
+  @doc """
+  Execute multiple SQL statements from a semicolon-separated string.
+
+  Uses LibSQL's native batch execution for optimal performance. This is more
+  efficient than executing statements one-by-one as it reduces round-trips
+  and allows LibSQL to optimize the execution.
+
+  Each statement is executed independently. If one fails, others may still
+  complete.
+
+  ## Parameters
+    - state: The connection state
+    - sql: Semicolon-separated SQL statements
+
+  ## Example
+
+      sql = \"""
+      CREATE TABLE IF NOT EXISTS users (id INTEGER PRIMARY KEY, name TEXT);
+      INSERT INTO users (name) VALUES ('Alice');
+      INSERT INTO users (name) VALUES ('Bob');
+      SELECT * FROM users;
+      \"""
+
+      {:ok, results} = EctoLibSql.Native.execute_batch_sql(state, sql)
+
+  ## Returns
+
+  A list of results, one for each statement. Each result is either:
+  - A map with columns/rows for SELECT statements
+  - `nil` for statements that don't return data
+
+  """
+  def execute_batch_sql(%EctoLibSql.State{conn_id: conn_id} = _state, sql)
+      when is_binary(sql) do
+    case execute_batch_native(conn_id, sql) do
+      results when is_list(results) ->
+        {:ok, results}
+
+      {:error, message} ->
+        {:error, message}
+    end
+  end
+
+  @doc """
+  Execute multiple SQL statements atomically in a transaction.
+
+  Uses LibSQL's native transactional batch execution. All statements execute
+  within a single transaction - if any statement fails, all changes are
+  rolled back.
+
+  ## Parameters
+    - state: The connection state
+    - sql: Semicolon-separated SQL statements
+
+  ## Example
+
+      sql = \"""
+      UPDATE accounts SET balance = balance - 100 WHERE id = 1;
+      UPDATE accounts SET balance = balance + 100 WHERE id = 2;
+      INSERT INTO transfers (from_id, to_id, amount) VALUES (1, 2, 100);
+      \"""
+
+      {:ok, results} = EctoLibSql.Native.execute_transactional_batch_sql(state, sql)
+
+  ## Notes
+
+  - All statements succeed or all are rolled back
+  - More efficient than manual transaction with multiple queries
+  - Ideal for migrations, data loading, and multi-statement operations
+
+  """
+  def execute_transactional_batch_sql(%EctoLibSql.State{conn_id: conn_id} = _state, sql)
+      when is_binary(sql) do
+    case execute_transactional_batch_native(conn_id, sql) do
+      results when is_list(results) ->
+        {:ok, results}
+
+      {:error, message} ->
+        {:error, message}
+    end
+  end
+
+  @doc """
+  Drop several tables in a single transaction.
+
+  Each name is quoted as an identifier, so names with spaces, keywords or
+  embedded quotes are handled safely. Intended for test teardown.
+
+  ## Parameters
+    - state: The connection state
+    - names: Table names (strings or atoms)
+    - if_exists: Use `DROP TABLE IF EXISTS` (default `true`). When `false`,
+      a missing table returns an error and no tables are dropped
+
+  ## Example
+
+      {:ok, 2} = EctoLibSql.Native.drop_tables(state, ["users", :posts, "never_created"])
+
+  ## Returns
+    - `{:ok, count}` - How many of the tables existed and were dropped
+    - `{:error, reason}` - If a drop failed; all drops are rolled back
+
+  """
+  @spec drop_tables(EctoLibSql.State.t(), [String.t() | atom()], boolean()) ::
+          {:ok, non_neg_integer()} | {:error, term()}
+  def drop_tables(%EctoLibSql.State{conn_id: conn_id} = _state, names, if_exists \\ true)
+      when is_list(names) and is_boolean(if_exists) do
+    names = Enum.map(names, &to_string/1)
+
+    case drop_tables_native(conn_id, names, if_exists) do
+      count when is_integer(count) -> {:ok, count}
+      {:error, reason} -> {:error, reason}
+    end
+  end
+
+  @doc """
+  Delete every row from a table.
+
+  Runs `DELETE FROM "table"` without a WHERE clause, so SQLite's truncate
+  optimisation applies. With `reset_sequence` set, an AUTOINCREMENT table's
+  counter in `sqlite_sequence` is cleared too, so new rows start at id 1.
+  Both steps run in one transaction.
+
+  ## Parameters
+    - state: The connection state
+    - table: Table name (string or atom); quoted as an identifier
+    - reset_sequence: Also reset the AUTOINCREMENT counter (default `false`)
+
+  ## Example
+
+      {:ok, 120} = EctoLibSql.Native.truncate(state, "cache_entries")
+      {:ok, _deleted} = EctoLibSql.Native.truncate(state, :users, true)
+
+  ## Returns
+    - `{:ok, deleted}` - The number of rows removed
+    - `{:error, reason}` - If the table does not exist or the delete failed
+
+  """
+  @spec truncate(EctoLibSql.State.t(), String.t() | atom(), boolean()) ::
+          {:ok, non_neg_integer()} | {:error, term()}
+  def truncate(%EctoLibSql.State{conn_id: conn_id} = _state, table, reset_sequence \\ false)
+      when (is_binary(table) or is_atom(table)) and is_boolean(reset_sequence) do
+    case truncate_native(conn_id, to_string(table), reset_sequence) do
+      deleted when is_integer(deleted) -> {:ok, deleted}
+      {:error, reason} -> {:error, reason}
+    end
+  end
+
+  @doc """
+  Reset a table's AUTOINCREMENT counter so the next id is `value + 1`.
+
+  Sets the table's row in `sqlite_sequence`, creating it if needed. SQLite
+  never reuses an id at or below the largest rowid still in the table, so
+  reset after deleting rows (or use `truncate/3` with `reset_sequence`).
+
+  ## Parameters
+    - state: The connection state
+    - table: Table name (string or atom)
+    - value: New sequence value (default `0`, so the next id is 1)
+
+  ## Example
+
+      :ok = EctoLibSql.Native.reset_sequence(state, "events")
+      :ok = EctoLibSql.Native.reset_sequence(state, :events, 1000)
+
+  ## Returns
+    - `:ok` - The sequence was updated
+    - `{:error, reason}` - If the table does not exist or does not use AUTOINCREMENT
+
+  """
+  @spec reset_sequence(EctoLibSql.State.t(), String.t() | atom(), non_neg_integer()) ::
+          :ok | {:error, term()}
+  def reset_sequence(%EctoLibSql.State{conn_id: conn_id} = _state, table, value \\ 0)
+      when (is_binary(table) or is_atom(table)) and is_integer(value) and value >= 0 do
+    reset_sequence_native(conn_id, to_string(table), value)
+  end
+
+  @doc """
+  Rebuild indexes with `REINDEX`.
+
+  With no target every index in the database is rebuilt. A target names an
+  index, a table (all of its indexes are rebuilt) or a collation (every index
+  using it is rebuilt); it is quoted as an identifier, so it is never run as
+  SQL. Useful after changing a custom collation or to repair a corrupt index.
+
+  ## Parameters
+    - state: The connection state
+    - target: Index, table or collation name (string or atom), or `nil` for all
+
+  ## Example
+
+      :ok = EctoLibSql.Native.reindex(state)
+      :ok = EctoLibSql.Native.reindex(state, "idx_users_email")
+      :ok = EctoLibSql.Native.reindex(state, :users)
+
+  ## Returns
+    - `:ok` - The indexes were rebuilt
+    - `{:error, reason}` - If the target does not exist or the rebuild failed
+
+  """
+  @spec reindex(EctoLibSql.State.t(), String.t() | atom() | nil) :: :ok | {:error, term()}
+  def reindex(%EctoLibSql.State{conn_id: conn_id} = _state, target \\ nil) do
+    case target do
+      nil -> reindex_native(conn_id, nil)
+      name when is_binary(name) or is_atom(name) -> reindex_native(conn_id, to_string(name))
+    end
+  end
+
+  @doc """
+  Copy the rows of a SELECT into another table.
+
+  Runs `INSERT INTO "dest_table" SELECT ...` and reports how many rows were
+  inserted. The SELECT must produce columns in the destination's column order.
+
+  With `batch_size`, each batch is committed in its own transaction, which keeps
+  transactions small for large copies. Pass a `:key` column, one of the SELECT's
+  output columns and ideally indexed, to batch by key ranges: each batch starts
+  after the last key copied, so the copy stays linear in the number of rows.
+  Rows whose key is NULL are not copied.
+
+  Without `:key`, the SELECT is paged with `LIMIT`/`OFFSET`. Every batch re-runs
+  the SELECT and skips the rows already copied, so the cost grows quadratically:
+  10,000 rows in batches of 100 read about 500,000 rows. Add an `ORDER BY` to the
+  SELECT so pages are deterministic.
+
+  Don't select from the destination table. If a batch fails it is rolled back,
+  but earlier batches stay committed. Because of that, a batched copy cannot run
+  while the connection has a transaction open; use a single statement
+  (`batch_size: nil`) there instead.
+
+  ## Parameters
+    - state: The connection state
+    - dest_table: Destination table name (string or atom); quoted as an identifier
+    - select_sql: The SELECT statement to copy from
+    - params: Positional values for the SELECT's placeholders
+    - batch_size: Rows per transaction, or `nil` for a single statement (default)
+    - opts: Options
+      - `:key` - Column to batch by (string or atom). Requires `batch_size`
+
+  ## Example
+
+      {:ok, 10_000} =
+        EctoLibSql.Native.copy_into(
+          state,
+          "events_archive",
+          "SELECT * FROM events WHERE created_at < ?",
+          ["2024-01-01"],
+          1_000,
+          key: :id
+        )
+
+  ## Returns
+    - `{:ok, inserted}` - Total number of rows inserted
+    - `{:error, reason}` - If the copy failed
+
+  """
+  @spec copy_into(
+          EctoLibSql.State.t(),
+          String.t() | atom(),
+          String.t(),
+          list(),
+          pos_integer() | nil,
+          Keyword.t()
+        ) :: {:ok, non_neg_integer()} | {:error, term()}
+  def copy_into(
+        %EctoLibSql.State{conn_id: conn_id} = _state,
+        dest_table,
+        select_sql,
+        params \\ [],
+        batch_size \\ nil,
+        opts \\ []
+      )
+      when (is_binary(dest_table) or is_atom(dest_table)) and is_binary(select_sql) and
+             is_list(params) and
+             (is_nil(batch_size) or (is_integer(batch_size) and batch_size > 0)) and
+             is_list(opts) do
+    key =
+      case Keyword.get(opts, :key) do
+        nil -> nil
+        key -> to_string(key)
+      end
+
+    case copy_into_native(conn_id, to_string(dest_table), select_sql, params, batch_size, key) do
+      inserted when is_integer(inserted) -> {:ok, inserted}
+      {:error, reason} -> {:error, reason}
+    end
+  end
+
+  @doc """
+  Write every row of a table to a file as newline-delimited JSON (NDJSON).
+
+  Each line is a JSON object keyed by column name. Blobs are written as
+  base64 strings and NULLs as `null`. Rows are streamed to the file as they
+  are read, and an existing file at `dest_path` is overwritten.
+
+  ## Parameters
+    - state: The connection state
+    - table: Table name (string or atom); quoted as an identifier
+    - dest_path: Path of the file to write
+
+  ## Example
+
+      {:ok, 3} = EctoLibSql.Native.dump_table_ndjson(state, "users", "/tmp/users.ndjson")
+
+  ## Returns
+    - `{:ok, written}` - Number of rows written
+    - `{:error, reason}` - If the table could not be read or the file written
+
+  """
+  @spec dump_table_ndjson(EctoLibSql.State.t(), String.t() | atom(), Path.t()) ::
+          {:ok, non_neg_integer()} | {:error, term()}
+  def dump_table_ndjson(%EctoLibSql.State{conn_id: conn_id} = _state, table, dest_path)
+      when (is_binary(table) or is_atom(table)) and is_binary(dest_path) do
+    case dump_table_ndjson_native(conn_id, to_string(table), dest_path) do
+      written when is_integer(written) -> {:ok, written}
+      {:error, reason} -> {:error, reason}
+    end
+  end
+
+  @doc """
+  Run a query and save its result as a table in a new SQLite database file.
+
+  Useful for shipping a read-only snapshot of a query, such as a join used for
+  edge caching, as a standalone file. The destination must not exist yet. Each
+  column's type is inferred from its first non-NULL value (`INTEGER`, `REAL`,
+  `TEXT` or `BLOB`); a column that is NULL in every row gets no declared type.
+
+  The whole result is held in memory while the file is written, and the rows
+  are inserted in one transaction. If anything fails the new file is removed.
+  Columns with the same name, as in `SELECT * FROM a JOIN b`, must be aliased
+  apart because they become the table's column names.
+
+  ## Parameters
+    - state: The connection state to run the query on
+    - sql: The query to materialize
+    - params: Positional values for the query's placeholders
+    - dest_path: Path of the database file to create
+    - table_name: Name of the table to create (string or atom); quoted as an identifier
+
+  ## Example
+
+      {:ok, 42} =
+        EctoLibSql.Native.materialize_to_db(
+          state,
+          "SELECT p.id, p.title, u.name AS author FROM posts p JOIN users u ON u.id = p.user_id",
+          [],
+          "priv/cache/posts.db",
+          "posts"
+        )
+
+  ## Returns
+    - `{:ok, written}` - Number of rows written
+    - `{:error, reason}` - If the query fails, the destination exists, or the
+      file cannot be written
+
+  """
+  @spec materialize_to_db(
+          EctoLibSql.State.t(),
+          String.t(),
+          list(),
+          Path.t(),
+          String.t() | atom()
+        ) :: {:ok, non_neg_integer()} | {:error, term()}
+  def materialize_to_db(
+        %EctoLibSql.State{conn_id: conn_id} = _state,
+        sql,
+        params,
+        dest_path,
+        table_name
+      )
+      when is_binary(sql) and is_list(params) and is_binary(dest_path) and
+             (is_binary(table_name) or is_atom(table_name)) do
+    case materialize_to_db_native(conn_id, sql, params, dest_path, to_string(table_name)) do
+      written when is_integer(written) -> {:ok, written}
+      {:error, reason} -> {:error, reason}
+    end
+  end
+
+  # Default size limit for insert_blob_from_file/5, which holds the file in native memory
+  @blob_file_max_bytes 100 * 1024 * 1024
+
+  @doc """
+  Execute a statement with one parameter bound to the contents of a file.
+
+  Put the `:file` atom in `params` where the blob belongs. The file is read by
+  the native code and bound as a blob, so large files already on disk never
+  become an Elixir binary. libsql has no incremental blob I/O, so the bytes are
+  still held in native memory once while the statement runs; files over
+  `:max_bytes` are rejected before they are read.
+
+  ## Parameters
+    - state: The connection state
+    - sql: The statement to run, usually an `INSERT` or `UPDATE`
+    - params: Positional parameters containing exactly one `:file` placeholder
+    - file_path: Path of the file to bind
+    - opts: Options
+      - `:max_bytes` - Largest file accepted, in bytes (default: 100 MiB)
+
+  ## Example
+
+      {:ok, 1} =
+        EctoLibSql.Native.insert_blob_from_file(
+          state,
+          "INSERT INTO attachments (name, data) VALUES (?, ?)",
+          ["report.pdf", :file],
+          "/var/uploads/report.pdf"
+        )
+
+  ## Returns
+    - `{:ok, affected}` - Number of rows affected
+    - `{:error, reason}` - If the file cannot be read or is over `:max_bytes`,
+      the placeholder is missing or repeated, or the statement fails
+
+  """
+  @spec insert_blob_from_file(EctoLibSql.State.t(), String.t(), list(), Path.t(), Keyword.t()) ::
+          {:ok, non_neg_integer()} | {:error, term()}
+  def insert_blob_from_file(
+        %EctoLibSql.State{conn_id: conn_id} = _state,
+        sql,
+        params,
+        file_path,
+        opts \\ []
+      )
+      when is_binary(sql) and is_list(params) and is_binary(file_path) and is_list(opts) do
+    max_bytes = Keyword.get(opts, :max_bytes, @blob_file_max_bytes)
+
+    case execute_blob_from_file_native(conn_id, sql, params, file_path, max_bytes) do
+      affected when is_integer(affected) -> {:ok, affected}
+      {:error, reason} -> {:error, reason}
+    end
+  end
+
+  @doc """
+  Count the rows of a table, optionally filtered by a WHERE clause.
+
+  Runs `SELECT count(*) FROM "table" [WHERE ...]` and returns the integer
+  directly. The table name is quoted as an identifier; the WHERE clause is
+  inserted as-is, so pass values through `?` placeholders rather than
+  interpolating them.
+
+  ## Parameters
+    - state: The connection state
+    - table: Table name (string or atom)
+    - where: SQL condition without the `WHERE` keyword, or `nil` for all rows
+    - params: Positional values for the condition's placeholders
+
+  ## Example
+
+      {:ok, 42} = EctoLibSql.Native.count(state, "users")
+      {:ok, 3} = EctoLibSql.Native.count(state, :users, "age > ? AND active = ?", [30, 1])
+
+  ## Returns
+    - `{:ok, count}` - Number of matching rows
+    - `{:error, reason}` - If the table name is invalid or the query fails
+
+  """
+  @spec count(EctoLibSql.State.t(), String.t() | atom(), String.t() | nil, list()) ::
+          {:ok, non_neg_integer()} | {:error, term()}
+  def count(%EctoLibSql.State{conn_id: conn_id} = _state, table, where \\ nil, params \\ [])
+      when (is_binary(table) or is_atom(table)) and (is_binary(where) or is_nil(where)) and
+             is_list(params) do
+    case count_native(conn_id, to_string(table), where, params) do
+      count when is_integer(count) -> {:ok, count}
+      {:error, reason} -> {:error, reason}
+    end
+  end
+
+  @doc """
+  Run a query and reduce one column of its rows with a built-in reducer.
+
+  The rows are folded in Rust as SQLite produces them, so only the result is
+  sent to Elixir. Use it for aggregations that are awkward to express in SQL, or
+  to avoid materialising a large result just to combine one column. NULLs are
+  skipped, as in the SQL aggregates.
+
+  Reducers:
+    - `:sum` - Integer sum, or a float once any real is seen; errors on text,
+      blobs or integer overflow
+    - `:min`, `:max` - Smallest or largest value in SQLite's sort order
+      (numbers, then text, then blobs)
+    - `:distinct` - Distinct values in order of first appearance; `1` and
+      `1.0` count as different values
+
+  ## Parameters
+    - state: The connection state
+    - sql: The query whose rows are reduced
+    - params: Positional values for the query's placeholders
+    - reducer: `{op, column}`, where `column` is a column name (matched
+      case-insensitively) or zero-based index
+
+  ## Example
+
+      {:ok, 1234} =
+        EctoLibSql.Native.query_reduce(state, "SELECT amount FROM orders", [], {:sum, "amount"})
+
+      {:ok, ["eu", "us"]} =
+        EctoLibSql.Native.query_reduce(
+          state,
+          "SELECT region, amount FROM orders WHERE amount > ?",
+          [100],
+          {:distinct, 0}
+        )
+
+  ## Returns
+    - `{:ok, value}` - The reduced value, `nil` if there were no non-NULL values
+    - `{:ok, values}` - For `:distinct`, the list of distinct values
+    - `{:error, reason}` - For an unknown reducer or column, or if the query fails
+
+  """
+  @spec query_reduce(
+          EctoLibSql.State.t(),
+          String.t(),
+          list(),
+          {:sum | :min | :max | :distinct, String.t() | non_neg_integer()}
+        ) :: {:ok, term()} | {:error, term()}
+  def query_reduce(%EctoLibSql.State{conn_id: conn_id} = _state, sql, params, {op, column})
+      when is_binary(sql) and is_list(params) and op in [:sum, :min, :max, :distinct] and
+             (is_binary(column) or (is_integer(column) and column >= 0)) do
+    case query_reduce_native(conn_id, sql, params, op, column) do
+      {:error, reason} -> {:error, reason}
+      value -> {:ok, value}
+    end
+  end
+
+  def query_reduce(%EctoLibSql.State{} = _state, _sql, _params, reducer) do
+    {:error, "Invalid reducer: #{inspect(reducer)}. Use {:sum | :min | :max | :distinct, column}"}
+  end
+
+  @doc """
+  Fetch one page of a query using keyset pagination.
+
+  Rather than `OFFSET`, which rescans every skipped row, each page seeks past
+  the last key of the previous one:
+
+      SELECT * FROM (base_sql) WHERE order_col > ? ORDER BY order_col LIMIT limit
+
+  `order_col` must appear in the base query's result and hold unique, non-NULL
+  values (typically the primary key). Pass `nil` as `last_key` for the first
+  page, then the returned `next_key` for each following page.
+
+  ## Parameters
+    - state: The connection state
+    - base_sql: Query to paginate, without ORDER BY or LIMIT
+    - order_col: Column to order and seek by (string or atom)
+    - last_key: Key of the last row already seen, or `nil`
+    - limit: Page size
+    - params: Positional values for the base query's placeholders
+
+  ## Example
+
+      {:ok, page, next_key} =
+        EctoLibSql.Native.keyset_page(state, "SELECT id, name FROM users", "id", nil, 100)
+
+      {:ok, page2, _next_key} =
+        EctoLibSql.Native.keyset_page(state, "SELECT id, name FROM users", "id", next_key, 100)
+
+  ## Returns
+    - `{:ok, result, next_key}` - The page as an `EctoLibSql.Result` and the key to pass
+      for the next page, or `nil` when the page was short and nothing is left
+    - `{:error, reason}` - If the arguments are invalid or the query fails
+
+  """
+  @spec keyset_page(
+          EctoLibSql.State.t(),
+          String.t(),
+          String.t() | atom(),
+          term(),
+          pos_integer(),
+          list()
+        ) :: {:ok, EctoLibSql.Result.t(), term()} | {:error, term()}
+  def keyset_page(
+        %EctoLibSql.State{conn_id: conn_id} = _state,
+        base_sql,
+        order_col,
+        last_key,
+        limit,
+        params \\ []
+      )
+      when is_binary(base_sql) and (is_binary(order_col) or is_atom(order_col)) and
+             is_integer(limit) and limit > 0 and is_list(params) do
+    case keyset_page_native(conn_id, base_sql, to_string(order_col), last_key, limit, params) do
+      {%{"columns" => columns, "rows" => rows, "num_rows" => num_rows}, next_key} ->
+        result = %EctoLibSql.Result{
+          command: :select,
+          columns: columns,
+          rows: rows,
+          num_rows: num_rows
+        }
+
+        {:ok, result, next_key}
+
+      {:error, reason} ->
+        {:error, reason}
+    end
+  end
+
+  @doc """
+  Run a query that must return exactly one row.
+
+  Useful for lookups by primary key or unique column, where anything other
+  than a single row indicates a bug in the SQL or the data.
+
+  ## Parameters
+    - state: The connection state (inside or outside a transaction)
+    - sql: The SQL query
+    - params: Query parameters (positional list or named map)
+
+  ## Example
+
+      {:ok, [1, "Alice"]} =
+        EctoLibSql.Native.query_one(state, "SELECT id, name FROM users WHERE id = ?", [1])
+
+  ## Returns
+    - `{:ok, row}` - The single row as a list of values
+    - `{:error, {:no_rows, %{expected: 1, actual: 0}}}` - The query matched nothing
+    - `{:error, {:too_many_rows, %{expected: 1, actual: n}}}` - The query matched `n` rows
+    - `{:error, reason}` - If the query itself failed
+
+  """
+  @spec query_one(EctoLibSql.State.t(), String.t(), list() | map()) ::
+          {:ok, list()} | {:error, term()}
+  def query_one(%EctoLibSql.State{} = state, sql, params \\ []) when is_binary(sql) do
+    with {:ok, result} <- run_typed_query(state, sql, params) do
+      single_row(result)
+    end
+  end
+
+  @doc """
+  Run a query that must return exactly one row with exactly one column.
+
+  Convenient for aggregates and single-value lookups such as
+  `SELECT max(id) FROM users`. Row count is checked before column count.
 
-      CREATE TRIGGER users_audit_insert AFTER INSERT ON users
-      BEGIN
-        INSERT INTO audit_log (action, table_name, row_id, timestamp)
-        VALUES ('insert', 'users', NEW.id, datetime('now'));
-      END;
+  ## Parameters
+    - state: The connection state (inside or outside a transaction)
+    - sql: The SQL query
+    - params: Query parameters (positional list or named map)
 
-  3. **Polling-based CDC** - Periodically query for changes using timestamps or version columns
+  ## Example
 
-  4. **Phoenix.Tracker** - Track state changes at the application level
+      {:ok, "Alice"} =
+        EctoLibSql.Native.query_scalar(state, "SELECT name FROM users WHERE id = ?", [1])
 
   ## Returns
-    - `:unsupported` - Always returns unsupported
+    - `{:ok, value}` - The single value
+    - `{:error, {:no_rows, %{expected: 1, actual: 0}}}` - The query matched nothing
+    - `{:error, {:too_many_rows, %{expected: 1, actual: n}}}` - The query matched `n` rows
+    - `{:error, {:too_many_columns, %{expected: 1, actual: n}}}` - The row has `n` columns
+    - `{:error, reason}` - If the query itself failed
 
   """
-  def add_update_hook(%EctoLibSql.State{} = state, pid \\ self()) do
-    set_update_hook(state.conn_id, pid)
+  @spec query_scalar(EctoLibSql.State.t(), String.t(), list() | map()) ::
+          {:ok, term()} | {:error, term()}
+  def query_scalar(%EctoLibSql.State{} = state, sql, params \\ []) when is_binary(sql) do
+    with {:ok, result} <- run_typed_query(state, sql, params),
+         {:ok, row} <- single_row(result) do
+      case row do
+        [value] -> {:ok, value}
+        columns -> {:error, {:too_many_columns, %{expected: 1, actual: length(columns)}}}
+      end
+    end
   end
 
   @doc """
-  Remove the update hook from a connection.
+  Run a bulk write and return the primary keys of the affected rows.
 
-  **NOT SUPPORTED** - Update hooks are not currently implemented.
+  Intended for cache invalidation after an `UPDATE` (or `DELETE`) that touches
+  many rows. SQLite reports the keys through `RETURNING`, so the statement
+  **must** end with a `RETURNING` clause naming the key column(s), e.g.
+  `RETURNING id`. Keys are returned in the order SQLite produced the rows.
+  The clause is detected the same way the adapter routes statements, so
+  `returning` inside a string literal, quoted name or comment does not count.
+
+  ## Parameters
+    - state: The connection state (inside or outside a transaction)
+    - sql: The write statement, including `RETURNING <pk>`
+    - params: Query parameters (positional list or named map)
+
+  ## Example
+
+      {:ok, [3, 7, 9]} =
+        EctoLibSql.Native.affected_keys(
+          state,
+          "UPDATE users SET active = 0 WHERE last_seen < ? RETURNING id",
+          [cutoff]
+        )
 
   ## Returns
-    - `:unsupported` - Always returns unsupported
+    - `{:ok, keys}` - One value per row, or a list of values per row when
+      `RETURNING` names several columns (composite keys)
+    - `{:error, :missing_returning}` - The statement has no `RETURNING` clause
+    - `{:error, reason}` - If the query itself failed
 
   """
-  def remove_update_hook(%EctoLibSql.State{conn_id: conn_id} = _state) do
-    clear_update_hook(conn_id)
+  @spec affected_keys(EctoLibSql.State.t(), String.t(), list() | map()) ::
+          {:ok, list()} | {:error, term()}
+  def affected_keys(%EctoLibSql.State{} = state, sql, params \\ []) when is_binary(sql) do
+    if should_use_query_path(sql) do
+      with {:ok, %EctoLibSql.Result{rows: rows}} <- run_typed_query(state, sql, params) do
+        {:ok, Enum.map(rows || [], &row_key/1)}
+      end
+    else
+      {:error, :missing_returning}
+    end
+  end
+
+  defp row_key([key]), do: key
+  defp row_key(key_columns), do: key_columns
+
+  defp run_typed_query(state, sql, params) do
+    case EctoLibSql.handle_execute(sql, params, [], state) do
+      {:ok, _query, result, _state} -> {:ok, result}
+      {:error, reason, _state} -> {:error, reason}
+    end
   end
 
+  defp single_row(%EctoLibSql.Result{rows: [row]}), do: {:ok, row}
+
+  defp single_row(%EctoLibSql.Result{rows: rows}) when rows in [nil, []],
+    do: {:error, {:no_rows, %{expected: 1, actual: 0}}}
+
+  defp single_row(%EctoLibSql.Result{rows: rows}),
+    do: {:error, {:too_many_rows, %{expected: 1, actual: length(rows)}}}
+
   @doc """
-  Install an authorizer hook for row-level security.
+  Check whether a table exists.
 
-  **NOT SUPPORTED** - Authorizer hooks require synchronous bidirectional communication
-  between Rust and Elixir, which is not feasible with Rustler's threading model.
+  Looks the name up in `sqlite_schema` (`sqlite_master`) with a bound
+  parameter, so any name is safe to pass. Views and indexes do not count.
+  Matching is case-insensitive, like SQLite identifiers.
 
-  ## Why Not Supported
+  ## Parameters
+    - state: The connection state (inside or outside a transaction)
+    - table: Table name (string or atom)
 
-  SQLite's authorizer callback is called synchronously during query compilation and expects
-  an immediate response (Allow/Deny/Ignore). This would require:
-  1. Sending a message from Rust to Elixir
-  2. Blocking the Rust thread waiting for a response
-  3. Receiving the response from Elixir
+  ## Example
 
-  This pattern is not safe with Rustler because:
-  - The callback runs on a SQLite thread (potentially holding locks)
-  - Blocking on Erlang scheduler threads can cause deadlocks
-  - No safe way to do synchronous Rust→Elixir→Rust calls
+      {:ok, true} = EctoLibSql.Native.table_exists(state, "users")
 
-  ## Alternatives
+  ## Returns
+    - `{:ok, boolean}` - Whether the table exists
+    - `{:error, reason}` - If the lookup fails
 
-  For row-level security and access control, consider:
+  """
+  @spec table_exists(EctoLibSql.State.t(), String.t() | atom()) ::
+          {:ok, boolean()} | {:error, term()}
+  def table_exists(%EctoLibSql.State{} = state, table) when is_binary(table) or is_atom(table) do
+    with {:ok, sql} <- table_schema(state, table) do
+      {:ok, sql != nil}
+    end
+  end
 
-  1. **Application-level authorization** - Check permissions in Elixir before queries:
+  @doc """
+  Get the `CREATE TABLE` statement of a table.
 
-      defmodule MyApp.Auth do
-        def can_access?(user, table, action) do
-          # Check user permissions
-        end
-      end
+  Returns the SQL exactly as stored in `sqlite_schema` (`sqlite_master`), or
+  `nil` when no such table exists. The name is bound as a parameter.
 
-      def get_user(id, current_user) do
-        if MyApp.Auth.can_access?(current_user, "users", :read) do
-          Repo.get(User, id)
-        else
-          {:error, :unauthorized}
-        end
-      end
+  ## Parameters
+    - state: The connection state (inside or outside a transaction)
+    - table: Table name (string or atom)
 
-  2. **Database views** - Create views with WHERE clauses for different user levels:
+  ## Example
 
-      CREATE VIEW user_visible_posts AS
-      SELECT * FROM posts WHERE user_id = current_user_id();
+      {:ok, "CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT)"} =
+        EctoLibSql.Native.table_schema(state, "users")
 
-  3. **Query rewriting** - Modify queries in Elixir to include authorization constraints:
+      {:ok, nil} = EctoLibSql.Native.table_schema(state, "missing")
 
-      defmodule MyApp.Repo do
-        def all(queryable, current_user) do
-          queryable
-          |> apply_tenant_filter(current_user)
-          |> Ecto.Repo.all()
-        end
-      end
+  ## Returns
+    - `{:ok, sql}` - The `CREATE` statement, or `nil` if the table does not exist
+    - `{:error, reason}` - If the lookup fails
 
-  4. **Connection-level restrictions** - Use different database connections with different privileges
+  """
+  @spec table_schema(EctoLibSql.State.t(), String.t() | atom()) ::
+          {:ok, String.t() | nil} | {:error, term()}
+  def table_schema(%EctoLibSql.State{} = state, table) when is_binary(table) or is_atom(table) do
+    sql = "SELECT sql FROM sqlite_master WHERE type = 'table' AND name = ? COLLATE NOCASE"
+
+    case run_typed_query(state, sql, [to_string(table)]) do
+      {:ok, %EctoLibSql.Result{rows: [[create_sql] | _]}} -> {:ok, create_sql}
+      {:ok, %EctoLibSql.Result{}} -> {:ok, nil}
+      {:error, reason} -> {:error, reason}
+    end
+  end
+
+  @doc """
+  List the tables, views, indexes and triggers in the database.
+
+  Reads `sqlite_schema` (`sqlite_master`) and returns one map per object,
+  ordered by type and then name. Internal objects whose names start with
+  `sqlite_` (such as `sqlite_sequence` and automatic indexes) are left out
+  unless `include_internal: true` is given.
+
+  ## Parameters
+    - state: The connection state (inside or outside a transaction)
+    - kind: `:table`, `:view`, `:index`, `:trigger` or `:all` (default `:all`)
+    - opts: `include_internal: true` to include `sqlite_*` objects
+
+  ## Example
+
+      {:ok, [%{name: "users", type: :table, sql: "CREATE TABLE users (...)"}]} =
+        EctoLibSql.Native.list_objects(state, :table)
 
   ## Returns
-    - `:unsupported` - Always returns unsupported
+    - `{:ok, objects}` - Maps with `:name`, `:type` and `:sql` keys; `:sql` is
+      `nil` for automatic indexes
+    - `{:error, reason}` - If the kind is unknown or the lookup fails
 
   """
-  def add_authorizer(%EctoLibSql.State{conn_id: conn_id} = _state, pid \\ self()) do
-    set_authorizer(conn_id, pid)
+  @spec list_objects(
+          EctoLibSql.State.t(),
+          :all | :table | :view | :index | :trigger,
+          keyword()
+        ) ::
+          {:ok, [%{name: String.t(), type: atom(), sql: String.t() | nil}]} | {:error, term()}
+  def list_objects(%EctoLibSql.State{} = state, kind \\ :all, opts \\ []) do
+    include_internal = Keyword.get(opts, :include_internal, false)
+
+    with {:ok, types} <- object_types(kind),
+         {:ok, %EctoLibSql.Result{rows: rows}} <-
+           run_typed_query(state, list_objects_sql(length(types), include_internal), types) do
+      {:ok,
+       Enum.map(rows || [], fn [name, type, sql] ->
+         %{name: name, type: String.to_existing_atom(type), sql: sql}
+       end)}
+    end
+  end
+
+  @object_kinds [:table, :view, :index, :trigger]
+
+  defp object_types(:all), do: {:ok, Enum.map(@object_kinds, &Atom.to_string/1)}
+  defp object_types(kind) when kind in @object_kinds, do: {:ok, [Atom.to_string(kind)]}
+  defp object_types(kind), do: {:error, "Unknown object kind: #{inspect(kind)}"}
+
+  defp list_objects_sql(type_count, include_internal) do
+    placeholders = Enum.map_join(1..type_count, ", ", fn _ -> "?" end)
+    internal = if include_internal, do: "", else: " AND name NOT LIKE 'sqlite\\_%' ESCAPE '\\'"
+
+    "SELECT name, type, sql FROM sqlite_master WHERE type IN (#{placeholders})" <>
+      internal <> " ORDER BY type, name"
   end
 
   @doc """
-  Execute multiple SQL statements from a semicolon-separated string.
+  Apply `CREATE ... IF NOT EXISTS` statements and report what they created.
 
-  Uses LibSQL's native batch execution for optimal performance. This is more
-  efficient than executing statements one-by-one as it reduces round-trips
-  and allows LibSQL to optimize the execution.
+  Runs every statement in one immediate transaction, so either all of them
+  apply or none do. The schema is read before and after, and each statement's
+  object is reported as `:created` if it appeared or `:existing` if it was
+  already there. Running the same list again is a no-op that reports every
+  object as existing, which makes this suitable for apps that bootstrap their
+  own schema on start.
 
-  Each statement is executed independently. If one fails, others may still
-  complete.
+  Only `CREATE TABLE`, `CREATE INDEX`, `CREATE VIEW` and `CREATE TRIGGER`
+  statements are accepted; use `IF NOT EXISTS` so existing objects are skipped
+  instead of failing.
 
   ## Parameters
-    - state: The connection state
-    - sql: Semicolon-separated SQL statements
+    - state: The connection state (outside a transaction)
+    - statements: List of `CREATE` statements, applied in order
 
   ## Example
 
-      sql = \"""
-      CREATE TABLE IF NOT EXISTS users (id INTEGER PRIMARY KEY, name TEXT);
-      INSERT INTO users (name) VALUES ('Alice');
-      INSERT INTO users (name) VALUES ('Bob');
-      SELECT * FROM users;
-      \"""
+      statements = [
+        "CREATE TABLE IF NOT EXISTS users (id INTEGER PRIMARY KEY, email TEXT)",
+        "CREATE UNIQUE INDEX IF NOT EXISTS users_email ON users (email)"
+      ]
 
-      {:ok, results} = EctoLibSql.Native.execute_batch_sql(state, sql)
+      {:ok, %{created: ["users", "users_email"], existing: []}} =
+        EctoLibSql.Native.ensure_schema(state, statements)
 
-  ## Returns
+      {:ok, %{created: [], existing: ["users", "users_email"]}} =
+        EctoLibSql.Native.ensure_schema(state, statements)
 
-  A list of results, one for each statement. Each result is either:
-  - A map with columns/rows for SELECT statements
-  - `nil` for statements that don't return data
+  ## Returns
+    - `{:ok, %{created: names, existing: names}}` - Object names in statement order
+    - `{:error, reason}` - If a statement is not a `CREATE` or fails; nothing
+      is applied
 
   """
-  def execute_batch_sql(%EctoLibSql.State{conn_id: conn_id} = _state, sql)
-      when is_binary(sql) do
-    case execute_batch_native(conn_id, sql) do
-      results when is_list(results) ->
-        {:ok, results}
+  @spec ensure_schema(EctoLibSql.State.t(), [String.t()]) ::
+          {:ok, %{created: [String.t()], existing: [String.t()]}} | {:error, term()}
+  def ensure_schema(%EctoLibSql.State{} = state, statements) when is_list(statements) do
+    with {:ok, names} <- schema_object_names(statements),
+         {:ok, trx_state} <- begin(state, behavior: :immediate) do
+      case apply_schema(trx_state, statements) do
+        {:ok, before, after_apply} ->
+          case commit(trx_state) do
+            {:ok, _} ->
+              {created, existing} = Enum.split_with(names, &created?(&1, before, after_apply))
+              {:ok, %{created: created, existing: existing}}
+
+            {:error, reason} ->
+              abort_schema_transaction(trx_state)
+              {:error, reason}
+          end
 
-      {:error, message} ->
-        {:error, message}
+        {:error, reason} ->
+          abort_schema_transaction(trx_state)
+          {:error, reason}
+      end
+    end
+  end
+
+  # A failed commit has already dropped the transaction entry, so `rollback/1`
+  # cannot reach it; issue a plain ROLLBACK if the IMMEDIATE lock is still held.
+  defp abort_schema_transaction(trx_state) do
+    rollback(trx_state)
+
+    if get_is_autocommit(trx_state) == false do
+      execute_batch_sql(trx_state, "ROLLBACK")
+    end
+
+    :ok
+  end
+
+  @create_object_regex ~r/
+    ^\s*CREATE\s+(?:UNIQUE\s+)?(?:TABLE|INDEX|VIEW|TRIGGER)\s+(?:IF\s+NOT\s+EXISTS\s+)?
+    (?:[\w"`\[\]]+\s*\.\s*)?(?:"([^"]+)"|`([^`]+)`|\[([^\]]+)\]|(\w+))
+  /ix
+
+  defp schema_object_names(statements) do
+    result =
+      Enum.reduce_while(statements, {:ok, []}, fn statement, {:ok, names} ->
+        # Only the alternative that matched captures a non-empty name
+        case Regex.run(@create_object_regex, statement) do
+          [_ | captures] -> {:cont, {:ok, [Enum.find(captures, &(&1 != "")) | names]}}
+          _ -> {:halt, {:error, "ensure_schema only accepts CREATE statements: #{statement}"}}
+        end
+      end)
+
+    with {:ok, names} <- result, do: {:ok, Enum.reverse(names)}
+  end
+
+  defp apply_schema(trx_state, statements) do
+    with {:ok, before} <- schema_names(trx_state),
+         :ok <- run_statements(trx_state, statements),
+         {:ok, after_apply} <- schema_names(trx_state) do
+      {:ok, before, after_apply}
+    end
+  end
+
+  defp run_statements(trx_state, statements) do
+    Enum.reduce_while(statements, :ok, fn statement, :ok ->
+      case run_typed_query(trx_state, statement, []) do
+        {:ok, _result} -> {:cont, :ok}
+        {:error, reason} -> {:halt, {:error, reason}}
+      end
+    end)
+  end
+
+  defp schema_names(state) do
+    with {:ok, objects} <- list_objects(state) do
+      {:ok, MapSet.new(objects, &String.downcase(&1.name))}
     end
   end
 
+  defp created?(name, before, after_apply) do
+    name = String.downcase(name)
+    not MapSet.member?(before, name) and MapSet.member?(after_apply, name)
+  end
+
   @doc """
-  Execute multiple SQL statements atomically in a transaction.
+  Drop every temporary table, view and trigger on the connection.
 
-  Uses LibSQL's native transactional batch execution. All statements execute
-  within a single transaction - if any statement fails, all changes are
-  rolled back.
+  Objects made with `CREATE TEMP` live as long as the connection that created
+  them, so on a pooled connection they outlive the caller and surprise the
+  next process to check the connection out. Call this before handing such a
+  connection back. Triggers go first, then views, then tables; indexes on
+  temporary tables are dropped along with their tables.
 
   ## Parameters
     - state: The connection state
-    - sql: Semicolon-separated SQL statements
 
   ## Example
 
-      sql = \"""
-      UPDATE accounts SET balance = balance - 100 WHERE id = 1;
-      UPDATE accounts SET balance = balance + 100 WHERE id = 2;
-      INSERT INTO transfers (from_id, to_id, amount) VALUES (1, 2, 100);
-      \"""
+      {:ok, _query, _result, state} =
+        EctoLibSql.handle_execute("CREATE TEMP TABLE scratch (id INTEGER)", [], [], state)
 
-      {:ok, results} = EctoLibSql.Native.execute_transactional_batch_sql(state, sql)
+      {:ok, ["scratch"]} = EctoLibSql.Native.drop_temp_objects(state)
 
-  ## Notes
+  ## Returns
+    - `{:ok, names}` - Names of the dropped objects, in drop order
+    - `{:error, reason}` - If a lookup or drop fails
 
-  - All statements succeed or all are rolled back
-  - More efficient than manual transaction with multiple queries
-  - Ideal for migrations, data loading, and multi-statement operations
+  """
+  @spec drop_temp_objects(EctoLibSql.State.t()) :: {:ok, [String.t()]} | {:error, term()}
+  def drop_temp_objects(%EctoLibSql.State{} = state) do
+    with {:ok, %EctoLibSql.Result{rows: rows}} <- run_typed_query(state, @temp_objects_sql, []),
+         {:ok, dropped} <- drop_temp_rows(state, rows || []) do
+      {:ok, Enum.reverse(dropped)}
+    end
+  end
 
+  @temp_objects_sql """
+  SELECT type, name FROM sqlite_temp_master
+  WHERE type IN ('trigger', 'view', 'table') AND name NOT LIKE 'sqlite\\_%' ESCAPE '\\'
+  ORDER BY CASE type WHEN 'trigger' THEN 0 WHEN 'view' THEN 1 ELSE 2 END, name
   """
-  def execute_transactional_batch_sql(%EctoLibSql.State{conn_id: conn_id} = _state, sql)
-      when is_binary(sql) do
-    case execute_transactional_batch_native(conn_id, sql) do
-      results when is_list(results) ->
-        {:ok, results}
 
-      {:error, message} ->
-        {:error, message}
-    end
+  defp drop_temp_rows(state, rows) do
+    Enum.reduce_while(rows, {:ok, []}, fn [type, name], {:ok, dropped} ->
+      quoted = String.replace(name, "\"", "\"\"")
+      sql = ~s(DROP #{String.upcase(type)} IF EXISTS temp."#{quoted}")
+
+      case run_typed_query(state, sql, []) do
+        {:ok, _result} -> {:cont, {:ok, [name | dropped]}}
+        {:error, reason} -> {:halt, {:error, reason}}
+      end
+    end)
   end
 
   @doc """
@@ -1917,6 +3689,45 @@ defmodule EctoLibSql.Native do
     {:error, "No active transaction"}
   end
 
+  @doc """
+  Report how many savepoints are open in the current transaction.
+
+  Counts savepoints created with `create_savepoint/2` that have not been released
+  or discarded by rolling back to an earlier savepoint. Handy when debugging
+  mismatched release/rollback calls. Savepoints opened with raw `SAVEPOINT` SQL
+  are not counted.
+
+  ## Parameters
+    - state: The connection state with an active transaction
+
+  ## Example
+
+      {:ok, trx_state} = EctoLibSql.Native.begin(state)
+      :ok = EctoLibSql.Native.create_savepoint(trx_state, "sp1")
+      :ok = EctoLibSql.Native.create_savepoint(trx_state, "sp2")
+      {:ok, 2} = EctoLibSql.Native.savepoint_depth(trx_state)
+
+      :ok = EctoLibSql.Native.release_savepoint_by_name(trx_state, "sp2")
+      {:ok, 1} = EctoLibSql.Native.savepoint_depth(trx_state)
+
+  ## Returns
+    - `{:ok, depth}` - Number of open savepoints, `0` if there are none
+    - `{:error, reason}` - Outside a transaction or on failure
+
+  """
+  @spec savepoint_depth(EctoLibSql.State.t()) :: {:ok, non_neg_integer()} | {:error, term()}
+  def savepoint_depth(%EctoLibSql.State{conn_id: conn_id, trx_id: trx_id} = _state)
+      when is_binary(conn_id) and is_binary(trx_id) do
+    case savepoint_depth_native(conn_id, trx_id) do
+      depth when is_integer(depth) -> {:ok, depth}
+      {:error, reason} -> {:error, reason}
+    end
+  end
+
+  def savepoint_depth(%EctoLibSql.State{trx_id: nil}) do
+    {:error, "No active transaction"}
+  end
+
   @doc """
   Get the current replication frame number from a remote replica.
 
@@ -1994,6 +3805,108 @@ defmodule EctoLibSql.Native do
     sync_until_frame(conn_id, target_frame)
   end
 
+  @doc """
+  Wait until a remote replica has applied a target frame number.
+
+  Unlike `sync_until_frame/2`, this does not pull from the primary itself. It
+  polls the replica's applied frame until it reaches `target_frame`, relying on
+  automatic sync (or `sync/1` calls from elsewhere) to make progress. Use it for
+  read-after-write when another process or node did the write.
+
+  ## Parameters
+    - conn_id: The connection ID or connection state
+    - target_frame: Frame number to wait for, e.g. from `get_max_write_frame/1`
+    - timeout_ms: Maximum time to wait in milliseconds (default: 5000)
+
+  ## Returns
+    - `:ok` - The replica has applied at least `target_frame`
+    - `{:error, :wait_timeout}` - The timeout elapsed first
+    - `{:error, reason}` - If the connection is invalid
+
+  ## Example
+
+      # On the writer
+      {:ok, frame} = EctoLibSql.Native.get_max_write_frame(writer.conn_id)
+
+      # On the reader
+      :ok = EctoLibSql.Native.wait_for_frame(reader, frame, 2_000)
+
+  ## Notes
+    - Local connections report frame 0, so any positive target times out
+    - A target of 0 returns `:ok` immediately
+
+  """
+  @spec wait_for_frame(String.t() | EctoLibSql.State.t(), non_neg_integer(), non_neg_integer()) ::
+          :ok | {:error, :wait_timeout | term()}
+  def wait_for_frame(conn_id, target_frame, timeout_ms \\ 5000)
+
+  def wait_for_frame(conn_id, target_frame, timeout_ms)
+      when is_binary(conn_id) and is_integer(target_frame) and target_frame >= 0 and
+             is_integer(timeout_ms) and timeout_ms >= 0 do
+    case wait_for_frame_native(conn_id, target_frame, timeout_ms) do
+      :ok -> :ok
+      {:error, reason} -> {:error, reason}
+    end
+  end
+
+  def wait_for_frame(%EctoLibSql.State{conn_id: conn_id}, target_frame, timeout_ms) do
+    wait_for_frame(conn_id, target_frame, timeout_ms)
+  end
+
+  @doc """
+  Get cumulative sync metrics for a remote replica connection.
+
+  Counters are kept per connection and updated by every `sync/1` and
+  `sync_until_frame/2` call, so they can be polled and graphed as a proxy
+  for replication lag and health.
+
+  ## Parameters
+    - conn_id: The connection ID (or connection state)
+
+  ## Returns
+    - `{:ok, metrics}` - A map with:
+      - `:total_syncs` - Number of successful syncs
+      - `:failed_syncs` - Number of syncs that failed or timed out
+      - `:total_frames_applied` - Frames applied across all successful syncs
+      - `:last_sync_at` - `DateTime` of the last successful sync, or `nil`
+      - `:last_error` - Message of the most recent failed sync, or `nil`
+    - `{:error, reason}` - If the connection is invalid
+
+  ## Example
+
+      {:ok, metrics} = EctoLibSql.Native.get_replica_metrics(state)
+      :telemetry.execute([:my_app, :replica], Map.take(metrics, [:total_syncs, :total_frames_applied]))
+
+  ## Notes
+    - For local and remote primary connections all counters stay at zero
+    - `:last_error` is kept after later successful syncs; compare it with
+      `:last_sync_at` to tell whether the replica has recovered
+
+  """
+  def get_replica_metrics(conn_id) when is_binary(conn_id) do
+    case replica_metrics(conn_id) do
+      {total_syncs, failed_syncs, total_frames_applied, last_sync_at_ms, last_error} ->
+        {:ok,
+         %{
+           total_syncs: total_syncs,
+           failed_syncs: failed_syncs,
+           total_frames_applied: total_frames_applied,
+           last_sync_at: last_sync_at_ms && DateTime.from_unix!(last_sync_at_ms, :millisecond),
+           last_error: last_error
+         }}
+
+      {:error, reason} ->
+        {:error, reason}
+
+      other ->
+        {:error, "Unexpected response: #{inspect(other)}"}
+    end
+  end
+
+  def get_replica_metrics(%EctoLibSql.State{conn_id: conn_id}) do
+    get_replica_metrics(conn_id)
+  end
+
   @doc """
   Flush the replicator, pushing pending writes to the remote database.
 
@@ -180,6 +180,78 @@ defmodule EctoLibSql.Pragma do
     query(state, "PRAGMA foreign_keys")
   end
 
+  @doc """
+  Defer foreign key checks until the current transaction commits.
+
+  With `defer_foreign_keys` on, every foreign key constraint behaves as if it
+  were declared `DEFERRABLE INITIALLY DEFERRED`: rows may reference parents that
+  are inserted later in the same transaction, and violations are only reported
+  when it commits. Useful for imports that cannot insert in dependency order.
+
+  Foreign keys must be enabled (see `enable_foreign_keys/1`) for there to be
+  anything to defer. Constraints declared `DEFERRABLE INITIALLY DEFERRED` in the
+  schema are deferred without this pragma.
+
+  ## Parameters
+
+    - state: Connection state with an active transaction
+    - enabled: `true` to defer checks, `false` to enforce them immediately again
+
+  ## Returns
+
+    - `:ok` on success
+    - `{:error, reason}` outside a transaction or on failure
+
+  ## Examples
+
+      {:ok, trx_state} = EctoLibSql.Native.begin(state)
+      :ok = EctoLibSql.Pragma.set_defer_foreign_keys(trx_state, true)
+      # ... insert children before their parents ...
+      {:ok, _} = EctoLibSql.Native.commit(trx_state)
+
+  ## Notes
+
+  SQLite switches `defer_foreign_keys` off again at every `COMMIT` and
+  `ROLLBACK`, so it has to be set in each transaction that needs it. If
+  violations remain at commit, the commit fails and the transaction is rolled
+  back.
+
+  """
+  @spec set_defer_foreign_keys(State.t(), boolean()) :: :ok | {:error, term()}
+  def set_defer_foreign_keys(%State{trx_id: trx_id} = state, enabled)
+      when is_binary(trx_id) and is_boolean(enabled) do
+    case query(state, "PRAGMA defer_foreign_keys = #{if enabled, do: "ON", else: "OFF"}") do
+      {:ok, _} -> :ok
+      {:error, reason} -> {:error, reason}
+    end
+  end
+
+  def set_defer_foreign_keys(%State{trx_id: nil}, _enabled) do
+    {:error, "No active transaction - defer_foreign_keys resets at the end of every transaction"}
+  end
+
+  @doc """
+  Query the current `defer_foreign_keys` setting.
+
+  ## Parameters
+
+    - state: Connection state
+
+  ## Returns
+
+    - `{:ok, result}` where result.rows is [[1]] if checks are deferred, [[0]] if not
+    - `{:error, reason}` on failure
+
+  ## Examples
+
+      {:ok, result} = EctoLibSql.Pragma.defer_foreign_keys(trx_state)
+      deferred? = result.rows == [[1]]
+
+  """
+  def defer_foreign_keys(%State{} = state) do
+    query(state, "PRAGMA defer_foreign_keys")
+  end
+
   @doc """
   Set the journal mode.
 
@@ -199,6 +271,7 @@ defmodule EctoLibSql.Pragma do
   ## Returns
 
     - `{:ok, result}` with the new journal mode
+    - `{:error, {:wal_not_supported, actual_mode}}` if `:wal` was requested but did not stick
     - `{:error, reason}` on failure
 
   ## Examples
@@ -212,13 +285,66 @@ defmodule EctoLibSql.Pragma do
 
       EctoLibSql.Pragma.set_journal_mode(state, :wal)
 
+  ## WAL Persistence Check
+
+  SQLite does not return an error when WAL mode cannot be enabled - it silently
+  stays in (or reverts to) a rollback-journal mode instead. When `:wal` is requested,
+  the journal mode is read back after being set. If it did not stick, the PRAGMA is
+  re-applied once, and if it still does not report `wal` this function returns
+  `{:error, {:wal_not_supported, actual_mode}}`:
+
+      case EctoLibSql.Pragma.set_journal_mode(state, :wal) do
+        {:ok, _result} -> :ok
+        {:error, {:wal_not_supported, mode}} -> Logger.warning("WAL unavailable, using \#{mode}")
+      end
+
+  WAL requires shared memory between connections, so it is **not supported on
+  network filesystems** (NFS, SMB/CIFS, and most FUSE-backed mounts) and is never
+  available for in-memory databases (which always report `memory`). Keep database
+  files on a local filesystem if you rely on WAL concurrency.
+
   """
+  def set_journal_mode(%State{} = state, :wal) do
+    with {:ok, result} <- query(state, "PRAGMA journal_mode = WAL"),
+         :ok <- verify_wal_mode(state, 1) do
+      {:ok, result}
+    end
+  end
+
   def set_journal_mode(%State{} = state, mode)
-      when mode in [:delete, :wal, :memory, :persist, :truncate, :off] do
+      when mode in [:delete, :memory, :persist, :truncate, :off] do
     mode_str = mode |> Atom.to_string() |> String.upcase()
     query(state, "PRAGMA journal_mode = #{mode_str}")
   end
 
+  # Read the journal mode back and re-apply WAL if it did not stick.
+  # `retries` is the number of re-apply attempts left before giving up.
+  @spec verify_wal_mode(State.t(), non_neg_integer()) ::
+          :ok | {:error, {:wal_not_supported, String.t()} | term()}
+  defp verify_wal_mode(state, retries) do
+    case journal_mode(state) do
+      {:ok, %EctoLibSql.Result{rows: [[mode]]}} when is_binary(mode) ->
+        cond do
+          String.downcase(mode) == "wal" ->
+            :ok
+
+          retries > 0 ->
+            with {:ok, _} <- query(state, "PRAGMA journal_mode = WAL") do
+              verify_wal_mode(state, retries - 1)
+            end
+
+          true ->
+            {:error, {:wal_not_supported, String.downcase(mode)}}
+        end
+
+      {:ok, %EctoLibSql.Result{rows: rows}} ->
+        {:error, {:wal_not_supported, inspect(rows)}}
+
+      {:error, reason} ->
+        {:error, reason}
+    end
+  end
+
   @doc """
   Query the current journal mode.
 
@@ -241,6 +367,59 @@ defmodule EctoLibSql.Pragma do
     query(state, "PRAGMA journal_mode")
   end
 
+  @doc """
+  Reclaim free pages from a database in incremental auto-vacuum mode.
+
+  Runs `PRAGMA incremental_vacuum(N)`, moving up to `pages` free pages to the
+  end of the file and truncating it, or `PRAGMA incremental_vacuum` to reclaim
+  every free page. Returns once the vacuum has finished.
+
+  Incremental vacuum only works when `auto_vacuum` is `INCREMENTAL`; the mode
+  is checked first. Note that `auto_vacuum` can only be changed on an empty
+  database or by running `VACUUM` afterwards.
+
+  ## Parameters
+
+    - state: Connection state
+    - pages: Maximum number of pages to reclaim, or `nil` for all (default)
+
+  ## Returns
+
+    - `:ok` when the vacuum has completed
+    - `{:error, {:auto_vacuum_not_incremental, mode}}` if the database's auto-vacuum
+      mode (`:none` or `:full`) does not support incremental vacuuming
+    - `{:error, reason}` on failure
+
+  ## Examples
+
+      {:ok, _} = EctoLibSql.Pragma.query(state, "PRAGMA auto_vacuum = INCREMENTAL")
+      # ... create tables, delete rows ...
+      :ok = EctoLibSql.Pragma.incremental_vacuum(state, 100)
+
+  """
+  @spec incremental_vacuum(State.t(), pos_integer() | nil) ::
+          :ok | {:error, {:auto_vacuum_not_incremental, atom()} | term()}
+  def incremental_vacuum(%State{} = state, pages \\ nil)
+      when is_nil(pages) or (is_integer(pages) and pages > 0) do
+    stmt =
+      if pages,
+        do: "PRAGMA incremental_vacuum(#{pages})",
+        else: "PRAGMA incremental_vacuum"
+
+    with {:ok, %EctoLibSql.Result{rows: [[mode]]}} <- query(state, "PRAGMA auto_vacuum"),
+         :ok <- ensure_incremental_auto_vacuum(mode),
+         {:ok, _result} <- query(state, stmt) do
+      :ok
+    end
+  end
+
+  defp ensure_incremental_auto_vacuum(2), do: :ok
+  defp ensure_incremental_auto_vacuum(0), do: {:error, {:auto_vacuum_not_incremental, :none}}
+  defp ensure_incremental_auto_vacuum(1), do: {:error, {:auto_vacuum_not_incremental, :full}}
+
+  defp ensure_incremental_auto_vacuum(mode),
+    do: {:error, {:auto_vacuum_not_incremental, mode}}
+
   @doc """
   Set the synchronous mode.
 
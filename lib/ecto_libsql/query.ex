@@ -48,12 +48,53 @@ defmodule EctoLibSql.Query do
     # - true/false → 1/0 (SQLite uses integers for booleans)
     # - UUID binary → string representation (if needed)
     # - :null atom → nil (SQL NULL)
+    # - {low, high} range tuple → two parameters, for `BETWEEN ? AND ?`
     def encode(_query, params, _opts) when is_list(params) do
-      Enum.map(params, &encode_param/1)
+      Enum.flat_map(params, &expand_param/1)
     end
 
     def encode(_query, params, _opts), do: params
 
+    # {:blob, data} from the binary dumper is a single value, not a range
+    defp expand_param({:blob, _data} = blob), do: [encode_param(blob)]
+
+    # {:charlist, chars} opts a charlist in to binding as TEXT
+    defp expand_param({:charlist, _chars} = charlist), do: [charlist]
+
+    # Range tuples expand to `low, high` in order, so `BETWEEN ? AND ?` can be
+    # bound with one `{low, high}` parameter. Both ends must be the same kind
+    # of non-NULL scalar.
+    defp expand_param({low, high} = range) do
+      low_kind = range_kind(low)
+
+      if low_kind == :invalid or low_kind != range_kind(high) do
+        raise ArgumentError,
+          message:
+            "Range tuple parameters must be {low, high} with two non-nil values of the " <>
+              "same type (numbers, strings, dates or times). Got: #{inspect(range)}"
+      end
+
+      [encode_param(low), encode_param(high)]
+    end
+
+    defp expand_param(tuple) when is_tuple(tuple) do
+      raise ArgumentError,
+        message:
+          "Tuple parameters must be a two-element {low, high} range. " <>
+            "Got #{tuple_size(tuple)} elements: #{inspect(tuple)}"
+    end
+
+    defp expand_param(value), do: [encode_param(value)]
+
+    defp range_kind(value) when is_number(value), do: :number
+    defp range_kind(%Decimal{}), do: :number
+    defp range_kind(value) when is_binary(value), do: :text
+
+    defp range_kind(%module{}) when module in [DateTime, NaiveDateTime, Date, Time],
+      do: {:temporal, module}
+
+    defp range_kind(_value), do: :invalid
+
     # Temporal types
     defp encode_param(%DateTime{} = dt), do: DateTime.to_iso8601(dt)
     defp encode_param(%NaiveDateTime{} = dt), do: NaiveDateTime.to_iso8601(dt)
@@ -95,6 +136,9 @@ defmodule EctoLibSql.Query do
     # - For Ecto queries with IN clauses: Ecto's query builder expands lists into individual parameters
     # - For array fields in schemas: Ecto dumpers handle JSON encoding via array_encode/1
     # - For raw SQL with arrays: Users should pre-encode lists using Jason.encode!
+    # - For raw SQL ranges: pass a {low, high} tuple (expanded above)
+    # - Bare integer lists (including charlists) are bound as a BLOB of bytes by the NIF;
+    #   tag a charlist as {:charlist, chars} to bind it as TEXT
     # This design allows IN clauses to work correctly while still supporting array fields.
     defp encode_param(value), do: value
 
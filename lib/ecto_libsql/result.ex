@@ -12,6 +12,8 @@ defmodule EctoLibSql.Result do
   - `:columns` - List of column names (for SELECT queries), or `nil` for write operations
   - `:rows` - List of rows, where each row is a list of values, or `nil` for write operations
   - `:num_rows` - Number of rows affected or returned
+  - `:timing` - Step timings in microseconds when the query ran with `timing: true`,
+    otherwise `nil` (see `EctoLibSql.handle_execute/4`)
 
   ## Examples
 
@@ -36,7 +38,8 @@ defmodule EctoLibSql.Result do
   defstruct command: nil,
             columns: nil,
             rows: nil,
-            num_rows: 0
+            num_rows: 0,
+            timing: nil
 
   @typedoc "The type of SQL command that was executed."
   @type command_type ::
@@ -54,12 +57,21 @@ defmodule EctoLibSql.Result do
           | :other
           | nil
 
+  @typedoc "Microseconds spent in each step of a timed query."
+  @type timing :: %{
+          prepare_us: non_neg_integer(),
+          execute_us: non_neg_integer(),
+          fetch_us: non_neg_integer(),
+          total_us: non_neg_integer()
+        }
+
   @typedoc "Result struct containing query results."
   @type t :: %__MODULE__{
           command: command_type(),
           columns: [String.t()] | nil,
           rows: [[term()]] | nil,
-          num_rows: non_neg_integer()
+          num_rows: non_neg_integer(),
+          timing: timing() | nil
         }
 
   @doc """
@@ -75,7 +87,7 @@ defmodule EctoLibSql.Result do
   ## Examples
 
       iex> EctoLibSql.Result.new(command: :select, columns: ["id"], rows: [[1]], num_rows: 1)
-      %EctoLibSql.Result{command: :select, columns: ["id"], rows: [[1]], num_rows: 1}
+      %EctoLibSql.Result{command: :select, columns: ["id"], rows: [[1]], num_rows: 1, timing: nil}
 
   """
   @spec new(Keyword.t()) :: t
@@ -11,6 +11,7 @@ defmodule EctoLibSql.State do
   - `:trx_id` - Transaction ID if a transaction is active, `nil` otherwise
   - `:mode` - Connection mode (`:local`, `:remote`, or `:remote_replica`)
   - `:sync` - Sync mode for replicas (`:enable_sync` or `:disable_sync`)
+  - `:tag` - Label given with the `:tag` connect option, `nil` otherwise
 
   ## Connection Modes
 
@@ -31,7 +32,8 @@ defmodule EctoLibSql.State do
           conn_id: String.t(),
           trx_id: String.t() | nil,
           mode: mode() | nil,
-          sync: sync_mode() | nil
+          sync: sync_mode() | nil,
+          tag: String.t() | nil
         }
 
   @enforce_keys [:conn_id]
@@ -40,7 +42,8 @@ defmodule EctoLibSql.State do
     :conn_id,
     :trx_id,
     :mode,
-    :sync
+    :sync,
+    :tag
   ]
 
   @doc """
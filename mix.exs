@@ -57,7 +57,8 @@ defmodule EctoLibSql.MixProject do
       {:jason, "~> 1.4"},
       {:rustler, "~> 0.37.1"},
       {:sobelow, "~> 0.13", only: [:dev, :test], runtime: false},
-      {:stream_data, "~> 1.0", only: [:dev, :test]}
+      {:stream_data, "~> 1.0", only: [:dev, :test]},
+      {:telemetry, "~> 0.4 or ~> 1.0"}
     ]
   end
 
@@ -1,13 +1,28 @@
 /// Batch operations for `LibSQL`/Turso databases
 ///
-/// This module handles batch execution of multiple SQL statements, both with
-/// and without transactional semantics. Supports both statement-level batch
-/// execution (with parameterized queries) and native SQL batch execution.
-use crate::constants::{CONNECTION_REGISTRY, TOKIO_RUNTIME};
-use crate::utils::{collect_rows, decode_term_to_value, safe_lock, safe_lock_arc};
-use libsql::Value;
-use rustler::types::atom::nil;
+/// This module handles batch execution and bulk data helpers, including:
+/// - Statement-level batches with parameterized queries, with or without a transaction
+/// - Native SQL batches of several statements in one string
+/// - A pipeline that runs several queries in one call
+/// - Batched `DROP TABLE`, table truncation and sequence reset for test teardown
+/// - `REINDEX` maintenance
+/// - `INSERT INTO ... SELECT` copies for ETL jobs
+/// - NDJSON table dumps for debugging
+/// - Snapshots of a query result into a standalone database file
+/// - Inserts that bind a blob straight from a file
+use crate::constants::{file, CONNECTION_REGISTRY, TOKIO_RUNTIME};
+use crate::models::LibSQLConn;
+use crate::utils::{
+    apply_bind_options, build_copy_batch_sql, build_materialize_sql, collect_rows,
+    decode_term_to_value, ensure_sql_allowed, quote_identifier, row_to_json, safe_lock,
+    safe_lock_arc, statement_error, uses_autoincrement,
+};
+use libsql::{Builder, Value};
+use rustler::types::atom::{error, nil, ok};
 use rustler::{Atom, Encoder, Env, NifResult, Term};
+use std::fs::File;
+use std::io::{BufWriter, Read, Write};
+use std::sync::{Arc, Mutex};
 
 /// Execute multiple SQL statements sequentially without a transaction.
 ///
@@ -49,12 +64,14 @@ pub fn execute_batch<'a>(
             rustler::Error::Term(Box::new(format!("Failed to decode statement: {e:?}")))
         })?;
 
-        let decoded_args: Vec<Value> = args
+        let mut decoded_args: Vec<Value> = args
             .into_iter()
             .map(|t| decode_term_to_value(t))
             .collect::<Result<_, _>>()
             .map_err(|e| rustler::Error::Term(Box::new(e)))?;
 
+        ensure_sql_allowed(conn_id, &query)?;
+        apply_bind_options(conn_id, &mut decoded_args)?;
         batch_stmts.push((query, decoded_args));
     }
 
@@ -79,9 +96,7 @@ pub fn execute_batch<'a>(
                     all_results.push(collected);
                 }
                 Err(e) => {
-                    return Err(rustler::Error::Term(Box::new(format!(
-                        "Batch statement error: {e}"
-                    ))));
+                    return Err(statement_error(format!("Batch statement error: {e}")));
                 }
             }
         }
@@ -131,12 +146,14 @@ pub fn execute_transactional_batch<'a>(
             rustler::Error::Term(Box::new(format!("Failed to decode statement: {e:?}")))
         })?;
 
-        let decoded_args: Vec<Value> = args
+        let mut decoded_args: Vec<Value> = args
             .into_iter()
             .map(|t| decode_term_to_value(t))
             .collect::<Result<_, _>>()
             .map_err(|e| rustler::Error::Term(Box::new(e)))?;
 
+        ensure_sql_allowed(conn_id, &query)?;
+        apply_bind_options(conn_id, &mut decoded_args)?;
         batch_stmts.push((query, decoded_args));
     }
 
@@ -168,9 +185,7 @@ pub fn execute_transactional_batch<'a>(
                 Err(e) => {
                     // Rollback on error
                     let _ = trx.rollback().await;
-                    return Err(rustler::Error::Term(Box::new(format!(
-                        "Batch statement error: {e}"
-                    ))));
+                    return Err(statement_error(format!("Batch statement error: {e}")));
                 }
             }
         }
@@ -203,6 +218,7 @@ pub fn execute_transactional_batch<'a>(
 pub fn execute_batch_native<'a>(env: Env<'a>, conn_id: &str, sql: &str) -> NifResult<Term<'a>> {
     // UTF-8 validation is guaranteed by Rust's &str type and Rustler's conversion,
     // so we can rely on the type system rather than runtime checks.
+    ensure_sql_allowed(conn_id, sql)?;
 
     let conn_map = safe_lock(&CONNECTION_REGISTRY, "execute_batch_native conn_map")?;
 
@@ -220,7 +236,7 @@ pub fn execute_batch_native<'a>(env: Env<'a>, conn_id: &str, sql: &str) -> NifRe
             let mut batch_rows = conn_guard
                 .execute_batch(sql)
                 .await
-                .map_err(|e| rustler::Error::Term(Box::new(format!("batch failed: {e}"))))?;
+                .map_err(|e| statement_error(format!("batch failed: {e}")))?;
             // Drop guards after batch is retrieved
             drop(conn_guard);
             drop(client_guard);
@@ -272,6 +288,8 @@ pub fn execute_transactional_batch_native<'a>(
     conn_id: &str,
     sql: &str,
 ) -> NifResult<Term<'a>> {
+    ensure_sql_allowed(conn_id, sql)?;
+
     let conn_map = safe_lock(
         &CONNECTION_REGISTRY,
         "execute_transactional_batch_native conn_map",
@@ -291,13 +309,10 @@ pub fn execute_transactional_batch_native<'a>(
                 &client_guard.client,
                 "execute_transactional_batch_native conn",
             )?;
-            let mut batch_rows =
-                conn_guard
-                    .execute_transactional_batch(sql)
-                    .await
-                    .map_err(|e| {
-                        rustler::Error::Term(Box::new(format!("transactional batch failed: {e}")))
-                    })?;
+            let mut batch_rows = conn_guard
+                .execute_transactional_batch(sql)
+                .await
+                .map_err(|e| statement_error(format!("transactional batch failed: {e}")))?;
             // Drop guards after batch is retrieved
             drop(conn_guard);
             drop(client_guard);
@@ -324,3 +339,993 @@ pub fn execute_transactional_batch_native<'a>(
         Err(rustler::Error::Term(Box::new("Invalid connection ID")))
     }
 }
+
+/// Run several read queries in one call, returning their result sets in order.
+///
+/// Queries run one after another on the connection, each with its own bound
+/// parameters, saving a NIF round trip per query for dashboards that issue
+/// many small SELECTs. Each query is a `{sql, params, continue_on_error}`
+/// tuple. A failing query stops the pipeline and reports its zero-based index,
+/// unless its `continue_on_error` flag is set, in which case its error takes
+/// its place in the results and later queries still run.
+///
+/// # Arguments
+/// - `env`: Elixir environment
+/// - `conn_id`: Database connection ID
+/// - `queries`: List of `{sql, params, continue_on_error}` tuples
+///
+/// Returns a list with `{:ok, result_map}` or `{:error, reason}` per query, or
+/// `{:error, {index, reason}}` for the first failing query that does not continue.
+#[rustler::nif(schedule = "DirtyIo")]
+pub fn query_pipeline_native<'a>(
+    env: Env<'a>,
+    conn_id: &str,
+    queries: Vec<Term<'a>>,
+) -> NifResult<Term<'a>> {
+    let conn_map = safe_lock(&CONNECTION_REGISTRY, "query_pipeline_native conn_map")?;
+
+    let client = conn_map
+        .get(conn_id)
+        .cloned()
+        .ok_or_else(|| rustler::Error::Term(Box::new("Invalid connection ID")))?;
+
+    drop(conn_map); // Release lock before async operation
+
+    let mut pipeline: Vec<(String, Vec<Term<'a>>, bool)> = Vec::with_capacity(queries.len());
+    for (index, query_term) in queries.into_iter().enumerate() {
+        let query = query_term.decode().map_err(|e| {
+            rustler::Error::Term(Box::new(format!(
+                "Failed to decode pipeline query {index}: {e:?}"
+            )))
+        })?;
+        pipeline.push(query);
+    }
+
+    TOKIO_RUNTIME.block_on(async {
+        let mut results: Vec<Term<'a>> = Vec::with_capacity(pipeline.len());
+
+        for (index, (sql, args, continue_on_error)) in pipeline.into_iter().enumerate() {
+            match run_pipeline_query(env, conn_id, &client, &sql, args).await {
+                Ok(result) => results.push((ok(), result).encode(env)),
+                Err(e) => {
+                    let reason = error_reason(env, e);
+
+                    if !continue_on_error {
+                        return Ok((error(), (index, reason)).encode(env));
+                    }
+
+                    results.push((error(), reason).encode(env));
+                }
+            }
+        }
+
+        Ok(results.encode(env))
+    })
+}
+
+/// Run one pipeline query, returning its result map.
+///
+/// # Note on Lock Safety
+/// This function holds a std::sync::Mutex guard across an await point. This is intentional
+/// and safe because it is called within TOKIO_RUNTIME.block_on() which executes synchronously.
+#[allow(clippy::await_holding_lock)]
+async fn run_pipeline_query<'a>(
+    env: Env<'a>,
+    conn_id: &str,
+    client: &Arc<Mutex<LibSQLConn>>,
+    sql: &str,
+    args: Vec<Term<'a>>,
+) -> Result<Term<'a>, rustler::Error> {
+    ensure_sql_allowed(conn_id, sql)?;
+
+    let mut params: Vec<Value> = args
+        .into_iter()
+        .map(|t| decode_term_to_value(t))
+        .collect::<Result<_, _>>()
+        .map_err(|e| rustler::Error::Term(Box::new(e)))?;
+
+    apply_bind_options(conn_id, &mut params)?;
+
+    let client_guard = safe_lock_arc(client, "query_pipeline_native client")?;
+    let conn_guard = safe_lock_arc(&client_guard.client, "query_pipeline_native conn")?;
+    let rows = conn_guard
+        .query(sql, params)
+        .await
+        .map_err(|e| statement_error(format!("Pipeline query failed: {e}")))?;
+    // Drop guards once the statement has run - the rows are read without them
+    drop(conn_guard);
+    drop(client_guard);
+
+    collect_rows(env, rows).await
+}
+
+/// Encode the reason carried by `err` so it can sit inside a result tuple.
+fn error_reason<'a>(env: Env<'a>, err: rustler::Error) -> Term<'a> {
+    match err {
+        rustler::Error::Term(reason) => reason.encode(env),
+        other => format!("{other:?}").encode(env),
+    }
+}
+
+/// Drop several tables in a single transaction.
+///
+/// Each name is quoted as an identifier and dropped with `DROP TABLE [IF EXISTS]`.
+/// With `if_exists` set, missing tables are skipped; without it, the first missing
+/// table aborts the batch and every drop is rolled back.
+///
+/// Intended for test fixtures that tear down many tables at once.
+///
+/// # Arguments
+/// - `conn_id`: Database connection ID
+/// - `names`: Table names to drop
+/// - `if_exists`: Whether to use `DROP TABLE IF EXISTS`
+///
+/// Returns the number of tables that existed and were dropped.
+#[rustler::nif(schedule = "DirtyIo")]
+pub fn drop_tables_native(conn_id: &str, names: Vec<String>, if_exists: bool) -> NifResult<usize> {
+    if names.iter().any(String::is_empty) {
+        return Err(rustler::Error::Term(Box::new("Table name cannot be empty")));
+    }
+
+    let conn_map = safe_lock(&CONNECTION_REGISTRY, "drop_tables_native conn_map")?;
+
+    let client = conn_map
+        .get(conn_id)
+        .cloned()
+        .ok_or_else(|| rustler::Error::Term(Box::new("Invalid connection ID")))?;
+
+    drop(conn_map); // Release lock before async operation
+
+    // SAFETY: We use TOKIO_RUNTIME.block_on(), which runs the future synchronously on a dedicated
+    // thread pool. This prevents deadlocks that could occur if we were in a true async context
+    // with std::sync::Mutex guards held across await points.
+    #[allow(clippy::await_holding_lock)]
+    TOKIO_RUNTIME.block_on(async {
+        let client_guard = safe_lock_arc(&client, "drop_tables_native client")?;
+        let conn_guard = safe_lock_arc(&client_guard.client, "drop_tables_native conn")?;
+        let trx = conn_guard.transaction().await.map_err(|e| {
+            rustler::Error::Term(Box::new(format!("Begin transaction failed: {e}")))
+        })?;
+        // Drop guards after transaction is started - the transaction owns its own connection
+        drop(conn_guard);
+        drop(client_guard);
+
+        let mut dropped = 0;
+
+        for name in &names {
+            match drop_table_in_transaction(&trx, name, if_exists).await {
+                Ok(true) => dropped += 1,
+                Ok(false) => {}
+                Err(e) => {
+                    let _ = trx.rollback().await;
+                    return Err(e);
+                }
+            }
+        }
+
+        trx.commit()
+            .await
+            .map_err(|e| rustler::Error::Term(Box::new(format!("Commit failed: {e}"))))?;
+
+        Ok(dropped)
+    })
+}
+
+/// Drop one table inside `trx`, returning whether it existed beforehand.
+async fn drop_table_in_transaction(
+    trx: &libsql::Transaction,
+    name: &str,
+    if_exists: bool,
+) -> Result<bool, rustler::Error> {
+    // SQLite identifiers are case-insensitive, so match the catalogue the same way
+    let mut rows = trx
+        .query(
+            "SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = ?1 COLLATE NOCASE",
+            vec![Value::Text(name.to_string())],
+        )
+        .await
+        .map_err(|e| rustler::Error::Term(Box::new(format!("Table lookup failed: {e}"))))?;
+
+    let existed = rows
+        .next()
+        .await
+        .map_err(|e| rustler::Error::Term(Box::new(format!("Table lookup failed: {e}"))))?
+        .is_some();
+    // Finalise the lookup before dropping; an active statement would lock the table
+    drop(rows);
+
+    let drop_sql = if if_exists {
+        format!("DROP TABLE IF EXISTS {}", quote_identifier(name))
+    } else {
+        format!("DROP TABLE {}", quote_identifier(name))
+    };
+
+    trx.execute(&drop_sql, ())
+        .await
+        .map_err(|e| rustler::Error::Term(Box::new(format!("Drop table {name} failed: {e}"))))?;
+
+    Ok(existed)
+}
+
+/// Delete every row from a table, optionally resetting its AUTOINCREMENT counter.
+///
+/// Runs an unqualified `DELETE FROM "table"` so SQLite's truncate optimisation
+/// applies (it is skipped when the table has triggers). With `reset_sequence`,
+/// the table's row in `sqlite_sequence` is removed in the same transaction so
+/// the next AUTOINCREMENT id starts again from 1; tables without AUTOINCREMENT
+/// are unaffected.
+///
+/// # Arguments
+/// - `conn_id`: Database connection ID
+/// - `table`: Table name (quoted automatically)
+/// - `reset_sequence`: Whether to clear the table's `sqlite_sequence` entry
+///
+/// Returns the number of rows deleted.
+#[rustler::nif(schedule = "DirtyIo")]
+pub fn truncate_native(conn_id: &str, table: &str, reset_sequence: bool) -> NifResult<u64> {
+    if table.is_empty() {
+        return Err(rustler::Error::Term(Box::new("Table name cannot be empty")));
+    }
+
+    let conn_map = safe_lock(&CONNECTION_REGISTRY, "truncate_native conn_map")?;
+
+    let client = conn_map
+        .get(conn_id)
+        .cloned()
+        .ok_or_else(|| rustler::Error::Term(Box::new("Invalid connection ID")))?;
+
+    drop(conn_map); // Release lock before async operation
+
+    // SAFETY: We use TOKIO_RUNTIME.block_on(), which runs the future synchronously on a dedicated
+    // thread pool. This prevents deadlocks that could occur if we were in a true async context
+    // with std::sync::Mutex guards held across await points.
+    #[allow(clippy::await_holding_lock)]
+    TOKIO_RUNTIME.block_on(async {
+        let client_guard = safe_lock_arc(&client, "truncate_native client")?;
+        let conn_guard = safe_lock_arc(&client_guard.client, "truncate_native conn")?;
+        let trx = conn_guard.transaction().await.map_err(|e| {
+            rustler::Error::Term(Box::new(format!("Begin transaction failed: {e}")))
+        })?;
+        // Drop guards after transaction is started - the transaction owns its own connection
+        drop(conn_guard);
+        drop(client_guard);
+
+        match truncate_in_transaction(&trx, table, reset_sequence).await {
+            Ok(deleted) => {
+                trx.commit()
+                    .await
+                    .map_err(|e| rustler::Error::Term(Box::new(format!("Commit failed: {e}"))))?;
+                Ok(deleted)
+            }
+            Err(e) => {
+                let _ = trx.rollback().await;
+                Err(e)
+            }
+        }
+    })
+}
+
+/// Delete all rows of `table` inside `trx` and optionally clear its sequence.
+async fn truncate_in_transaction(
+    trx: &libsql::Transaction,
+    table: &str,
+    reset_sequence: bool,
+) -> Result<u64, rustler::Error> {
+    let deleted = trx
+        .execute(&format!("DELETE FROM {}", quote_identifier(table)), ())
+        .await
+        .map_err(|e| rustler::Error::Term(Box::new(format!("Truncate {table} failed: {e}"))))?;
+
+    if reset_sequence {
+        // sqlite_sequence only exists once some table has used AUTOINCREMENT
+        let mut rows = trx
+            .query(
+                "SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'sqlite_sequence'",
+                (),
+            )
+            .await
+            .map_err(|e| rustler::Error::Term(Box::new(format!("Sequence lookup failed: {e}"))))?;
+
+        let has_sequence_table = rows
+            .next()
+            .await
+            .map_err(|e| rustler::Error::Term(Box::new(format!("Sequence lookup failed: {e}"))))?
+            .is_some();
+        drop(rows);
+
+        if has_sequence_table {
+            trx.execute(
+                "DELETE FROM sqlite_sequence WHERE name = ?1 COLLATE NOCASE",
+                vec![Value::Text(table.to_string())],
+            )
+            .await
+            .map_err(|e| rustler::Error::Term(Box::new(format!("Sequence reset failed: {e}"))))?;
+        }
+    }
+
+    Ok(deleted)
+}
+
+/// Reset the AUTOINCREMENT counter of a table so the next id is `value + 1`.
+///
+/// Updates (or creates) the table's row in `sqlite_sequence`. `SQLite` never hands out
+/// an id at or below the largest existing rowid, so rows still in the table win over a
+/// lower `value`. Errors if the table does not exist or does not use AUTOINCREMENT.
+///
+/// # Arguments
+/// - `conn_id`: Database connection ID
+/// - `table`: Table name
+/// - `value`: New sequence value (non-negative); the next insert gets `value + 1`
+///
+/// Returns `:ok` on success.
+#[rustler::nif(schedule = "DirtyIo")]
+pub fn reset_sequence_native(conn_id: &str, table: &str, value: i64) -> NifResult<Atom> {
+    if table.is_empty() {
+        return Err(rustler::Error::Term(Box::new("Table name cannot be empty")));
+    }
+    if value < 0 {
+        return Err(rustler::Error::Term(Box::new(
+            "Sequence value must be a non-negative integer",
+        )));
+    }
+
+    let conn_map = safe_lock(&CONNECTION_REGISTRY, "reset_sequence_native conn_map")?;
+
+    let client = conn_map
+        .get(conn_id)
+        .cloned()
+        .ok_or_else(|| rustler::Error::Term(Box::new("Invalid connection ID")))?;
+
+    drop(conn_map); // Release lock before async operation
+
+    // SAFETY: We use TOKIO_RUNTIME.block_on(), which runs the future synchronously on a dedicated
+    // thread pool. This prevents deadlocks that could occur if we were in a true async context
+    // with std::sync::Mutex guards held across await points.
+    #[allow(clippy::await_holding_lock)]
+    TOKIO_RUNTIME.block_on(async {
+        let client_guard = safe_lock_arc(&client, "reset_sequence_native client")?;
+        let conn_guard = safe_lock_arc(&client_guard.client, "reset_sequence_native conn")?;
+        let trx = conn_guard.transaction().await.map_err(|e| {
+            rustler::Error::Term(Box::new(format!("Begin transaction failed: {e}")))
+        })?;
+        // Drop guards after transaction is started - the transaction owns its own connection
+        drop(conn_guard);
+        drop(client_guard);
+
+        match reset_sequence_in_transaction(&trx, table, value).await {
+            Ok(()) => {
+                trx.commit()
+                    .await
+                    .map_err(|e| rustler::Error::Term(Box::new(format!("Commit failed: {e}"))))?;
+                Ok(rustler::types::atom::ok())
+            }
+            Err(e) => {
+                let _ = trx.rollback().await;
+                Err(e)
+            }
+        }
+    })
+}
+
+async fn reset_sequence_in_transaction(
+    trx: &libsql::Transaction,
+    table: &str,
+    value: i64,
+) -> Result<(), rustler::Error> {
+    let mut rows = trx
+        .query(
+            "SELECT name, sql FROM sqlite_master WHERE type = 'table' AND name = ?1 COLLATE NOCASE",
+            vec![Value::Text(table.to_string())],
+        )
+        .await
+        .map_err(|e| rustler::Error::Term(Box::new(format!("Table lookup failed: {e}"))))?;
+
+    let row = rows
+        .next()
+        .await
+        .map_err(|e| rustler::Error::Term(Box::new(format!("Table lookup failed: {e}"))))?
+        .ok_or_else(|| rustler::Error::Term(Box::new(format!("Table not found: {table}"))))?;
+
+    // sqlite_sequence stores the name as written in CREATE TABLE
+    let name: String = row
+        .get(0)
+        .map_err(|e| rustler::Error::Term(Box::new(format!("Table lookup failed: {e}"))))?;
+    let create_sql: String = row
+        .get(1)
+        .map_err(|e| rustler::Error::Term(Box::new(format!("Table lookup failed: {e}"))))?;
+    drop(rows);
+
+    if !uses_autoincrement(&create_sql) {
+        return Err(rustler::Error::Term(Box::new(format!(
+            "Table {name} does not use AUTOINCREMENT"
+        ))));
+    }
+
+    let updated = trx
+        .execute(
+            "UPDATE sqlite_sequence SET seq = ?1 WHERE name = ?2",
+            vec![Value::Integer(value), Value::Text(name.clone())],
+        )
+        .await
+        .map_err(|e| rustler::Error::Term(Box::new(format!("Sequence reset failed: {e}"))))?;
+
+    // No row yet if nothing has been inserted since the table was created or truncated
+    if updated == 0 {
+        trx.execute(
+            "INSERT INTO sqlite_sequence (name, seq) VALUES (?1, ?2)",
+            vec![Value::Text(name), Value::Integer(value)],
+        )
+        .await
+        .map_err(|e| rustler::Error::Term(Box::new(format!("Sequence reset failed: {e}"))))?;
+    }
+
+    Ok(())
+}
+
+/// Rebuild indexes with `REINDEX`.
+///
+/// Without a target every index in every attached database is rebuilt. With one,
+/// `SQLite` resolves the name as a collation (rebuilding every index that uses it),
+/// a table (all of its indexes) or a single index. The name is quoted as an
+/// identifier, so it is never interpreted as SQL.
+///
+/// # Arguments
+/// - `conn_id`: Database connection ID
+/// - `target`: Index, table or collation name, or `None` for a global rebuild
+///
+/// Returns `:ok` on success.
+#[rustler::nif(schedule = "DirtyIo")]
+pub fn reindex_native(conn_id: &str, target: Option<String>) -> NifResult<Atom> {
+    let sql = match target.as_deref() {
+        None => "REINDEX".to_string(),
+        Some("") => {
+            return Err(rustler::Error::Term(Box::new(
+                "Reindex target cannot be empty",
+            )))
+        }
+        Some(name) => format!("REINDEX {}", quote_identifier(name)),
+    };
+
+    let conn_map = safe_lock(&CONNECTION_REGISTRY, "reindex_native conn_map")?;
+
+    let client = conn_map
+        .get(conn_id)
+        .cloned()
+        .ok_or_else(|| rustler::Error::Term(Box::new("Invalid connection ID")))?;
+
+    drop(conn_map); // Release lock before async operation
+
+    // SAFETY: We use TOKIO_RUNTIME.block_on(), which runs the future synchronously on a dedicated
+    // thread pool. This prevents deadlocks that could occur if we were in a true async context
+    // with std::sync::Mutex guards held across await points.
+    #[allow(clippy::await_holding_lock)]
+    TOKIO_RUNTIME.block_on(async {
+        let client_guard = safe_lock_arc(&client, "reindex_native client")?;
+        let conn_guard = safe_lock_arc(&client_guard.client, "reindex_native conn")?;
+
+        conn_guard
+            .execute(&sql, ())
+            .await
+            .map_err(|e| rustler::Error::Term(Box::new(format!("Reindex failed: {e}"))))?;
+
+        Ok(rustler::types::atom::ok())
+    })
+}
+
+/// Copy the rows of a SELECT into another table with `INSERT INTO "dest" SELECT ...`.
+///
+/// Without `batch_size` the copy is a single statement. With it, each batch is
+/// committed in its own transaction, keeping transactions (and WAL growth) small.
+///
+/// With a `key` column (one of the SELECT's output columns, ideally indexed), batches
+/// are keyset ranges: each batch finds the largest key among the next `batch_size`
+/// rows after the last one copied and inserts that key range, so the whole copy reads
+/// each source row a constant number of times. Rows with a NULL key are not copied.
+///
+/// Without a key, the SELECT is wrapped as `SELECT * FROM (select_sql) LIMIT n OFFSET m`.
+/// Every batch re-runs the SELECT and skips the rows already copied, so the total
+/// work grows quadratically with the row count (10,000 rows in batches of 100 read
+/// about 500,000). Batches continue until one copies fewer than `batch_size` rows.
+/// Give the SELECT an `ORDER BY` so batches are deterministic. In both modes, don't
+/// read from the destination.
+///
+/// If a batch fails it is rolled back, but earlier batches stay committed; the error
+/// reports how many rows were copied before the failure.
+///
+/// # Arguments
+/// - `conn_id`: Database connection ID
+/// - `dest_table`: Destination table name (quoted automatically)
+/// - `select_sql`: SELECT statement producing rows in the destination's column order
+/// - `args`: Values for the SELECT's placeholders
+/// - `batch_size`: Rows per transaction, or `None` for a single statement
+/// - `key`: Column to batch by, or `None` for `LIMIT`/`OFFSET` batches
+///
+/// Returns the total number of rows inserted.
+#[rustler::nif(schedule = "DirtyIo")]
+pub fn copy_into_native(
+    conn_id: &str,
+    dest_table: &str,
+    select_sql: &str,
+    args: Vec<Term>,
+    batch_size: Option<u64>,
+    key: Option<String>,
+) -> NifResult<u64> {
+    if dest_table.is_empty() {
+        return Err(rustler::Error::Term(Box::new("Table name cannot be empty")));
+    }
+    if batch_size == Some(0) {
+        return Err(rustler::Error::Term(Box::new(
+            "batch_size must be a positive integer",
+        )));
+    }
+    if key.is_some() && batch_size.is_none() {
+        return Err(rustler::Error::Term(Box::new("key requires a batch_size")));
+    }
+    ensure_sql_allowed(conn_id, select_sql)?;
+
+    let mut params: Vec<Value> = args
+        .into_iter()
+        .map(decode_term_to_value)
+        .collect::<Result<_, _>>()
+        .map_err(|e| rustler::Error::Term(Box::new(e)))?;
+    apply_bind_options(conn_id, &mut params)?;
+
+    let select_sql = select_sql.trim().trim_end_matches(';').trim_end();
+    let dest = quote_identifier(dest_table);
+
+    let conn_map = safe_lock(&CONNECTION_REGISTRY, "copy_into_native conn_map")?;
+
+    let client = conn_map
+        .get(conn_id)
+        .cloned()
+        .ok_or_else(|| rustler::Error::Term(Box::new("Invalid connection ID")))?;
+
+    drop(conn_map); // Release lock before async operation
+
+    // SAFETY: We use TOKIO_RUNTIME.block_on(), which runs the future synchronously on a dedicated
+    // thread pool. This prevents deadlocks that could occur if we were in a true async context
+    // with std::sync::Mutex guards held across await points.
+    #[allow(clippy::await_holding_lock)]
+    TOKIO_RUNTIME.block_on(async {
+        let client_guard = safe_lock_arc(&client, "copy_into_native client")?;
+        let conn_guard = safe_lock_arc(&client_guard.client, "copy_into_native conn")?;
+
+        let Some(batch_size) = batch_size else {
+            return conn_guard
+                .execute(&format!("INSERT INTO {dest} {select_sql}"), params)
+                .await
+                .map_err(|e| rustler::Error::Term(Box::new(format!("Copy failed: {e}"))));
+        };
+
+        // Each batch commits its own transaction, which cannot nest in the caller's
+        if !conn_guard.is_autocommit() {
+            return Err(rustler::Error::Term(Box::new(
+                "copy_into with a batch_size cannot run inside a transaction",
+            )));
+        }
+
+        if let Some(key) = key {
+            return copy_by_key(
+                &conn_guard,
+                dest_table,
+                select_sql,
+                &key,
+                params,
+                batch_size,
+            )
+            .await;
+        }
+
+        let mut copied: u64 = 0;
+
+        loop {
+            let sql = format!(
+                "INSERT INTO {dest} SELECT * FROM ({select_sql}) LIMIT {batch_size} OFFSET {copied}"
+            );
+
+            let trx = conn_guard.transaction().await.map_err(|e| {
+                rustler::Error::Term(Box::new(format!("Begin transaction failed: {e}")))
+            })?;
+
+            let inserted = match trx.execute(&sql, params.clone()).await {
+                Ok(inserted) => inserted,
+                Err(e) => {
+                    let _ = trx.rollback().await;
+                    return Err(rustler::Error::Term(Box::new(format!(
+                        "Copy failed after {copied} rows: {e}"
+                    ))));
+                }
+            };
+
+            trx.commit().await.map_err(|e| {
+                rustler::Error::Term(Box::new(format!("Commit failed after {copied} rows: {e}")))
+            })?;
+
+            copied += inserted;
+
+            if inserted < batch_size {
+                return Ok(copied);
+            }
+        }
+    })
+}
+
+/// Copy `select_sql` into `dest_table` in keyset batches over the `key` column.
+///
+/// Each transaction reads the next batch's largest key, then inserts every row up to
+/// it (see `build_copy_batch_sql`). The copy ends when no rows follow the last key.
+async fn copy_by_key(
+    conn: &libsql::Connection,
+    dest_table: &str,
+    select_sql: &str,
+    key: &str,
+    params: Vec<Value>,
+    batch_size: u64,
+) -> NifResult<u64> {
+    let mut copied: u64 = 0;
+    let mut last_key: Option<Value> = None;
+
+    loop {
+        let (bound_sql, insert_sql) =
+            build_copy_batch_sql(dest_table, select_sql, key, last_key.is_some(), batch_size)
+                .map_err(|e| rustler::Error::Term(Box::new(e)))?;
+
+        let mut batch_params = params.clone();
+        batch_params.extend(last_key.clone());
+
+        let trx = conn.transaction().await.map_err(|e| {
+            rustler::Error::Term(Box::new(format!("Begin transaction failed: {e}")))
+        })?;
+
+        let batch = async {
+            let mut rows = trx.query(&bound_sql, batch_params.clone()).await?;
+            let bound = match rows.next().await? {
+                Some(row) => row.get_value(0)?,
+                None => Value::Null,
+            };
+            drop(rows);
+
+            if bound == Value::Null {
+                return Ok(None);
+            }
+
+            batch_params.push(bound.clone());
+            let inserted = trx.execute(&insert_sql, batch_params).await?;
+
+            Ok::<_, libsql::Error>(Some((bound, inserted)))
+        }
+        .await;
+
+        let (bound, inserted) = match batch {
+            Ok(Some(batch)) => batch,
+            Ok(None) => {
+                let _ = trx.rollback().await;
+                return Ok(copied);
+            }
+            Err(e) => {
+                let _ = trx.rollback().await;
+                return Err(rustler::Error::Term(Box::new(format!(
+                    "Copy failed after {copied} rows: {e}"
+                ))));
+            }
+        };
+
+        trx.commit().await.map_err(|e| {
+            rustler::Error::Term(Box::new(format!("Commit failed after {copied} rows: {e}")))
+        })?;
+
+        copied += inserted;
+        last_key = Some(bound);
+    }
+}
+
+/// Write every row of a table to a file as newline-delimited JSON.
+///
+/// Each line is one JSON object keyed by column name. Blobs are written as base64
+/// strings and NULLs as `null`. An existing file at `dest_path` is overwritten; rows
+/// are streamed to disk as they are read, so large tables are not held in memory.
+///
+/// # Arguments
+/// - `conn_id`: Database connection ID
+/// - `table`: Table to dump (quoted automatically)
+/// - `dest_path`: File to write
+///
+/// Returns the number of rows written.
+#[rustler::nif(schedule = "DirtyIo")]
+pub fn dump_table_ndjson_native(conn_id: &str, table: &str, dest_path: &str) -> NifResult<u64> {
+    if table.is_empty() {
+        return Err(rustler::Error::Term(Box::new("Table name cannot be empty")));
+    }
+
+    let sql = format!("SELECT * FROM {}", quote_identifier(table));
+
+    let conn_map = safe_lock(&CONNECTION_REGISTRY, "dump_table_ndjson_native conn_map")?;
+
+    let client = conn_map
+        .get(conn_id)
+        .cloned()
+        .ok_or_else(|| rustler::Error::Term(Box::new("Invalid connection ID")))?;
+
+    drop(conn_map); // Release lock before async operation
+
+    // SAFETY: We use TOKIO_RUNTIME.block_on(), which runs the future synchronously on a dedicated
+    // thread pool. This prevents deadlocks that could occur if we were in a true async context
+    // with std::sync::Mutex guards held across await points.
+    #[allow(clippy::await_holding_lock)]
+    TOKIO_RUNTIME.block_on(async {
+        let client_guard = safe_lock_arc(&client, "dump_table_ndjson_native client")?;
+        let conn_guard = safe_lock_arc(&client_guard.client, "dump_table_ndjson_native conn")?;
+
+        let mut rows = conn_guard
+            .query(&sql, ())
+            .await
+            .map_err(|e| rustler::Error::Term(Box::new(format!("Query failed: {e}"))))?;
+
+        let columns: Vec<String> = (0..rows.column_count())
+            .map(|i| rows.column_name(i).unwrap_or_default().to_string())
+            .collect();
+
+        let write_error =
+            |e: std::io::Error| rustler::Error::Term(Box::new(format!("Write failed: {e}")));
+
+        let mut out = BufWriter::new(File::create(dest_path).map_err(|e| {
+            rustler::Error::Term(Box::new(format!("Failed to create {dest_path}: {e}")))
+        })?);
+        let mut written: u64 = 0;
+
+        while let Some(row) = rows
+            .next()
+            .await
+            .map_err(|e| rustler::Error::Term(Box::new(e.to_string())))?
+        {
+            let values: Vec<Value> = (0..columns.len())
+                .map(|i| row.get(i as i32).unwrap_or(Value::Null))
+                .collect();
+
+            writeln!(out, "{}", row_to_json(&columns, &values)).map_err(write_error)?;
+            written += 1;
+        }
+
+        out.flush().map_err(write_error)?;
+
+        Ok(written)
+    })
+}
+
+/// Run a query and write its result to a new database file as a single table.
+///
+/// The whole result is read first, because each column's declared type is inferred
+/// from its first non-NULL value (see `build_materialize_sql`). The destination
+/// must not exist yet; it is created, the table is filled inside one transaction,
+/// and the file is removed again if anything fails. Duplicate column names (common
+/// in joins) must be aliased apart in the query.
+///
+/// # Arguments
+/// - `conn_id`: Database connection ID to run the query on
+/// - `sql`: Query whose result is materialised
+/// - `args`: Values for the query's placeholders
+/// - `dest_path`: Database file to create
+/// - `table`: Name of the table to create in it (quoted automatically)
+///
+/// Returns the number of rows written.
+#[rustler::nif(schedule = "DirtyIo")]
+pub fn materialize_to_db_native(
+    conn_id: &str,
+    sql: &str,
+    args: Vec<Term>,
+    dest_path: &str,
+    table: &str,
+) -> NifResult<u64> {
+    ensure_sql_allowed(conn_id, sql)?;
+
+    if std::path::Path::new(dest_path).exists() {
+        return Err(rustler::Error::Term(Box::new(format!(
+            "Destination already exists: {dest_path}"
+        ))));
+    }
+
+    let mut params: Vec<Value> = args
+        .into_iter()
+        .map(decode_term_to_value)
+        .collect::<Result<_, _>>()
+        .map_err(|e| rustler::Error::Term(Box::new(e)))?;
+    apply_bind_options(conn_id, &mut params)?;
+
+    let conn_map = safe_lock(&CONNECTION_REGISTRY, "materialize_to_db_native conn_map")?;
+
+    let client = conn_map
+        .get(conn_id)
+        .cloned()
+        .ok_or_else(|| rustler::Error::Term(Box::new("Invalid connection ID")))?;
+
+    drop(conn_map); // Release lock before async operation
+
+    // SAFETY: We use TOKIO_RUNTIME.block_on(), which runs the future synchronously on a dedicated
+    // thread pool. This prevents deadlocks that could occur if we were in a true async context
+    // with std::sync::Mutex guards held across await points.
+    #[allow(clippy::await_holding_lock)]
+    TOKIO_RUNTIME.block_on(async {
+        let (columns, rows) = {
+            let client_guard = safe_lock_arc(&client, "materialize_to_db_native client")?;
+            let conn_guard = safe_lock_arc(&client_guard.client, "materialize_to_db_native conn")?;
+
+            let mut result = conn_guard
+                .query(sql, params)
+                .await
+                .map_err(|e| rustler::Error::Term(Box::new(format!("Query failed: {e}"))))?;
+
+            let columns: Vec<String> = (0..result.column_count())
+                .map(|i| result.column_name(i).unwrap_or_default().to_string())
+                .collect();
+            let mut rows: Vec<Vec<Value>> = Vec::new();
+
+            while let Some(row) = result
+                .next()
+                .await
+                .map_err(|e| rustler::Error::Term(Box::new(e.to_string())))?
+            {
+                rows.push(
+                    (0..columns.len())
+                        .map(|i| row.get(i as i32).unwrap_or(Value::Null))
+                        .collect(),
+                );
+            }
+
+            (columns, rows)
+        }; // Source connection is released before the destination is written
+
+        let (create_sql, insert_sql) = build_materialize_sql(table, &columns, &rows)
+            .map_err(|e| rustler::Error::Term(Box::new(e)))?;
+
+        let written = write_materialized(dest_path, &create_sql, &insert_sql, rows).await;
+
+        if written.is_err() {
+            let _ = std::fs::remove_file(dest_path);
+        }
+
+        written.map_err(|e| {
+            rustler::Error::Term(Box::new(format!("Failed to write {dest_path}: {e}")))
+        })
+    })
+}
+
+/// Create the database at `dest_path` and fill one table in a single transaction.
+async fn write_materialized(
+    dest_path: &str,
+    create_sql: &str,
+    insert_sql: &str,
+    rows: Vec<Vec<Value>>,
+) -> Result<u64, libsql::Error> {
+    let db = Builder::new_local(dest_path).build().await?;
+    let conn = db.connect()?;
+
+    conn.execute(create_sql, ()).await?;
+
+    let trx = conn.transaction().await?;
+    let stmt = trx.prepare(insert_sql).await?;
+    let mut written: u64 = 0;
+
+    for row in rows {
+        stmt.execute(row).await?;
+        stmt.reset();
+        written += 1;
+    }
+
+    drop(stmt);
+    trx.commit().await?;
+
+    Ok(written)
+}
+
+/// Execute a statement with one parameter bound to the contents of a file as a blob.
+///
+/// The `:file` atom in `args` marks the parameter to replace. The file is read in
+/// native code, so large binaries already on disk never pass through the BEAM.
+/// `LibSQL` has no incremental blob I/O (and keeps the raw `sqlite3` handle that
+/// `sqlite3_blob_write` needs private), so the bytes are held in native memory once
+/// while the statement runs. Files larger than `max_bytes` are rejected before they
+/// are read, which bounds that memory.
+///
+/// # Arguments
+/// - `conn_id`: Database connection ID
+/// - `sql`: Statement to execute, usually an INSERT or UPDATE
+/// - `args`: Parameters, with exactly one `:file` placeholder
+/// - `path`: File whose bytes are bound at the placeholder
+/// - `max_bytes`: Largest file size accepted
+///
+/// Returns the number of rows affected.
+#[rustler::nif(schedule = "DirtyIo")]
+pub fn execute_blob_from_file_native(
+    conn_id: &str,
+    sql: &str,
+    args: Vec<Term>,
+    path: &str,
+    max_bytes: u64,
+) -> NifResult<u64> {
+    ensure_sql_allowed(conn_id, sql)?;
+
+    let is_placeholder = |term: &Term| term.decode::<Atom>().is_ok_and(|atom| atom == file());
+
+    let placeholders: Vec<usize> = args
+        .iter()
+        .enumerate()
+        .filter(|(_, term)| is_placeholder(term))
+        .map(|(i, _)| i)
+        .collect();
+
+    let [placeholder] = placeholders[..] else {
+        return Err(rustler::Error::Term(Box::new(
+            "params must contain exactly one :file placeholder",
+        )));
+    };
+
+    let metadata = std::fs::metadata(path)
+        .map_err(|e| rustler::Error::Term(Box::new(format!("Cannot read {path}: {e}"))))?;
+    if !metadata.is_file() {
+        return Err(rustler::Error::Term(Box::new(format!(
+            "Cannot read {path}: not a regular file"
+        ))));
+    }
+    if metadata.len() > max_bytes {
+        return Err(rustler::Error::Term(Box::new(format!(
+            "{path} is {} bytes, over the {max_bytes} byte limit",
+            metadata.len()
+        ))));
+    }
+
+    let mut params: Vec<Value> = args
+        .into_iter()
+        .enumerate()
+        .map(|(i, term)| {
+            if i == placeholder {
+                Ok(Value::Null) // Replaced with the file contents below
+            } else {
+                decode_term_to_value(term)
+            }
+        })
+        .collect::<Result<_, _>>()
+        .map_err(|e| rustler::Error::Term(Box::new(e)))?;
+    apply_bind_options(conn_id, &mut params)?;
+
+    // Read at most one byte past the limit, so a file that grew since the size check
+    // is still caught without loading all of it
+    let mut contents = Vec::new();
+    File::open(path)
+        .and_then(|file| {
+            file.take(max_bytes.saturating_add(1))
+                .read_to_end(&mut contents)
+        })
+        .map_err(|e| rustler::Error::Term(Box::new(format!("Cannot read {path}: {e}"))))?;
+    if contents.len() as u64 > max_bytes {
+        return Err(rustler::Error::Term(Box::new(format!(
+            "{path} is over the {max_bytes} byte limit"
+        ))));
+    }
+    params[placeholder] = Value::Blob(contents);
+
+    let conn_map = safe_lock(
+        &CONNECTION_REGISTRY,
+        "execute_blob_from_file_native conn_map",
+    )?;
+
+    let client = conn_map
+        .get(conn_id)
+        .cloned()
+        .ok_or_else(|| rustler::Error::Term(Box::new("Invalid connection ID")))?;
+
+    drop(conn_map); // Release lock before async operation
+
+    // SAFETY: We use TOKIO_RUNTIME.block_on(), which runs the future synchronously on a dedicated
+    // thread pool. This prevents deadlocks that could occur if we were in a true async context
+    // with std::sync::Mutex guards held across await points.
+    #[allow(clippy::await_holding_lock)]
+    TOKIO_RUNTIME.block_on(async {
+        let client_guard = safe_lock_arc(&client, "execute_blob_from_file_native client")?;
+        let conn_guard = safe_lock_arc(&client_guard.client, "execute_blob_from_file_native conn")?;
+
+        conn_guard
+            .execute(sql, params)
+            .await
+            .map_err(|e| rustler::Error::Term(Box::new(format!("Execute failed: {e}"))))
+    })
+}
@@ -4,10 +4,10 @@
 /// and connection state management including cleanup and timeouts.
 use crate::constants::*;
 use crate::decode;
-use crate::models::{LibSQLConn, Mode};
+use crate::models::{BusyPolicy, LibSQLConn, Mode, ReplicaMetrics};
 use crate::utils::safe_lock_arc;
 use bytes::Bytes;
-use libsql::{Builder, Cipher, EncryptionConfig, EncryptionContext, EncryptionKey};
+use libsql::{Builder, Cipher, EncryptionConfig, EncryptionContext, EncryptionKey, OpenFlags};
 use rustler::{Atom, NifResult, Term};
 use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
@@ -27,6 +27,29 @@ use uuid::Uuid;
 /// - `auth_token` - Authentication token (required for `remote`/`remote_replica` modes)
 /// - `encryption_key` - Optional local encryption key for local database encryption at rest (`local`/`remote_replica` modes)
 /// - `remote_encryption_key` - Optional remote encryption key for Turso encrypted databases (`remote`/`remote_replica` modes)
+/// - `default_transaction_mode` - Optional behaviour (`:deferred`, `:immediate`, `:exclusive`, `:read_only`)
+///   for transactions begun without an explicit mode; defaults to `:deferred`
+/// - `read_only` - Open the database read-only (`local` mode only). Writes fail in `SQLite` and
+///   state-changing pragmas are rejected with `{:error, :read_only}`
+/// - `empty_string_as_null` - Bind empty text parameters (`""`) as `NULL`; defaults to `false`
+/// - `disable_statement_cache` - Prepare and finalise a fresh statement on every use instead of
+///   caching compiled statements in `STMT_REGISTRY`; defaults to `false`
+/// - `auto_reconnect` - Rebuild a dropped connection from the stored database handle and retry
+///   the failed query once (`remote` mode only); defaults to `false`
+/// - `max_sql_length` - Optional limit in bytes; longer SQL is rejected with
+///   `{:error, :sql_too_long}` before it is prepared. Unlimited by default
+/// - `tag` - Optional label such as a tenant name, reported by `connection_info_native` and
+///   matched by `list_connections_native`
+/// - `optimize_on_close` - Run `PRAGMA optimize` when the connection is closed; defaults to
+///   `false`. Not supported with `read_only`
+/// - `secure_delete` - Optional `:on`, `:off` or `:fast`, applied with `PRAGMA secure_delete`
+///   before the connection is returned
+/// - `threads` - Optional non-negative limit on auxiliary sorter threads, applied with
+///   `PRAGMA threads` before the connection is returned
+/// - `local_replica` - Test-only, and only accepted in builds with the `test-hooks`
+///   feature: open `database` as an embedded replica with no primary (`local` mode
+///   only); defaults to `false`. It exists to exercise the `{:error, :replica_read_only}`
+///   path and is not a supported deployment mode. Not supported with `encryption_key`
 ///
 /// **Encryption Support**:
 /// - **Local encryption**: Uses AES-256-CBC for local database files (via `encryption_key`)
@@ -62,6 +85,111 @@ pub fn connect(opts: Term, mode: Term) -> NifResult<String> {
     let remote_encryption_key = map
         .get("remote_encryption_key")
         .and_then(|t| t.decode::<String>().ok());
+    let default_transaction_mode = match map.get("default_transaction_mode") {
+        Some(term) => {
+            let atom: Atom = term.decode().map_err(|_| {
+                rustler::Error::Term(Box::new("default_transaction_mode must be an atom"))
+            })?;
+            decode::decode_transaction_behavior(atom).ok_or_else(|| {
+                rustler::Error::Term(Box::new(format!(
+                    "Invalid default_transaction_mode: {atom:?}. Use :deferred, :immediate, :exclusive, or :read_only"
+                )))
+            })?;
+            atom
+        }
+        None => deferred(),
+    };
+    let read_only = match map.get("read_only") {
+        Some(term) => term
+            .decode::<bool>()
+            .map_err(|_| rustler::Error::Term(Box::new("read_only must be a boolean")))?,
+        None => false,
+    };
+
+    let empty_string_as_null = match map.get("empty_string_as_null") {
+        Some(term) => term.decode::<bool>().map_err(|_| {
+            rustler::Error::Term(Box::new("empty_string_as_null must be a boolean"))
+        })?,
+        None => false,
+    };
+    let statement_cache_disabled = match map.get("disable_statement_cache") {
+        Some(term) => term.decode::<bool>().map_err(|_| {
+            rustler::Error::Term(Box::new("disable_statement_cache must be a boolean"))
+        })?,
+        None => false,
+    };
+    let auto_reconnect = match map.get("auto_reconnect") {
+        Some(term) => term
+            .decode::<bool>()
+            .map_err(|_| rustler::Error::Term(Box::new("auto_reconnect must be a boolean")))?,
+        None => false,
+    };
+    let max_sql_length = match map.get("max_sql_length") {
+        Some(term) => match term.decode::<Option<usize>>() {
+            Ok(Some(0)) | Err(_) => {
+                return Err(rustler::Error::Term(Box::new(
+                    "max_sql_length must be a positive integer or nil",
+                )))
+            }
+            Ok(limit) => limit,
+        },
+        None => None,
+    };
+    let tag = match map.get("tag") {
+        Some(term) => term
+            .decode::<Option<String>>()
+            .map_err(|_| rustler::Error::Term(Box::new("tag must be a string")))?,
+        None => None,
+    };
+    let optimize_on_close = match map.get("optimize_on_close") {
+        Some(term) => term
+            .decode::<bool>()
+            .map_err(|_| rustler::Error::Term(Box::new("optimize_on_close must be a boolean")))?,
+        None => false,
+    };
+    if optimize_on_close && read_only {
+        return Err(rustler::Error::Term(Box::new(
+            "optimize_on_close is not supported for read_only connections",
+        )));
+    }
+    #[cfg(feature = "test-hooks")]
+    let local_replica = match map.get("local_replica") {
+        Some(term) => term
+            .decode::<bool>()
+            .map_err(|_| rustler::Error::Term(Box::new("local_replica must be a boolean")))?,
+        None => false,
+    };
+    #[cfg(not(feature = "test-hooks"))]
+    let local_replica = if map.contains_key("local_replica") {
+        return Err(rustler::Error::Term(Box::new(
+            "local_replica requires a build with the test-hooks feature",
+        )));
+    } else {
+        false
+    };
+    if local_replica && encryption_key.is_some() {
+        return Err(rustler::Error::Term(Box::new(
+            "encryption_key is not supported for local_replica connections",
+        )));
+    }
+    let secure_delete = match map.get("secure_delete") {
+        Some(term) => {
+            let atom: Atom = term
+                .decode()
+                .map_err(|_| rustler::Error::Term(Box::new("secure_delete must be an atom")))?;
+            Some(decode::decode_secure_delete(atom).ok_or_else(|| {
+                rustler::Error::Term(Box::new(format!(
+                    "Invalid secure_delete: {atom:?}. Use :on, :off, or :fast"
+                )))
+            })?)
+        }
+        None => None,
+    };
+
+    let threads = map
+        .get("threads")
+        .map(|term| decode_thread_limit(*term))
+        .transpose()?;
 
     // Wrap the entire connection process with a timeout using the global runtime.
     TOKIO_RUNTIME.block_on(async {
@@ -75,6 +203,24 @@ pub fn connect(opts: Term, mode: Term) -> NifResult<String> {
             let mode_enum = decode::decode_mode(mode_atom)
                 .ok_or_else(|| rustler::Error::Term(Box::new("Unknown mode")))?;
 
+            if read_only && mode_enum != Mode::Local {
+                return Err(rustler::Error::Term(Box::new(
+                    "read_only is only supported for local connections",
+                )));
+            }
+
+            if auto_reconnect && mode_enum != Mode::Remote {
+                return Err(rustler::Error::Term(Box::new(
+                    "auto_reconnect is only supported for remote connections",
+                )));
+            }
+
+            if local_replica && mode_enum != Mode::Local {
+                return Err(rustler::Error::Term(Box::new(
+                    "local_replica is only supported for local connections",
+                )));
+            }
+
             let db = match mode_enum {
                 Mode::RemoteReplica => {
                     let url = url.ok_or_else(|| rustler::Error::BadArg)?;
@@ -118,11 +264,28 @@ pub fn connect(opts: Term, mode: Term) -> NifResult<String> {
 
                     builder.build().await
                 }
+                #[cfg(feature = "test-hooks")]
+                Mode::Local if local_replica => {
+                    let dbname = dbname.ok_or_else(|| rustler::Error::BadArg)?;
+
+                    // A replica without a primary: libsql rejects writes instead of forwarding them
+                    let mut builder = Builder::new_local_replica(dbname);
+
+                    if read_only {
+                        builder = builder.flags(OpenFlags::SQLITE_OPEN_READ_ONLY);
+                    }
+
+                    builder.build().await
+                }
                 Mode::Local => {
                     let dbname = dbname.ok_or_else(|| rustler::Error::BadArg)?;
 
                     let mut builder = Builder::new_local(dbname);
 
+                    if read_only {
+                        builder = builder.flags(OpenFlags::SQLITE_OPEN_READ_ONLY);
+                    }
+
                     if let Some(key) = encryption_key {
                         let config = EncryptionConfig {
                             cipher: Cipher::Aes256Cbc,
@@ -147,9 +310,32 @@ pub fn connect(opts: Term, mode: Term) -> NifResult<String> {
                     .map_err(|e| rustler::Error::Term(Box::new(format!("Failed ping: {e}"))))?;
             }
 
+            if let Some(value) = secure_delete {
+                apply_secure_delete(&conn, value)
+                    .await
+                    .map_err(|e| rustler::Error::Term(Box::new(e)))?;
+            }
+
+            if let Some(limit) = threads {
+                apply_threads(&conn, limit)
+                    .await
+                    .map_err(|e| rustler::Error::Term(Box::new(e)))?;
+            }
+
             let libsql_conn = Arc::new(Mutex::new(LibSQLConn {
                 db,
                 client: Arc::new(Mutex::new(conn)),
+                mode: mode_enum,
+                default_transaction_mode,
+                read_only,
+                empty_string_as_null,
+                statement_cache_disabled,
+                auto_reconnect,
+                max_sql_length,
+                tag,
+                optimize_on_close,
+                busy_backoff: None,
+                replica_metrics: ReplicaMetrics::default(),
             }));
 
             let conn_id = Uuid::new_v4().to_string();
@@ -170,6 +356,124 @@ pub fn connect(opts: Term, mode: Term) -> NifResult<String> {
     })
 }
 
+/// Describe an open connection.
+///
+/// # Arguments
+/// - `conn_id`: Database connection ID
+///
+/// Returns `{mode, tag, read_only, auto_reconnect}`, where `tag` is `nil` unless the
+/// connection was opened with the `tag` connect option.
+#[rustler::nif(schedule = "DirtyIo")]
+pub fn connection_info_native(conn_id: &str) -> NifResult<(Atom, Option<String>, bool, bool)> {
+    let client = crate::utils::safe_lock(&CONNECTION_REGISTRY, "connection_info_native conn_map")?
+        .get(conn_id)
+        .cloned()
+        .ok_or_else(|| rustler::Error::Term(Box::new("Invalid connection ID")))?;
+
+    let client_guard = safe_lock_arc(&client, "connection_info_native client")?;
+    let mode = match client_guard.mode {
+        Mode::Local => local(),
+        Mode::Remote => remote(),
+        Mode::RemoteReplica => remote_replica(),
+    };
+
+    Ok((
+        mode,
+        client_guard.tag.clone(),
+        client_guard.read_only,
+        client_guard.auto_reconnect,
+    ))
+}
+
+/// List open connections, optionally only those opened with a given `tag`.
+///
+/// The registry lock is released before each connection is inspected, so a connection
+/// busy with a long operation delays this call but never blocks other connections.
+///
+/// # Arguments
+/// - `tag`: Only return connections with this tag, or `nil` for all connections
+///
+/// Returns `{conn_id, tag}` pairs sorted by connection ID.
+#[rustler::nif(schedule = "DirtyIo")]
+pub fn list_connections_native(tag: Option<String>) -> NifResult<Vec<(String, Option<String>)>> {
+    let clients: Vec<_> =
+        crate::utils::safe_lock(&CONNECTION_REGISTRY, "list_connections_native conn_map")?
+            .iter()
+            .map(|(conn_id, client)| (conn_id.clone(), client.clone()))
+            .collect();
+
+    let mut connections = Vec::with_capacity(clients.len());
+    for (conn_id, client) in clients {
+        let conn_tag = safe_lock_arc(&client, "list_connections_native client")?
+            .tag
+            .clone();
+        if tag.is_none() || conn_tag == tag {
+            connections.push((conn_id, conn_tag));
+        }
+    }
+
+    connections.sort();
+    Ok(connections)
+}
+
+/// Check that `key` decrypts an existing encrypted local database.
+///
+/// Opens `path` read-only with the key, reads `sqlite_schema` and closes it again, so a
+/// pool can reject a wrong key before handing out connections. The file is never created.
+///
+/// # Arguments
+/// - `path`: Path to the encrypted database file
+/// - `key`: Encryption key to try
+/// - `cipher`: Cipher the database was encrypted with; only `:aes256cbc` is supported
+///
+/// Returns `:ok` if the key works, `{:error, :invalid_encryption_key}` if it does not,
+/// or an error string if the file is missing or cannot be read for another reason.
+#[rustler::nif(schedule = "DirtyIo")]
+pub fn verify_encryption_key_native(path: &str, key: &str, cipher: Atom) -> NifResult<Atom> {
+    let cipher = decode::decode_cipher(cipher).ok_or_else(|| {
+        rustler::Error::Term(Box::new(format!(
+            "Invalid cipher: {cipher:?}. Use :aes256cbc"
+        )))
+    })?;
+
+    if !std::path::Path::new(path).is_file() {
+        return Err(rustler::Error::Term(Box::new(format!(
+            "Database file not found: {path}"
+        ))));
+    }
+
+    let classify = |e: libsql::Error| match e {
+        // SQLITE_NOTADB: the first page does not decrypt to a valid header
+        libsql::Error::SqliteFailure(code, _) if code & 0xff == 26 => {
+            rustler::Error::Term(Box::new(invalid_encryption_key()))
+        }
+        other => rustler::Error::Term(Box::new(format!(
+            "Failed to verify encryption key: {other}"
+        ))),
+    };
+
+    TOKIO_RUNTIME.block_on(async {
+        let db = Builder::new_local(path)
+            .flags(OpenFlags::SQLITE_OPEN_READ_ONLY)
+            .encryption_config(EncryptionConfig {
+                cipher,
+                encryption_key: Bytes::from(key.to_string()),
+            })
+            .build()
+            .await
+            .map_err(classify)?;
+        let conn = db.connect().map_err(classify)?;
+
+        let mut rows = conn
+            .query("SELECT count(*) FROM sqlite_schema", ())
+            .await
+            .map_err(classify)?;
+        rows.next().await.map_err(classify)?;
+
+        Ok(rustler::types::atom::ok())
+    })
+}
+
 /// Check if a database connection is alive and responsive.
 ///
 /// Performs a simple `SELECT 1` query to verify the connection is working.
@@ -210,7 +514,8 @@ pub fn ping(conn_id: &str) -> NifResult<bool> {
 /// Close a resource (connection, transaction, statement, or cursor).
 ///
 /// The `opt` parameter specifies which type of resource to close:
-/// - `:conn_id` - Close a database connection
+/// - `:conn_id` - Close a database connection, first running `PRAGMA optimize` if it was
+///   opened with `optimize_on_close`
 /// - `:trx_id` - Close/forget a transaction
 /// - `:stmt_id` - Close a prepared statement
 /// - `:cursor_id` - Close a cursor
@@ -221,7 +526,16 @@ pub fn close(id: &str, opt: Atom) -> NifResult<Atom> {
     if opt == conn_id() {
         let removed = crate::utils::safe_lock(&CONNECTION_REGISTRY, "close conn")?.remove(id);
         match removed {
-            Some(_) => Ok(rustler::types::atom::ok()),
+            Some(client) => {
+                // Best effort: the connection is already closed, so don't fail over leftovers
+                if let Ok(mut uncached) =
+                    crate::utils::safe_lock(&UNCACHED_STMT_REGISTRY, "close conn uncached")
+                {
+                    uncached.retain(|_, (owner, _)| owner != id);
+                }
+                optimize_before_close(&client);
+                Ok(rustler::types::atom::ok())
+            }
             None => Err(rustler::Error::Term(Box::new("Connection not found"))),
         }
     } else if opt == trx_id() {
@@ -232,9 +546,10 @@ pub fn close(id: &str, opt: Atom) -> NifResult<Atom> {
         }
     } else if opt == stmt_id() {
         let removed = crate::utils::safe_lock(&STMT_REGISTRY, "close stmt")?.remove(id);
-        match removed {
-            Some(_) => Ok(rustler::types::atom::ok()),
-            None => Err(rustler::Error::Term(Box::new("Statement not found"))),
+        if removed.is_some() || crate::utils::forget_uncached_statement(id)? {
+            Ok(rustler::types::atom::ok())
+        } else {
+            Err(rustler::Error::Term(Box::new("Statement not found")))
         }
     } else if opt == cursor_id() {
         let removed = crate::utils::safe_lock(&CURSOR_REGISTRY, "close cursor")?.remove(id);
@@ -247,6 +562,30 @@ pub fn close(id: &str, opt: Atom) -> NifResult<Atom> {
     }
 }
 
+/// Run `PRAGMA optimize` on a connection that is being closed, if it asked for it.
+///
+/// Best effort: the connection is already out of the registry, so a failure here must
+/// not stop it from closing.
+fn optimize_before_close(client: &Arc<Mutex<LibSQLConn>>) {
+    // SAFETY: We're inside TOKIO_RUNTIME.block_on(), so this is synchronous execution.
+    // The std::sync::Mutex guards are safe to hold across await points here because
+    // we're not in a true async context - block_on runs the future to completion.
+    #[allow(clippy::await_holding_lock)]
+    TOKIO_RUNTIME.block_on(async {
+        let Ok(client_guard) = safe_lock_arc(client, "close optimize client") else {
+            return;
+        };
+        if !client_guard.optimize_on_close {
+            return;
+        }
+        let Ok(conn_guard) = safe_lock_arc(&client_guard.client, "close optimize conn") else {
+            return;
+        };
+
+        let _ = apply_optimize(&conn_guard, None).await;
+    });
+}
+
 /// Set the busy timeout for a database connection.
 ///
 /// Controls how long `SQLite` waits for locks before returning `SQLITE_BUSY`.
@@ -257,6 +596,8 @@ pub fn close(id: &str, opt: Atom) -> NifResult<Atom> {
 /// - `conn_id`: Database connection ID
 /// - `timeout_ms`: Timeout in milliseconds
 ///
+/// Replaces any busy policy set with `set_busy_handler_native`.
+///
 /// Returns `:ok` on success, error on failure.
 #[rustler::nif(schedule = "DirtyIo")]
 pub fn set_busy_timeout(conn_id: &str, timeout_ms: u64) -> NifResult<Atom> {
@@ -267,13 +608,17 @@ pub fn set_busy_timeout(conn_id: &str, timeout_ms: u64) -> NifResult<Atom> {
         drop(conn_map); // Release lock before blocking operation
 
         let result = TOKIO_RUNTIME.block_on(async {
-            let client_guard = safe_lock_arc(&client, "set_busy_timeout client")?;
+            let mut client_guard = safe_lock_arc(&client, "set_busy_timeout client")?;
             let conn_guard: std::sync::MutexGuard<libsql::Connection> =
                 safe_lock_arc(&client_guard.client, "set_busy_timeout conn")?;
 
             conn_guard
                 .busy_timeout(Duration::from_millis(timeout_ms))
-                .map_err(|e| rustler::Error::Term(Box::new(format!("busy_timeout failed: {e}"))))
+                .map_err(|e| rustler::Error::Term(Box::new(format!("busy_timeout failed: {e}"))))?;
+            drop(conn_guard);
+
+            client_guard.busy_backoff = None;
+            Ok(())
         });
 
         match result {
@@ -285,6 +630,311 @@ pub fn set_busy_timeout(conn_id: &str, timeout_ms: u64) -> NifResult<Atom> {
     }
 }
 
+/// Set how a connection behaves when the database is locked by another connection.
+///
+/// - `{:timeout, ms}` - `SQLite` retries for up to `ms` milliseconds, the same as
+///   `set_busy_timeout`
+/// - `:fail_fast` - `SQLITE_BUSY` is returned at once
+/// - `{:backoff, base_ms, max_ms}` - `SQLite` waits up to `max_ms` milliseconds with its
+///   own increasing poll delays, and statements run through `query_args` outside a
+///   transaction that still get `SQLITE_BUSY` are retried after `base_ms`, doubling the
+///   delay each time, until `max_ms` have passed since the first attempt
+///
+/// libsql keeps the raw `sqlite3` handle private, so `sqlite3_busy_handler` cannot be
+/// registered. The busy timeout gives every path (prepared statements, batches, cursors,
+/// pipelines, transactions) the `max_ms` wait, and the statement retry covers the cases
+/// where `SQLite` returns `SQLITE_BUSY` without calling its handler. Statements inside a
+/// transaction are never retried, as retrying there can deadlock.
+///
+/// # Arguments
+/// - `conn_id`: Database connection ID
+/// - `policy`: Busy policy term
+///
+/// Returns `:ok` on success, error on failure.
+#[rustler::nif(schedule = "DirtyIo")]
+pub fn set_busy_handler_native(conn_id: &str, policy: Term) -> NifResult<Atom> {
+    let policy = decode::decode_busy_policy(policy).ok_or_else(|| {
+        rustler::Error::Term(Box::new(format!(
+            "Invalid busy policy: {policy:?}. Use {{:timeout, ms}}, :fail_fast or {{:backoff, base_ms, max_ms}} with base_ms > 0"
+        )))
+    })?;
+
+    let (timeout_ms, busy_backoff) = match policy {
+        BusyPolicy::Timeout(ms) => (ms, None),
+        BusyPolicy::FailFast => (0, None),
+        BusyPolicy::Backoff(backoff) => (backoff.max_ms, Some(backoff)),
+    };
+
+    let client = {
+        let conn_map =
+            crate::utils::safe_lock(&CONNECTION_REGISTRY, "set_busy_handler_native conn_map")?;
+        conn_map
+            .get(conn_id)
+            .cloned()
+            .ok_or_else(|| rustler::Error::Term(Box::new("Invalid connection ID")))?
+    };
+
+    let mut client_guard = safe_lock_arc(&client, "set_busy_handler_native client")?;
+    safe_lock_arc(&client_guard.client, "set_busy_handler_native conn")?
+        .busy_timeout(Duration::from_millis(timeout_ms))
+        .map_err(|e| rustler::Error::Term(Box::new(format!("busy_timeout failed: {e}"))))?;
+    client_guard.busy_backoff = busy_backoff;
+
+    Ok(rustler::types::atom::ok())
+}
+
+/// Run `PRAGMA secure_delete = <value>` on a connection.
+///
+/// The pragma echoes the new setting as a row, so it is issued with `query`.
+async fn apply_secure_delete(conn: &libsql::Connection, value: &str) -> Result<(), String> {
+    conn.query(&format!("PRAGMA secure_delete = {value}"), ())
+        .await
+        .map(|_| ())
+        .map_err(|e| format!("Failed to set secure_delete: {e}"))
+}
+
+/// Set the `secure_delete` mode for a database connection.
+///
+/// With `:on`, `SQLite` overwrites deleted content with zeros; `:fast` only does so
+/// when it costs no extra I/O; `:off` leaves deleted content in the file.
+///
+/// # Arguments
+/// - `conn_id`: Database connection ID
+/// - `mode`: One of `:on`, `:off`, `:fast`
+///
+/// Returns `:ok` on success, error on an unknown mode or failure.
+#[rustler::nif(schedule = "DirtyIo")]
+pub fn set_secure_delete_native(conn_id: &str, mode: Atom) -> NifResult<Atom> {
+    let value = decode::decode_secure_delete(mode).ok_or_else(|| {
+        rustler::Error::Term(Box::new(format!(
+            "Invalid secure_delete mode: {mode:?}. Use :on, :off, or :fast"
+        )))
+    })?;
+
+    let conn_map = crate::utils::safe_lock(&CONNECTION_REGISTRY, "set_secure_delete conn_map")?;
+
+    let Some(client) = conn_map.get(conn_id).cloned() else {
+        return Err(rustler::Error::Term(Box::new("Invalid connection ID")));
+    };
+    drop(conn_map); // Release lock before async operation
+
+    // SAFETY: We're inside TOKIO_RUNTIME.block_on(), so this is synchronous execution.
+    // The std::sync::Mutex guards are safe to hold across await points here because
+    // we're not in a true async context - block_on runs the future to completion.
+    #[allow(clippy::await_holding_lock)]
+    TOKIO_RUNTIME.block_on(async {
+        let client_guard = safe_lock_arc(&client, "set_secure_delete client")?;
+        let conn_guard: std::sync::MutexGuard<libsql::Connection> =
+            safe_lock_arc(&client_guard.client, "set_secure_delete conn")?;
+
+        apply_secure_delete(&conn_guard, value)
+            .await
+            .map_err(|e| rustler::Error::Term(Box::new(e)))
+    })?;
+
+    Ok(rustler::types::atom::ok())
+}
+
+/// Read the current `secure_delete` mode of a database connection.
+///
+/// Returns `:off`, `:on` or `:fast`.
+#[rustler::nif(schedule = "DirtyIo")]
+pub fn get_secure_delete_native(conn_id: &str) -> NifResult<Atom> {
+    let conn_map = crate::utils::safe_lock(&CONNECTION_REGISTRY, "get_secure_delete conn_map")?;
+
+    let Some(client) = conn_map.get(conn_id).cloned() else {
+        return Err(rustler::Error::Term(Box::new("Invalid connection ID")));
+    };
+    drop(conn_map); // Release lock before async operation
+
+    // SAFETY: We're inside TOKIO_RUNTIME.block_on(), so this is synchronous execution.
+    // The std::sync::Mutex guards are safe to hold across await points here because
+    // we're not in a true async context - block_on runs the future to completion.
+    #[allow(clippy::await_holding_lock)]
+    let value = TOKIO_RUNTIME.block_on(async {
+        let client_guard = safe_lock_arc(&client, "get_secure_delete client")?;
+        let conn_guard: std::sync::MutexGuard<libsql::Connection> =
+            safe_lock_arc(&client_guard.client, "get_secure_delete conn")?;
+
+        let mut rows = conn_guard
+            .query("PRAGMA secure_delete", ())
+            .await
+            .map_err(|e| {
+                rustler::Error::Term(Box::new(format!("Failed to query secure_delete: {e}")))
+            })?;
+
+        let row = rows
+            .next()
+            .await
+            .map_err(|e| {
+                rustler::Error::Term(Box::new(format!("Failed to read secure_delete: {e}")))
+            })?
+            .ok_or_else(|| {
+                rustler::Error::Term(Box::new("PRAGMA secure_delete returned no rows"))
+            })?;
+
+        row.get::<i64>(0).map_err(|e| {
+            rustler::Error::Term(Box::new(format!("Failed to decode secure_delete: {e}")))
+        })
+    })?;
+
+    match value {
+        0 => Ok(off()),
+        1 => Ok(on()),
+        2 => Ok(fast()),
+        other => Err(rustler::Error::Term(Box::new(format!(
+            "Unexpected secure_delete value: {other}"
+        )))),
+    }
+}
+
+/// Decode a `threads` limit, rejecting negative or non-integer values.
+fn decode_thread_limit(term: Term) -> NifResult<u32> {
+    let limit: i64 = term
+        .decode()
+        .map_err(|_| rustler::Error::Term(Box::new("threads must be an integer")))?;
+
+    u32::try_from(limit).map_err(|_| {
+        rustler::Error::Term(Box::new(format!(
+            "Invalid threads value: {limit}. Must be a non-negative integer"
+        )))
+    })
+}
+
+/// Run `PRAGMA threads = <limit>` on a connection and return the limit `SQLite` applied.
+///
+/// `SQLite` caps the value at its compile-time `SQLITE_MAX_WORKER_THREADS`, so the
+/// result can be lower than requested.
+async fn apply_threads(conn: &libsql::Connection, limit: u32) -> Result<i64, String> {
+    let mut rows = conn
+        .query(&format!("PRAGMA threads = {limit}"), ())
+        .await
+        .map_err(|e| format!("Failed to set threads: {e}"))?;
+
+    let row = rows
+        .next()
+        .await
+        .map_err(|e| format!("Failed to read threads: {e}"))?
+        .ok_or_else(|| "PRAGMA threads returned no rows".to_string())?;
+
+    row.get::<i64>(0)
+        .map_err(|e| format!("Failed to decode threads: {e}"))
+}
+
+/// Set the maximum number of auxiliary threads `SQLite` may use for a connection.
+///
+/// Large sorts (e.g. `ORDER BY` or `CREATE INDEX` over many rows) can use these
+/// helper threads. `0` disables them.
+///
+/// # Arguments
+/// - `conn_id`: Database connection ID
+/// - `limit`: Requested thread limit (must be non-negative)
+///
+/// Returns the limit `SQLite` applied, which is capped at its compile-time maximum.
+#[rustler::nif(schedule = "DirtyIo")]
+pub fn set_threads_native(conn_id: &str, limit: Term) -> NifResult<i64> {
+    let limit = decode_thread_limit(limit)?;
+
+    let conn_map = crate::utils::safe_lock(&CONNECTION_REGISTRY, "set_threads conn_map")?;
+
+    let Some(client) = conn_map.get(conn_id).cloned() else {
+        return Err(rustler::Error::Term(Box::new("Invalid connection ID")));
+    };
+    drop(conn_map); // Release lock before async operation
+
+    // SAFETY: We're inside TOKIO_RUNTIME.block_on(), so this is synchronous execution.
+    // The std::sync::Mutex guards are safe to hold across await points here because
+    // we're not in a true async context - block_on runs the future to completion.
+    #[allow(clippy::await_holding_lock)]
+    TOKIO_RUNTIME.block_on(async {
+        let client_guard = safe_lock_arc(&client, "set_threads client")?;
+        let conn_guard: std::sync::MutexGuard<libsql::Connection> =
+            safe_lock_arc(&client_guard.client, "set_threads conn")?;
+
+        apply_threads(&conn_guard, limit)
+            .await
+            .map_err(|e| rustler::Error::Term(Box::new(e)))
+    })
+}
+
+/// Decode an optional `PRAGMA optimize` mask, rejecting negative or non-integer values.
+fn decode_optimize_mask(term: Term) -> NifResult<Option<u32>> {
+    let invalid = || {
+        rustler::Error::Term(Box::new(
+            "optimize mask must be a non-negative integer or nil",
+        ))
+    };
+
+    term.decode::<Option<i64>>()
+        .map_err(|_| invalid())?
+        .map(|mask| u32::try_from(mask).map_err(|_| invalid()))
+        .transpose()
+}
+
+/// Run `PRAGMA optimize`, optionally with a bitmask selecting which optimisations to run.
+///
+/// With the `0x01` debug bit set the pragma returns the statements it would run as rows
+/// instead of running them, so the rows are drained and discarded.
+async fn apply_optimize(conn: &libsql::Connection, mask: Option<u32>) -> Result<(), String> {
+    let sql = match mask {
+        Some(mask) => format!("PRAGMA optimize({mask})"),
+        None => "PRAGMA optimize".to_string(),
+    };
+
+    let mut rows = conn
+        .query(&sql, ())
+        .await
+        .map_err(|e| format!("optimize failed: {e}"))?;
+
+    while rows
+        .next()
+        .await
+        .map_err(|e| format!("optimize failed: {e}"))?
+        .is_some()
+    {}
+
+    Ok(())
+}
+
+/// Run `PRAGMA optimize` on a database connection.
+///
+/// `SQLite` recommends running it periodically on long-lived connections and just before
+/// closing them; it re-analyses only the tables whose statistics look stale, so it is
+/// usually cheap.
+///
+/// # Arguments
+/// - `conn_id`: Database connection ID
+/// - `mask`: Optional bitmask passed as `PRAGMA optimize(<mask>)`, or `nil` for the default
+///
+/// Returns `:ok` on success, error on an invalid mask or failure.
+#[rustler::nif(schedule = "DirtyIo")]
+pub fn optimize_native(conn_id: &str, mask: Term) -> NifResult<Atom> {
+    let mask = decode_optimize_mask(mask)?;
+
+    let conn_map = crate::utils::safe_lock(&CONNECTION_REGISTRY, "optimize conn_map")?;
+
+    let Some(client) = conn_map.get(conn_id).cloned() else {
+        return Err(rustler::Error::Term(Box::new("Invalid connection ID")));
+    };
+    drop(conn_map); // Release lock before async operation
+
+    // SAFETY: We're inside TOKIO_RUNTIME.block_on(), so this is synchronous execution.
+    // The std::sync::Mutex guards are safe to hold across await points here because
+    // we're not in a true async context - block_on runs the future to completion.
+    #[allow(clippy::await_holding_lock)]
+    TOKIO_RUNTIME.block_on(async {
+        let client_guard = safe_lock_arc(&client, "optimize client")?;
+        let conn_guard: std::sync::MutexGuard<libsql::Connection> =
+            safe_lock_arc(&client_guard.client, "optimize conn")?;
+
+        apply_optimize(&conn_guard, mask)
+            .await
+            .map_err(|e| rustler::Error::Term(Box::new(e)))
+    })?;
+
+    Ok(rustler::types::atom::ok())
+}
+
 /// Reset the connection state to a clean state.
 ///
 /// This clears any prepared statements and resets the connection to a clean state.
@@ -354,6 +1004,44 @@ pub fn interrupt_connection(conn_id: &str) -> NifResult<Atom> {
     }
 }
 
+/// Swap a remote connection for one that cannot reach any server.
+///
+/// Test support for `auto_reconnect`: the next query fails as if the network had dropped,
+/// while the stored database handle is untouched so reconnecting recovers.
+///
+/// Only compiled with the `test-hooks` cargo feature, so release builds never expose it.
+///
+/// # Arguments
+/// - `conn_id`: Database connection ID (must be a `remote` connection)
+///
+/// Returns `:ok` on success, error on failure.
+#[cfg(feature = "test-hooks")]
+#[rustler::nif(schedule = "DirtyIo")]
+pub fn simulate_connection_loss(conn_id: &str) -> NifResult<Atom> {
+    let client =
+        crate::utils::safe_lock(&CONNECTION_REGISTRY, "simulate_connection_loss conn_map")?
+            .get(conn_id)
+            .cloned()
+            .ok_or_else(|| rustler::Error::Term(Box::new("Invalid connection ID")))?;
+
+    let client_guard = safe_lock_arc(&client, "simulate_connection_loss client")?;
+    if client_guard.mode != Mode::Remote {
+        return Err(rustler::Error::Term(Box::new(
+            "simulate_connection_loss requires a remote connection",
+        )));
+    }
+
+    // Nothing listens on the discard port, so every request is refused
+    let unreachable = TOKIO_RUNTIME
+        .block_on(Builder::new_remote("http://127.0.0.1:9".to_string(), String::new()).build())
+        .and_then(|db| db.connect())
+        .map_err(|e| rustler::Error::Term(Box::new(format!("Failed to build connection: {e}"))))?;
+
+    *safe_lock_arc(&client_guard.client, "simulate_connection_loss conn")? = unreachable;
+
+    Ok(rustler::types::atom::ok())
+}
+
 /// Enable or disable loading of SQLite extensions.
 ///
 /// By default, extension loading is disabled for security reasons.
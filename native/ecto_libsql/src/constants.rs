@@ -7,10 +7,10 @@ use std::collections::HashMap;
 use std::sync::{Arc, LazyLock, Mutex};
 use tokio::runtime::Runtime;
 
-use crate::models::{CursorData, LibSQLConn, TransactionEntry};
+use crate::models::{CachedStatement, CursorData, LibSQLConn, OperationEntry, TransactionEntry};
 
 /// Type alias to reduce complexity of the statement registry
-type StatementEntry = (String, Arc<Mutex<libsql::Statement>>);
+type StatementEntry = (String, Arc<Mutex<CachedStatement>>);
 
 /// Global Tokio runtime for async operations
 ///
@@ -31,6 +31,9 @@ pub static TOKIO_RUNTIME: LazyLock<Runtime> = LazyLock::new(|| {
 /// Default timeout for sync operations (in seconds)
 pub const DEFAULT_SYNC_TIMEOUT_SECS: u64 = 30;
 
+/// Interval between replication index checks while waiting for a replica (in milliseconds)
+pub const FRAME_POLL_INTERVAL_MS: u64 = 25;
+
 /// Global registry for active database connections
 ///
 /// Maps connection ID to `LibSQLConn` state wrapped in `Arc<Mutex>` for thread-safe access.
@@ -49,12 +52,24 @@ pub static TXN_REGISTRY: LazyLock<Mutex<HashMap<String, TransactionEntry>>> =
 pub static STMT_REGISTRY: LazyLock<Mutex<HashMap<String, StatementEntry>>> =
     LazyLock::new(|| Mutex::new(HashMap::new()));
 
+/// Global registry for statements prepared on connections with the statement cache disabled
+///
+/// Maps statement ID to (connection_id, sql); each use prepares and finalises a fresh statement.
+pub static UNCACHED_STMT_REGISTRY: LazyLock<Mutex<HashMap<String, (String, String)>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
 /// Global registry for active cursors
 ///
 /// Maps cursor ID to `CursorData` containing buffered rows and position.
 pub static CURSOR_REGISTRY: LazyLock<Mutex<HashMap<String, CursorData>>> =
     LazyLock::new(|| Mutex::new(HashMap::new()));
 
+/// Global registry for cancellable operations
+///
+/// Maps operation handle to `OperationEntry`; entries are removed when the operation finishes.
+pub static OPERATION_REGISTRY: LazyLock<Mutex<HashMap<String, OperationEntry>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
 // Atom declarations for EctoLibSql - used as return values and option identifiers in the NIF interface
 atoms! {
     local,
@@ -72,9 +87,26 @@ atoms! {
     immediate,
     exclusive,
     read_only,
+    sql_too_long,
+    replica_read_only,
+    invalid_encryption_key,
+    aes256cbc,
     transaction,
     connection,
     blob,
+    charlist,
     nil,
-    unsupported
+    unsupported,
+    on,
+    off,
+    fast,
+    wait_timeout,
+    file,
+    sum,
+    min,
+    max,
+    distinct,
+    timeout,
+    fail_fast,
+    backoff
 }
@@ -5,18 +5,154 @@
 /// - Fetching rows from cursors in batches
 /// - Memory-efficient iteration over large result sets
 /// - Cursor ownership verification
+/// - Reaping cursors leaked by crashed owners
 ///
 /// Cursors allow processing large result sets without loading everything into memory at once.
 /// Results are fetched in configurable batch sizes for efficient memory usage.
+///
+/// Local cursors read the whole result set when declared. Cursors on remote connections keep
+/// the server's result stream open instead and read only as many rows as each fetch needs.
+/// Cursors declared with `prefetch` read ahead in a background task instead, keeping a bounded
+/// number of batches ready so a slow consumer does not wait on the database for every fetch.
 use crate::{
     constants::{CONNECTION_REGISTRY, CURSOR_REGISTRY, TOKIO_RUNTIME},
     decode,
-    models::CursorData,
+    models::{CursorData, Mode, Prefetch},
     transaction::TransactionEntryGuard,
     utils,
 };
 use libsql::Value;
-use rustler::{Atom, Binary, Encoder, Env, NifResult, OwnedBinary, Term};
+use rustler::{Atom, Encoder, Env, NifResult, Term};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Result set of a remote cursor that has not been read yet.
+type PendingRows = Option<Arc<Mutex<libsql::Rows>>>;
+
+/// Whether `conn_id` talks to a remote server directly, so its cursors should stream.
+fn streams_remotely(conn_id: &str) -> Result<bool, rustler::Error> {
+    let client = {
+        let conn_map = utils::safe_lock(&CONNECTION_REGISTRY, "streams_remotely conn_map")?;
+        conn_map
+            .get(conn_id)
+            .cloned()
+            .ok_or_else(|| rustler::Error::Term(Box::new("Invalid connection ID")))?
+    };
+
+    let mode = utils::safe_lock_arc(&client, "streams_remotely client")?.mode;
+    Ok(mode == Mode::Remote)
+}
+
+/// Column names of a result set, read before any row arrives.
+fn result_columns(rows: &libsql::Rows) -> Vec<String> {
+    (0..rows.column_count())
+        .map(|i| {
+            rows.column_name(i)
+                .map_or_else(|| format!("col{i}"), ToString::to_string)
+        })
+        .collect()
+}
+
+/// Read up to `limit` rows of `column_count` values from a result set.
+///
+/// Returns the rows and whether the result set is exhausted.
+async fn pull_rows(
+    rows: &mut libsql::Rows,
+    column_count: usize,
+    limit: usize,
+) -> libsql::Result<(Vec<Vec<Value>>, bool)> {
+    let mut batch = Vec::with_capacity(limit);
+
+    while batch.len() < limit {
+        match rows.next().await? {
+            Some(row) => batch.push(
+                (0..column_count)
+                    .map(|i| row.get(i as i32).unwrap_or(Value::Null))
+                    .collect(),
+            ),
+            None => return Ok((batch, true)),
+        }
+    }
+
+    Ok((batch, false))
+}
+
+/// Start a background task that reads `rows` in batches of `batch_size` rows.
+///
+/// Up to `batches` batches wait in the channel; the task then blocks until the consumer
+/// receives one, so at most `batches + 1` batches are held at any time. The task stops
+/// when the result set is exhausted, on the first error, or when the cursor is dropped.
+fn spawn_prefetch(
+    mut rows: libsql::Rows,
+    column_count: usize,
+    batch_size: usize,
+    batches: usize,
+) -> Prefetch {
+    let (sender, receiver) = tokio::sync::mpsc::channel(batches);
+    let queued_rows = Arc::new(AtomicUsize::new(0));
+    let queued = queued_rows.clone();
+
+    TOKIO_RUNTIME.spawn(async move {
+        loop {
+            let (batch, exhausted) = match pull_rows(&mut rows, column_count, batch_size).await {
+                Ok(pulled) => pulled,
+                Err(e) => {
+                    let _ = sender.send(Err(e.to_string())).await;
+                    return;
+                }
+            };
+
+            if !batch.is_empty() {
+                queued.fetch_add(batch.len(), Ordering::SeqCst);
+                if sender.send(Ok(batch)).await.is_err() {
+                    return; // Cursor closed
+                }
+            }
+
+            if exhausted {
+                return;
+            }
+        }
+    });
+
+    Prefetch {
+        batches: Arc::new(Mutex::new(receiver)),
+        queued_rows,
+    }
+}
+
+/// Receive prefetched batches until at least `wanted` rows arrived or the reader finished.
+///
+/// Returns the rows and whether the result set is exhausted.
+fn receive_prefetched(
+    prefetch: &Prefetch,
+    wanted: usize,
+) -> Result<(Vec<Vec<Value>>, bool), rustler::Error> {
+    // SAFETY: We use TOKIO_RUNTIME.block_on(), which runs the future synchronously on a
+    // dedicated thread pool. This prevents deadlocks that could occur if we were in a true
+    // async context with std::sync::Mutex guards held across await points.
+    #[allow(clippy::await_holding_lock)]
+    TOKIO_RUNTIME.block_on(async {
+        let mut receiver = utils::safe_lock_arc(&prefetch.batches, "fetch_cursor prefetch")?;
+        let mut received = Vec::new();
+
+        while received.len() < wanted {
+            match receiver.recv().await {
+                Some(Ok(batch)) => {
+                    prefetch
+                        .queued_rows
+                        .fetch_sub(batch.len(), Ordering::SeqCst);
+                    received.extend(batch);
+                }
+                Some(Err(message)) => return Err(utils::statement_error(message)),
+                None => return Ok((received, true)),
+            }
+        }
+
+        Ok((received, false))
+    })
+}
 
 /// Declare a cursor for streaming result set from a connection.
 ///
@@ -33,6 +169,7 @@ use rustler::{Atom, Binary, Encoder, Env, NifResult, OwnedBinary, Term};
 pub fn declare_cursor(conn_id: &str, sql: &str, args: Vec<Term>) -> NifResult<String> {
     // UTF-8 validation is guaranteed by Rust's &str type and Rustler's conversion,
     // so we can rely on the type system rather than runtime checks.
+    utils::ensure_sql_allowed(conn_id, sql)?;
 
     let conn_map = utils::safe_lock(&CONNECTION_REGISTRY, "declare_cursor conn_map")?;
 
@@ -43,24 +180,33 @@ pub fn declare_cursor(conn_id: &str, sql: &str, args: Vec<Term>) -> NifResult<St
 
     drop(conn_map); // Release lock before async operation
 
-    let decoded_args: Vec<Value> = args
+    let remote = streams_remotely(conn_id)?;
+
+    let mut decoded_args: Vec<Value> = args
         .into_iter()
         .map(|t| utils::decode_term_to_value(t))
         .collect::<Result<_, _>>()
         .map_err(|e| rustler::Error::Term(Box::new(e)))?;
+    utils::apply_bind_options(conn_id, &mut decoded_args)?;
 
     // SAFETY: We're inside TOKIO_RUNTIME.block_on(), so this is synchronous execution.
     // The std::sync::Mutex guards are safe to hold across await points here because
     // we're not in a true async context - block_on runs the future to completion.
     #[allow(clippy::await_holding_lock)]
-    let (columns, rows) = TOKIO_RUNTIME.block_on(async {
+    let (columns, rows, pending) = TOKIO_RUNTIME.block_on(async {
         let client_guard = utils::safe_lock_arc(&client, "declare_cursor client")?;
         let conn_guard = utils::safe_lock_arc(&client_guard.client, "declare_cursor conn")?;
 
         let mut result_rows = conn_guard
             .query(sql, decoded_args)
             .await
-            .map_err(|e| rustler::Error::Term(Box::new(format!("Query failed: {e}"))))?;
+            .map_err(|e| utils::statement_error(format!("Query failed: {e}")))?;
+
+        if remote {
+            let columns = result_columns(&result_rows);
+            let pending: PendingRows = Some(Arc::new(Mutex::new(result_rows)));
+            return Ok((columns, Vec::new(), pending));
+        }
 
         let mut columns: Vec<String> = Vec::new();
         let mut rows: Vec<Vec<Value>> = Vec::new();
@@ -90,7 +236,7 @@ pub fn declare_cursor(conn_id: &str, sql: &str, args: Vec<Term>) -> NifResult<St
             rows.push(row_values);
         }
 
-        Ok::<_, rustler::Error>((columns, rows))
+        Ok::<_, rustler::Error>((columns, rows, None))
     })?;
 
     let cursor_id = uuid::Uuid::new_v4().to_string();
@@ -98,7 +244,10 @@ pub fn declare_cursor(conn_id: &str, sql: &str, args: Vec<Term>) -> NifResult<St
         conn_id: conn_id.to_string(),
         columns,
         rows,
+        pending,
+        prefetch: None,
         position: 0,
+        created_at: Instant::now(),
     };
 
     utils::safe_lock(&CURSOR_REGISTRY, "declare_cursor cursor_registry")?
@@ -130,14 +279,18 @@ pub fn declare_cursor_with_context(
 ) -> NifResult<String> {
     // UTF-8 validation is guaranteed by Rust's &str type and Rustler's conversion,
     // so we can rely on the type system rather than runtime checks.
+    utils::ensure_sql_allowed(conn_id, sql)?;
 
-    let decoded_args: Vec<Value> = args
+    let mut decoded_args: Vec<Value> = args
         .into_iter()
         .map(|t| utils::decode_term_to_value(t))
         .collect::<Result<_, _>>()
         .map_err(|e| rustler::Error::Term(Box::new(e)))?;
+    utils::apply_bind_options(conn_id, &mut decoded_args)?;
 
-    let (cursor_conn_id, columns, rows) = if id_type == crate::constants::transaction() {
+    let remote = streams_remotely(conn_id)?;
+
+    let (cursor_conn_id, columns, rows, pending) = if id_type == crate::constants::transaction() {
         // Take transaction entry with ownership verification using guard
         let guard = TransactionEntryGuard::take(id, conn_id)?;
 
@@ -145,12 +298,18 @@ pub fn declare_cursor_with_context(
         let cursor_conn_id = conn_id.to_string();
 
         // Execute query without holding the lock
-        let (cols, rows) = TOKIO_RUNTIME.block_on(async {
+        let (cols, rows, pending) = TOKIO_RUNTIME.block_on(async {
             let mut result_rows = guard
                 .transaction()?
                 .query(sql, decoded_args)
                 .await
-                .map_err(|e| rustler::Error::Term(Box::new(format!("Query failed: {e}"))))?;
+                .map_err(|e| utils::statement_error(format!("Query failed: {e}")))?;
+
+            if remote {
+                let columns = result_columns(&result_rows);
+                let pending: PendingRows = Some(Arc::new(Mutex::new(result_rows)));
+                return Ok((columns, Vec::new(), pending));
+            }
 
             let mut columns: Vec<String> = Vec::new();
             let mut rows: Vec<Vec<Value>> = Vec::new();
@@ -178,12 +337,12 @@ pub fn declare_cursor_with_context(
                 rows.push(row_values);
             }
 
-            Ok::<_, rustler::Error>((columns, rows))
+            Ok::<_, rustler::Error>((columns, rows, None))
         })?;
 
         // Guard automatically re-inserts the entry on drop
 
-        (cursor_conn_id, cols, rows)
+        (cursor_conn_id, cols, rows, pending)
     } else if id_type == crate::constants::connection() {
         // For connection, verify that the provided conn_id matches the id
         if conn_id != id {
@@ -212,13 +371,19 @@ pub fn declare_cursor_with_context(
         // The std::sync::Mutex guards are safe to hold across await points here because
         // we're not in a true async context - block_on runs the future to completion.
         #[allow(clippy::await_holding_lock)]
-        let (cols, rows) = TOKIO_RUNTIME.block_on(async {
+        let (cols, rows, pending) = TOKIO_RUNTIME.block_on(async {
             let conn_guard = utils::safe_lock_arc(&connection, "declare_cursor_with_context conn")?;
 
             let mut result_rows = conn_guard
                 .query(sql, decoded_args)
                 .await
-                .map_err(|e| rustler::Error::Term(Box::new(format!("Query failed: {e}"))))?;
+                .map_err(|e| utils::statement_error(format!("Query failed: {e}")))?;
+
+            if remote {
+                let columns = result_columns(&result_rows);
+                let pending: PendingRows = Some(Arc::new(Mutex::new(result_rows)));
+                return Ok((columns, Vec::new(), pending));
+            }
 
             let mut columns: Vec<String> = Vec::new();
             let mut rows: Vec<Vec<Value>> = Vec::new();
@@ -246,10 +411,10 @@ pub fn declare_cursor_with_context(
                 rows.push(row_values);
             }
 
-            Ok::<_, rustler::Error>((columns, rows))
+            Ok::<_, rustler::Error>((columns, rows, None))
         })?;
 
-        (cursor_conn_id, cols, rows)
+        (cursor_conn_id, cols, rows, pending)
     } else {
         return Err(rustler::Error::Term(Box::new("Invalid id_type for cursor")));
     };
@@ -259,7 +424,10 @@ pub fn declare_cursor_with_context(
         conn_id: cursor_conn_id,
         columns,
         rows,
+        pending,
+        prefetch: None,
         position: 0,
+        created_at: Instant::now(),
     };
 
     utils::safe_lock(&CURSOR_REGISTRY, "declare_cursor_with_context cursor")?
@@ -268,12 +436,124 @@ pub fn declare_cursor_with_context(
     Ok(cursor_id)
 }
 
+/// Declare a cursor whose rows are read ahead in the background.
+///
+/// Instead of buffering the whole result set (local) or reading on demand (remote), a
+/// background task reads `batch_size` rows at a time and keeps up to `prefetch` batches
+/// ready for `fetch_cursor`. Once that many are waiting it pauses until the consumer
+/// catches up, so memory stays bounded however slowly the rows are consumed.
+///
+/// # Arguments
+/// - `conn_id`: Connection ID (used for ownership validation)
+/// - `id`: Transaction ID or connection ID
+/// - `id_type`: `:transaction` or `:connection`
+/// - `sql`: SQL query string
+/// - `args`: Query parameters
+/// - `batch_size`: Rows read per batch
+/// - `prefetch`: Maximum number of batches read ahead of the consumer
+///
+/// Returns a cursor ID on success, error on failure.
+#[rustler::nif(schedule = "DirtyIo")]
+pub fn declare_prefetch_cursor(
+    conn_id: &str,
+    id: &str,
+    id_type: Atom,
+    sql: &str,
+    args: Vec<Term>,
+    batch_size: usize,
+    prefetch: usize,
+) -> NifResult<String> {
+    if batch_size == 0 || prefetch == 0 {
+        return Err(rustler::Error::Term(Box::new(
+            "batch_size and prefetch must be positive",
+        )));
+    }
+
+    utils::ensure_sql_allowed(conn_id, sql)?;
+
+    let mut decoded_args: Vec<Value> = args
+        .into_iter()
+        .map(|t| utils::decode_term_to_value(t))
+        .collect::<Result<_, _>>()
+        .map_err(|e| rustler::Error::Term(Box::new(e)))?;
+    utils::apply_bind_options(conn_id, &mut decoded_args)?;
+
+    let rows = if id_type == crate::constants::transaction() {
+        let guard = TransactionEntryGuard::take(id, conn_id)?;
+
+        TOKIO_RUNTIME.block_on(async {
+            guard
+                .transaction()?
+                .query(sql, decoded_args)
+                .await
+                .map_err(|e| utils::statement_error(format!("Query failed: {e}")))
+        })?
+    } else if id_type == crate::constants::connection() {
+        if conn_id != id {
+            return Err(rustler::Error::Term(Box::new(
+                "Connection ID mismatch: provided conn_id does not match cursor connection ID",
+            )));
+        }
+
+        let client = {
+            let conn_map = utils::safe_lock(&CONNECTION_REGISTRY, "declare_prefetch_cursor conn")?;
+            conn_map
+                .get(id)
+                .cloned()
+                .ok_or_else(|| rustler::Error::Term(Box::new("Connection not found")))?
+        };
+
+        let connection = {
+            let client_guard = utils::safe_lock_arc(&client, "declare_prefetch_cursor client")?;
+            client_guard.client.clone()
+        };
+
+        // SAFETY: We're inside TOKIO_RUNTIME.block_on(), so this is synchronous execution.
+        // The std::sync::Mutex guards are safe to hold across await points here because
+        // we're not in a true async context - block_on runs the future to completion.
+        #[allow(clippy::await_holding_lock)]
+        TOKIO_RUNTIME.block_on(async {
+            let conn_guard = utils::safe_lock_arc(&connection, "declare_prefetch_cursor conn")?;
+
+            conn_guard
+                .query(sql, decoded_args)
+                .await
+                .map_err(|e| utils::statement_error(format!("Query failed: {e}")))
+        })?
+    } else {
+        return Err(rustler::Error::Term(Box::new("Invalid id_type for cursor")));
+    };
+
+    let columns = result_columns(&rows);
+    let prefetch = spawn_prefetch(rows, columns.len(), batch_size, prefetch);
+
+    let cursor_id = uuid::Uuid::new_v4().to_string();
+    let cursor_data = CursorData {
+        conn_id: conn_id.to_string(),
+        columns,
+        rows: Vec::new(),
+        pending: None,
+        prefetch: Some(prefetch),
+        position: 0,
+        created_at: Instant::now(),
+    };
+
+    utils::safe_lock(&CURSOR_REGISTRY, "declare_prefetch_cursor cursor")?
+        .insert(cursor_id.clone(), cursor_data);
+
+    Ok(cursor_id)
+}
+
 /// Fetch rows from a cursor in batches.
 ///
 /// Returns up to `max_rows` rows from the cursor's current position.
 /// The cursor position is automatically advanced. When no more rows are available,
 /// returns an empty result set.
 ///
+/// Remote cursors read the next rows from the server here, so only the current batch is
+/// held in memory. Prefetching cursors take the next batches from their background reader,
+/// waiting for it only when nothing has been read ahead yet.
+///
 /// # Arguments
 /// - `env`: Elixir environment
 /// - `conn_id`: Connection ID (for ownership verification)
@@ -288,6 +568,60 @@ pub fn fetch_cursor<'a>(
     cursor_id: &str,
     max_rows: usize,
 ) -> NifResult<Term<'a>> {
+    let (pending, prefetch, column_count, wanted) = {
+        let cursor_registry = utils::safe_lock(&CURSOR_REGISTRY, "fetch_cursor cursor_registry")?;
+        let cursor = cursor_registry
+            .get(cursor_id)
+            .ok_or_else(|| rustler::Error::Term(Box::new("Cursor not found")))?;
+
+        decode::verify_cursor_ownership(cursor, conn_id)?;
+
+        let buffered = cursor.rows.len().saturating_sub(cursor.position);
+        (
+            cursor.pending.clone(),
+            cursor.prefetch.clone(),
+            cursor.columns.len(),
+            max_rows.saturating_sub(buffered),
+        )
+    }; // Registry lock dropped before reading from the server or the prefetch task
+
+    let refill = match (pending, prefetch) {
+        _ if wanted == 0 => None,
+        (Some(rows), _) => {
+            // SAFETY: We use TOKIO_RUNTIME.block_on(), which runs the future synchronously on a
+            // dedicated thread pool. This prevents deadlocks that could occur if we were in a true
+            // async context with std::sync::Mutex guards held across await points.
+            #[allow(clippy::await_holding_lock)]
+            let pulled = TOKIO_RUNTIME.block_on(async {
+                let mut rows_guard = utils::safe_lock_arc(&rows, "fetch_cursor pending")?;
+                pull_rows(&mut rows_guard, column_count, wanted)
+                    .await
+                    .map_err(|e| rustler::Error::Term(Box::new(e.to_string())))
+            })?;
+            Some(pulled)
+        }
+        (None, Some(prefetch)) => Some(receive_prefetched(&prefetch, wanted)?),
+        (None, None) => None,
+    };
+
+    if let Some((pulled, exhausted)) = refill {
+        let mut cursor_registry =
+            utils::safe_lock(&CURSOR_REGISTRY, "fetch_cursor cursor_registry")?;
+        let cursor = cursor_registry
+            .get_mut(cursor_id)
+            .ok_or_else(|| rustler::Error::Term(Box::new("Cursor not found")))?;
+
+        // Forget rows already handed out so the cursor only holds the current batch
+        cursor.rows.drain(..cursor.position);
+        cursor.position = 0;
+        cursor.rows.extend(pulled);
+
+        if exhausted {
+            cursor.pending = None;
+            cursor.prefetch = None;
+        }
+    }
+
     let mut cursor_registry = utils::safe_lock(&CURSOR_REGISTRY, "fetch_cursor cursor_registry")?;
 
     let cursor = cursor_registry
@@ -320,22 +654,7 @@ pub fn fetch_cursor<'a>(
         .map(|row| {
             let row_terms: Result<Vec<Term>, rustler::Error> = row
                 .iter()
-                .map(|val| match val {
-                    Value::Text(s) => Ok(s.encode(env)),
-                    Value::Integer(i) => Ok(i.encode(env)),
-                    Value::Real(f) => Ok(f.encode(env)),
-                    Value::Blob(b) => OwnedBinary::new(b.len())
-                        .ok_or_else(|| {
-                            rustler::Error::Term(Box::new(
-                                "Failed to allocate binary for blob data",
-                            ))
-                        })
-                        .map(|mut owned| {
-                            owned.as_mut_slice().copy_from_slice(b);
-                            Binary::from_owned(owned, env).encode(env)
-                        }),
-                    Value::Null => Ok(rustler::types::atom::nil().encode(env)),
-                })
+                .map(|val| utils::value_to_term(env, val))
                 .collect();
             row_terms.map(|terms| terms.encode(env))
         })
@@ -345,3 +664,60 @@ pub fn fetch_cursor<'a>(
     let result = (elixir_columns, elixir_rows, fetch_count);
     Ok(result.encode(env))
 }
+
+/// Forcibly close cursors that have outlived their owners.
+///
+/// Cursors are normally released when their stream finishes, but if the owning
+/// process crashes mid-stream the buffered rows stay in the registry. This removes
+/// every cursor declared more than `older_than_ms` milliseconds ago, across all
+/// connections.
+///
+/// # Arguments
+/// - `older_than_ms`: Minimum cursor age in milliseconds; younger cursors are kept
+///
+/// Returns the number of cursors removed.
+///
+/// # Examples
+///
+/// ```elixir
+/// # Drop anything declared more than five minutes ago
+/// {:ok, reaped} = EctoLibSql.Native.reap_stale_cursors(300_000)
+/// ```
+#[rustler::nif(schedule = "DirtyIo")]
+pub fn reap_cursors(older_than_ms: u64) -> NifResult<usize> {
+    let threshold = Duration::from_millis(older_than_ms);
+    let mut cursor_registry = utils::safe_lock(&CURSOR_REGISTRY, "reap_cursors cursor_registry")?;
+
+    let before = cursor_registry.len();
+    cursor_registry.retain(|_, cursor| cursor.created_at.elapsed() < threshold);
+
+    Ok(before - cursor_registry.len())
+}
+
+/// Number of rows a cursor currently holds in memory.
+///
+/// Local cursors hold every row not yet fetched; remote cursors hold at most the rows of the
+/// batch being fetched, since the rest are still on the server. Prefetching cursors also count
+/// the rows their background reader has queued.
+///
+/// # Arguments
+/// - `conn_id`: Connection ID (for ownership verification)
+/// - `cursor_id`: Cursor ID
+#[rustler::nif(schedule = "DirtyIo")]
+pub fn cursor_buffered_rows(conn_id: &str, cursor_id: &str) -> NifResult<usize> {
+    let cursor_registry =
+        utils::safe_lock(&CURSOR_REGISTRY, "cursor_buffered_rows cursor_registry")?;
+
+    let cursor = cursor_registry
+        .get(cursor_id)
+        .ok_or_else(|| rustler::Error::Term(Box::new("Cursor not found")))?;
+
+    decode::verify_cursor_ownership(cursor, conn_id)?;
+
+    let queued = cursor
+        .prefetch
+        .as_ref()
+        .map_or(0, |prefetch| prefetch.queued_rows.load(Ordering::SeqCst));
+
+    Ok(cursor.rows.len().saturating_sub(cursor.position) + queued)
+}
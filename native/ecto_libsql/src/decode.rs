@@ -2,11 +2,11 @@
 ///
 /// This module provides functions to convert Elixir atoms and values into
 /// Rust types, and to validate resource ownership.
-use libsql::TransactionBehavior;
-use rustler::Atom;
+use libsql::{Cipher, TransactionBehavior};
+use rustler::{Atom, Term};
 
 use crate::constants::*;
-use crate::models::{CursorData, Mode};
+use crate::models::{BusyBackoff, BusyPolicy, CursorData, Mode, ReduceOp};
 
 /// Decode an Elixir atom to a Mode enum
 ///
@@ -41,6 +41,71 @@ pub fn decode_transaction_behavior(atom: Atom) -> Option<TransactionBehavior> {
     }
 }
 
+/// Decode an Elixir atom to a `secure_delete` pragma value
+///
+/// Converts `:on`, `:off`, `:fast` to the keyword accepted by `PRAGMA secure_delete`.
+pub fn decode_secure_delete(atom: Atom) -> Option<&'static str> {
+    if atom == on() {
+        Some("ON")
+    } else if atom == off() {
+        Some("OFF")
+    } else if atom == fast() {
+        Some("FAST")
+    } else {
+        None
+    }
+}
+
+/// Decode an Elixir atom to a local encryption cipher
+///
+/// Only `:aes256cbc`, the cipher `connect` uses for `encryption_key`, is supported.
+pub fn decode_cipher(atom: Atom) -> Option<Cipher> {
+    if atom == aes256cbc() {
+        Some(Cipher::Aes256Cbc)
+    } else {
+        None
+    }
+}
+
+/// Decode an Elixir atom to a built-in reducer for `query_reduce_native`
+///
+/// Converts `:sum`, `:min`, `:max`, `:distinct` to their `ReduceOp` equivalents.
+pub fn decode_reduce_op(atom: Atom) -> Option<ReduceOp> {
+    if atom == sum() {
+        Some(ReduceOp::Sum)
+    } else if atom == min() {
+        Some(ReduceOp::Min)
+    } else if atom == max() {
+        Some(ReduceOp::Max)
+    } else if atom == distinct() {
+        Some(ReduceOp::Distinct)
+    } else {
+        None
+    }
+}
+
+/// Decode an Elixir term to a busy policy for `set_busy_handler_native`
+///
+/// Accepts `{:timeout, ms}`, `:fail_fast` and `{:backoff, base_ms, max_ms}` with a
+/// positive `base_ms`; anything else is `None`.
+pub fn decode_busy_policy(term: Term) -> Option<BusyPolicy> {
+    if let Ok(atom) = term.decode::<Atom>() {
+        return (atom == fail_fast()).then_some(BusyPolicy::FailFast);
+    }
+
+    if let Ok((tag, ms)) = term.decode::<(Atom, u64)>() {
+        return (tag == timeout()).then_some(BusyPolicy::Timeout(ms));
+    }
+
+    if let Ok((tag, base_ms, max_ms)) = term.decode::<(Atom, u64, u64)>() {
+        if tag == backoff() && base_ms > 0 {
+            return Some(BusyPolicy::Backoff(BusyBackoff { base_ms, max_ms }));
+        }
+    }
+
+    None
+}
+
 /// Verify that a prepared statement belongs to the specified connection
 ///
 /// Returns error if the statement's connection ID doesn't match.
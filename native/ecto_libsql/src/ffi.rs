@@ -0,0 +1,27 @@
+//! Direct calls into the bundled SQLite C API
+//!
+//! The crate denies `unsafe_code` (see `Cargo.toml`). This module is the single,
+//! deliberate exception: `libsql` has no safe wrapper for SQLite's process-wide
+//! memory statistics, so the few C functions needed are called here behind safe
+//! functions. Keep additions to this module small and limited to calls that take
+//! no pointers, so each `unsafe` block is trivially sound.
+#![allow(unsafe_code)]
+
+/// SQLite's heap usage as `(used, highwater)` in bytes, optionally resetting the
+/// high-water mark to the current usage after reading it.
+pub fn memory_used(reset_highwater: bool) -> (i64, i64) {
+    // SAFETY: Both functions take no pointers and only read SQLite's global,
+    // internally synchronised allocator counters.
+    unsafe {
+        (
+            libsql::ffi::sqlite3_memory_used(),
+            libsql::ffi::sqlite3_memory_highwater(i32::from(reset_highwater)),
+        )
+    }
+}
+
+/// Ask SQLite to free up to `bytes` of unused heap memory; returns the bytes freed.
+pub fn release_memory(bytes: i32) -> i32 {
+    // SAFETY: Takes no pointers; SQLite serialises access to its caches internally.
+    unsafe { libsql::ffi::sqlite3_release_memory(bytes) }
+}
@@ -7,9 +7,11 @@ pub mod connection;
 pub mod constants;
 pub mod cursor;
 pub mod decode;
+pub mod ffi;
 pub mod hooks;
 pub mod metadata;
 pub mod models;
+pub mod operation;
 pub mod query;
 pub mod replication;
 pub mod savepoint;
@@ -1,9 +1,10 @@
 /// Database metadata and introspection functions
 ///
 /// This module provides functions to query database metadata and state information,
-/// such as the number of affected rows, last inserted row IDs, and autocommit mode.
+/// such as the number of affected rows, last inserted row IDs, autocommit mode, the
+/// schema version, and SQLite's process-wide memory statistics.
 use crate::constants::*;
-use crate::utils::{safe_lock, safe_lock_arc};
+use crate::utils::{read_schema_version, safe_lock, safe_lock_arc};
 use rustler::NifResult;
 
 /// Get the rowid of the last inserted row in the current connection.
@@ -149,3 +150,82 @@ pub fn is_autocommit(conn_id: &str) -> NifResult<bool> {
         Err(rustler::Error::Term(Box::new("Invalid connection ID")))
     }
 }
+
+/// Get the current schema version (schema cookie) of the database.
+///
+/// SQLite increments the schema version whenever the schema changes (CREATE, DROP,
+/// ALTER, etc.). Comparing values taken at different times is a cheap way to detect
+/// DDL changes, e.g. to decide whether cached prepared statements may be stale.
+///
+/// Prepared statement introspection functions use this internally to re-prepare
+/// statements compiled against an older schema.
+///
+/// # Arguments
+/// - `conn_id`: Database connection ID
+///
+/// # Examples
+/// ```elixir
+/// {:ok, before} = EctoLibSql.Native.get_schema_version(state)
+/// # ... run a migration ...
+/// {:ok, after_migration} = EctoLibSql.Native.get_schema_version(state)
+/// schema_changed? = after_migration != before
+/// ```
+#[rustler::nif(schedule = "DirtyIo")]
+pub fn schema_version(conn_id: &str) -> NifResult<i64> {
+    let conn_map = safe_lock(&CONNECTION_REGISTRY, "schema_version conn_map")?;
+
+    if let Some(client) = conn_map.get(conn_id) {
+        let client = client.clone();
+        drop(conn_map); // Release lock before async operation
+
+        // SAFETY: We're inside TOKIO_RUNTIME.block_on(), so this is synchronous execution.
+        // The std::sync::Mutex guards are safe to hold across await points here because
+        // we're not in a true async context - block_on runs the future to completion.
+        #[allow(clippy::await_holding_lock)]
+        let result = TOKIO_RUNTIME.block_on(async {
+            let client_guard = safe_lock_arc(&client, "schema_version client")?;
+            let conn_guard = safe_lock_arc(&client_guard.client, "schema_version conn")?;
+
+            read_schema_version(&conn_guard)
+                .await
+                .map_err(|e| rustler::Error::Term(Box::new(e)))
+        })?;
+
+        Ok(result)
+    } else {
+        Err(rustler::Error::Term(Box::new("Invalid connection ID")))
+    }
+}
+
+/// Report SQLite's heap usage as `(used, highwater)` in bytes.
+///
+/// These are **process-wide** statistics covering every connection in the VM,
+/// not just one database: `used` is memory currently allocated by SQLite and
+/// `highwater` the maximum since startup (or the last reset).
+///
+/// # Arguments
+/// - `reset_highwater`: Reset the high-water mark to the current usage after reading it
+///
+/// # Examples
+/// ```elixir
+/// {:ok, %{used: used, highwater: peak}} = EctoLibSql.Native.memory_used()
+/// ```
+#[rustler::nif]
+pub fn memory_used_native(reset_highwater: bool) -> (i64, i64) {
+    crate::ffi::memory_used(reset_highwater)
+}
+
+/// Ask SQLite to free up to `bytes` of unused heap memory (page cache and lookaside).
+///
+/// Like `memory_used_native`, this acts on the whole process rather than one
+/// connection. SQLite only releases memory when built with
+/// `SQLITE_ENABLE_MEMORY_MANAGEMENT`; otherwise it is a no-op that returns 0.
+///
+/// # Arguments
+/// - `bytes`: Upper bound on the amount of memory to release
+///
+/// Returns the number of bytes actually freed.
+#[rustler::nif(schedule = "DirtyCpu")]
+pub fn release_memory_native(bytes: i32) -> i32 {
+    crate::ffi::release_memory(bytes)
+}
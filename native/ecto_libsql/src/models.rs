@@ -4,7 +4,9 @@
 /// including connection wrappers, transaction entries, and cursor state.
 use libsql::{Transaction, Value};
 use rustler::Resource;
+use std::sync::atomic::AtomicUsize;
 use std::sync::Arc;
+use std::time::Instant;
 
 /// LibSQL connection wrapper - resource passed to Elixir
 ///
@@ -16,6 +18,69 @@ pub struct LibSQLConn {
     pub db: libsql::Database,
     /// An active connection to the database
     pub client: Arc<std::sync::Mutex<libsql::Connection>>,
+    /// How the connection was established (local file, remote, or embedded replica)
+    pub mode: Mode,
+    /// Transaction behaviour atom used when a transaction is begun without an explicit mode
+    /// (`:deferred` unless overridden by the `default_transaction_mode` connect option)
+    pub default_transaction_mode: rustler::Atom,
+    /// Whether the connection was opened with the `read_only` connect option; state-changing
+    /// pragmas are then rejected with `{:error, :read_only}`
+    pub read_only: bool,
+    /// Whether empty text parameters are bound as NULL (`empty_string_as_null` connect option)
+    pub empty_string_as_null: bool,
+    /// Whether prepared statements bypass `STMT_REGISTRY` (`disable_statement_cache` connect
+    /// option); each use then prepares and finalises a fresh statement
+    pub statement_cache_disabled: bool,
+    /// Whether a remote connection that drops is rebuilt and the failed query retried once
+    /// (`auto_reconnect` connect option)
+    pub auto_reconnect: bool,
+    /// Longest SQL text in bytes that may be executed (`max_sql_length` connect option);
+    /// longer statements are rejected with `{:error, :sql_too_long}`
+    pub max_sql_length: Option<usize>,
+    /// Caller-chosen label such as a tenant name (`tag` connect option), reported by
+    /// `connection_info_native` and used to filter `list_connections_native`
+    pub tag: Option<String>,
+    /// Whether `PRAGMA optimize` runs when the connection is closed (`optimize_on_close`
+    /// connect option)
+    pub optimize_on_close: bool,
+    /// Retry schedule for statements that fail with `SQLITE_BUSY`, set by a `{:backoff, ...}`
+    /// policy in `set_busy_handler_native`; `None` leaves busy handling to `SQLite`
+    pub busy_backoff: Option<BusyBackoff>,
+    /// Cumulative sync statistics (only updated for remote replicas)
+    pub replica_metrics: ReplicaMetrics,
+}
+
+/// Cumulative sync statistics for an embedded replica connection
+///
+/// Updated after every explicit or frame-targeted sync so dashboards can graph
+/// replication activity over time.
+#[derive(Debug, Default, Clone)]
+pub struct ReplicaMetrics {
+    /// Number of syncs that completed successfully
+    pub total_syncs: u64,
+    /// Number of syncs that failed or timed out
+    pub failed_syncs: u64,
+    /// Total frames applied across all successful syncs
+    pub total_frames_applied: u64,
+    /// Unix timestamp in milliseconds of the last successful sync
+    pub last_sync_at_ms: Option<u64>,
+    /// Error message of the most recent failed sync (kept after later successes)
+    pub last_error: Option<String>,
+}
+
+impl ReplicaMetrics {
+    /// Record a successful sync that applied `frames_synced` frames at `at_ms`.
+    pub fn record_success(&mut self, frames_synced: u64, at_ms: u64) {
+        self.total_syncs += 1;
+        self.total_frames_applied += frames_synced;
+        self.last_sync_at_ms = Some(at_ms);
+    }
+
+    /// Record a failed sync.
+    pub fn record_failure(&mut self, error: &str) {
+        self.failed_syncs += 1;
+        self.last_error = Some(error.to_string());
+    }
 }
 
 /// Resource implementation for LibSQLConn
@@ -31,10 +96,48 @@ pub struct CursorData {
     pub conn_id: String,
     /// Column names from the query
     pub columns: Vec<String>,
-    /// All rows returned by the query
+    /// Rows read from the result set but not yet fetched past `position`
     pub rows: Vec<Vec<Value>>,
+    /// Result set still being read from a remote server; `None` for local cursors and once
+    /// the remote result set is exhausted
+    pub pending: Option<Arc<std::sync::Mutex<libsql::Rows>>>,
+    /// Batches read ahead by a background task for cursors declared with `prefetch`;
+    /// `None` for other cursors and once the result set is exhausted
+    pub prefetch: Option<Prefetch>,
     /// Current position in the result set
     pub position: usize,
+    /// When the cursor was declared, used to reap leaked cursors
+    pub created_at: Instant,
+}
+
+/// Read-ahead buffer of a prefetching cursor
+///
+/// A background task reads the result set in batches and queues them on a bounded
+/// channel, waiting whenever the channel is full until `fetch_cursor` drains it.
+#[derive(Debug, Clone)]
+pub struct Prefetch {
+    /// Receiving end of the batch channel; an `Err` carries the error that stopped the reader
+    pub batches:
+        Arc<std::sync::Mutex<tokio::sync::mpsc::Receiver<Result<Vec<Vec<Value>>, String>>>>,
+    /// Rows read by the background task that `fetch_cursor` has not received yet
+    pub queued_rows: Arc<AtomicUsize>,
+}
+
+/// Prepared statement cached in the statement registry
+///
+/// Records the SQL and the schema version (`PRAGMA schema_version`) the statement's
+/// metadata was last checked against, so stale statements can be re-prepared after DDL
+/// changes. Remote connections leave the version unset at prepare time, keeping `prepare`
+/// to a single round trip; the first metadata access records it.
+#[derive(Debug)]
+pub struct CachedStatement {
+    /// Original SQL used to prepare the statement
+    pub sql: String,
+    /// Schema version the metadata was last checked against, or `None` on a remote
+    /// connection before the first metadata access
+    pub schema_version: Option<i64>,
+    /// The prepared statement
+    pub stmt: libsql::Statement,
 }
 
 /// Transaction entry with ownership tracking
@@ -45,6 +148,57 @@ pub struct TransactionEntry {
     pub conn_id: String,
     /// The actual transaction object
     pub transaction: Transaction,
+    /// Names of the savepoints opened through the savepoint NIFs, innermost last
+    pub savepoints: Vec<String>,
+}
+
+/// Cancellable operation registered by `new_operation_native`
+///
+/// Tracks whether the operation has been cancelled and, while its statement runs, a
+/// handle to the connection so `cancel_operation_native` can interrupt it.
+pub struct OperationEntry {
+    /// Set by `cancel_operation_native`
+    pub cancelled: bool,
+    /// Connection running the operation's statement; `None` until it starts
+    pub running: Option<libsql::Connection>,
+    /// When the handle was created, used to expire handles that never start
+    pub created_at: Instant,
+}
+
+/// How a connection waits when the database is locked, set by `set_busy_handler_native`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BusyPolicy {
+    /// Let `SQLite` retry for up to this many milliseconds (its built-in busy timeout)
+    Timeout(u64),
+    /// Return `SQLITE_BUSY` at once
+    FailFast,
+    /// Retry the statement with exponentially growing delays
+    Backoff(BusyBackoff),
+}
+
+/// Exponential retry schedule for statements that fail with `SQLITE_BUSY`
+///
+/// The first retry waits `base_ms`, and each later one twice as long as the one
+/// before, until `max_ms` have been spent waiting in total.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BusyBackoff {
+    /// Delay before the first retry, in milliseconds (at least 1)
+    pub base_ms: u64,
+    /// Total time to spend waiting before giving up, in milliseconds
+    pub max_ms: u64,
+}
+
+/// Built-in reduction that `query_reduce_native` applies to one column of a query
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReduceOp {
+    /// Sum of the numeric values (integer unless a real is seen)
+    Sum,
+    /// Smallest value in `SQLite` sort order
+    Min,
+    /// Largest value in `SQLite` sort order
+    Max,
+    /// Distinct values in order of first appearance
+    Distinct,
 }
 
 /// Connection mode enumeration
@@ -0,0 +1,127 @@
+/// Cancellable operations for `LibSQL` databases.
+///
+/// `interrupt_connection` stops whatever is running on a connection. An operation
+/// handle narrows that to a single call: the handle is created up front, passed to
+/// the query that should be cancellable, and `cancel_operation_native` then aborts
+/// only that query. A query cancelled before it starts never runs; one cancelled
+/// while its statement is running is interrupted.
+///
+/// Handles are single-use and are removed from the registry when their query finishes.
+/// Handles that are never passed to a query expire after `OPERATION_TTL`.
+use crate::constants::OPERATION_REGISTRY;
+use crate::models::OperationEntry;
+use crate::utils::safe_lock;
+use rustler::{Atom, NifResult};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// Error message returned by a query whose operation was cancelled.
+pub const CANCELLED: &str = "Operation cancelled";
+
+/// How long a handle may wait for its query to start before it is treated as abandoned.
+pub const OPERATION_TTL: Duration = Duration::from_secs(60 * 60);
+
+/// Create an operation handle.
+///
+/// The handle is not tied to a connection until its query starts, so it can be
+/// created before a connection is checked out from a pool. Creating a handle also
+/// removes handles that were never started within `OPERATION_TTL`, so abandoned
+/// handles cannot accumulate.
+///
+/// Returns the operation handle.
+#[rustler::nif]
+pub fn new_operation_native() -> NifResult<String> {
+    let op_id = uuid::Uuid::new_v4().to_string();
+    let now = Instant::now();
+    let entry = OperationEntry {
+        cancelled: false,
+        running: None,
+        created_at: now,
+    };
+
+    let mut registry = safe_lock(&OPERATION_REGISTRY, "new_operation_native registry")?;
+    expire_operations(&mut registry, now);
+    registry.insert(op_id.clone(), entry);
+
+    Ok(op_id)
+}
+
+/// Remove handles that are not running and were created more than `OPERATION_TTL`
+/// before `now`. Returns the number removed.
+pub fn expire_operations(registry: &mut HashMap<String, OperationEntry>, now: Instant) -> usize {
+    let before = registry.len();
+    registry.retain(|_, entry| {
+        entry.running.is_some() || now.saturating_duration_since(entry.created_at) < OPERATION_TTL
+    });
+    before - registry.len()
+}
+
+/// Cancel an operation.
+///
+/// Marks the operation cancelled and, if its statement is running, interrupts it.
+/// The registry lock is held while interrupting, and a running operation removes
+/// itself under that lock before releasing its connection, so the interrupt can
+/// only reach the operation's own statement.
+///
+/// # Arguments
+/// - `op_id`: Operation handle from `new_operation_native`
+///
+/// Returns `:ok`, or an error if the operation is unknown or has already finished.
+#[rustler::nif(schedule = "DirtyIo")]
+pub fn cancel_operation_native(op_id: &str) -> NifResult<Atom> {
+    let mut registry = safe_lock(&OPERATION_REGISTRY, "cancel_operation_native registry")?;
+
+    let entry = registry
+        .get_mut(op_id)
+        .ok_or_else(|| rustler::Error::Term(Box::new("Operation not found")))?;
+
+    entry.cancelled = true;
+
+    if let Some(conn) = &entry.running {
+        conn.interrupt()
+            .map_err(|e| rustler::Error::Term(Box::new(format!("interrupt failed: {e}"))))?;
+    }
+
+    Ok(rustler::types::atom::ok())
+}
+
+/// Mark an operation as running on `conn`, unless it was cancelled first.
+///
+/// Call once the connection is held, just before the statement starts. A cancelled
+/// operation is removed and reported as `CANCELLED`.
+pub fn start_operation(op_id: &str, conn: &libsql::Connection) -> Result<(), String> {
+    let mut registry = OPERATION_REGISTRY
+        .lock()
+        .map_err(|e| format!("Mutex poisoned in start_operation: {e}"))?;
+
+    let cancelled = match registry.get_mut(op_id) {
+        None => return Err("Operation not found".to_string()),
+        Some(entry) if entry.running.is_some() => {
+            return Err("Operation is already running".to_string())
+        }
+        Some(entry) => {
+            if !entry.cancelled {
+                entry.running = Some(conn.clone());
+            }
+            entry.cancelled
+        }
+    };
+
+    if cancelled {
+        registry.remove(op_id);
+        return Err(CANCELLED.to_string());
+    }
+
+    Ok(())
+}
+
+/// Remove a finished operation, returning whether it was cancelled.
+///
+/// Call before releasing the connection the statement ran on.
+pub fn finish_operation(op_id: &str) -> bool {
+    OPERATION_REGISTRY
+        .lock()
+        .ok()
+        .and_then(|mut registry| registry.remove(op_id))
+        .is_some_and(|entry| entry.cancelled)
+}
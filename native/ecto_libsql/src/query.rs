@@ -3,12 +3,18 @@
 /// This module handles executing SQL queries, returning results, and managing
 /// manual synchronization for remote replicas.
 use crate::constants::*;
+use crate::decode;
+use crate::operation::{finish_operation, start_operation, CANCELLED};
+use crate::transaction::TransactionEntryGuard;
 use crate::utils::{
-    build_empty_result, collect_rows, enhance_constraint_error, safe_lock, safe_lock_arc,
-    should_use_query,
+    apply_bind_options, build_count_sql, build_empty_result, build_keyset_sql, collect_rows,
+    enhance_constraint_error, ensure_sql_allowed, reconnect, retry_busy, safe_lock, safe_lock_arc,
+    should_reconnect, should_use_query, statement_error, value_to_term, ColumnReducer, Reduced,
 };
 use libsql::Value;
-use rustler::{Atom, Env, NifResult, Term};
+use rustler::{Atom, Encoder, Env, NifResult, Term};
+use std::collections::HashMap;
+use std::time::Instant;
 
 /// Execute a SQL query with arguments and return results.
 ///
@@ -22,6 +28,12 @@ use rustler::{Atom, Env, NifResult, Term};
 /// **Automatic Sync**: For remote replicas, writes are automatically synced to the remote database
 /// by LibSQL. Manual sync is still available via `do_sync()` for explicit control.
 ///
+/// **Auto-reconnect**: On connections opened with `auto_reconnect`, a query that fails because
+/// the connection was lost is retried once on a fresh connection, unless a transaction is open.
+///
+/// **Busy backoff**: On connections given a `{:backoff, base_ms, max_ms}` busy policy, a
+/// statement that fails with `SQLITE_BUSY` is retried on that schedule.
+///
 /// # Arguments
 /// - `env`: Elixir environment
 /// - `conn_id`: Database connection ID
@@ -38,6 +50,8 @@ pub fn query_args<'a>(
     query: &str,
     args: Vec<Term<'a>>,
 ) -> NifResult<Term<'a>> {
+    ensure_sql_allowed(conn_id, query)?;
+
     let client = {
         let conn_map = safe_lock(&CONNECTION_REGISTRY, "query_args conn_map")?;
         conn_map
@@ -51,25 +65,34 @@ pub fn query_args<'a>(
         .map(|t| crate::utils::decode_term_to_value(t))
         .collect();
 
-    let params = params.map_err(|e| rustler::Error::Term(Box::new(e)))?;
+    let mut params = params.map_err(|e| rustler::Error::Term(Box::new(e)))?;
+    apply_bind_options(conn_id, &mut params)?;
 
     // Determine whether to use query() or execute() based on statement
     let use_query = should_use_query(query);
 
     // Clone the inner connection Arc and drop the outer lock before async operations
     // This reduces lock coupling and prevents holding the LibSQLConn lock during I/O
-    let connection = {
+    let (connection, auto_reconnect, busy_backoff) = {
         let client_guard = safe_lock_arc(&client, "query_args client")?;
-        client_guard.client.clone()
+        (
+            client_guard.client.clone(),
+            client_guard.auto_reconnect,
+            client_guard.busy_backoff,
+        )
     }; // Outer lock dropped here
 
+    // Keep a single copy of the parameters, and only when a retry is possible. Each retry
+    // that actually runs binds its own clone of it, as libsql takes parameters by value.
+    let retry_params = (auto_reconnect || busy_backoff.is_some()).then(|| params.clone());
+
     // SAFETY: We're inside TOKIO_RUNTIME.block_on(), so this is synchronous execution.
     // The std::sync::Mutex guards are safe to hold across await points here because
     // we're not in a true async context - block_on runs the future to completion.
     #[allow(clippy::await_holding_lock)]
     {
         TOKIO_RUNTIME.block_on(async {
-            let conn_guard: std::sync::MutexGuard<libsql::Connection> =
+            let mut conn_guard: std::sync::MutexGuard<libsql::Connection> =
                 safe_lock_arc(&connection, "query_args conn")?;
 
             // NOTE: LibSQL automatically syncs writes to remote for embedded replicas.
@@ -79,9 +102,31 @@ pub fn query_args<'a>(
             // and cause performance issues. Manual sync via do_sync() is still available for
             // explicit user control.
 
+            let started = Instant::now();
+
             if use_query {
                 // Statements that return rows (SELECT, or INSERT/UPDATE/DELETE with RETURNING)
-                let res = conn_guard.query(query, params).await;
+                let mut res = conn_guard.query(query, params).await;
+
+                if let Some(retry_params) = retry_params.as_ref().filter(|_| auto_reconnect) {
+                    if matches!(&res, Err(e) if should_reconnect(&conn_guard, e)) {
+                        drop(conn_guard);
+                        reconnect(&client)?;
+                        conn_guard = safe_lock_arc(&connection, "query_args conn")?;
+                        res = conn_guard.query(query, retry_params.clone()).await;
+                    }
+                }
+
+                // As with reconnects, never retry inside a transaction: the busy error may
+                // have come from a statement whose earlier writes the caller must handle
+                let backoff = busy_backoff.filter(|_| conn_guard.is_autocommit());
+                if let (Some(backoff), Some(retry_params)) = (backoff, &retry_params) {
+                    let conn: &libsql::Connection = &conn_guard;
+                    res = retry_busy(backoff, started, res, move || {
+                        conn.query(query, retry_params.clone())
+                    })
+                    .await;
+                }
 
                 match res {
                     Ok(res_rows) => {
@@ -93,12 +138,30 @@ pub fn query_args<'a>(
                         let enhanced_msg = enhance_constraint_error(&conn_guard, &error_msg)
                             .await
                             .unwrap_or(error_msg);
-                        Err(rustler::Error::Term(Box::new(enhanced_msg)))
+                        Err(statement_error(enhanced_msg))
                     }
                 }
             } else {
                 // Statements that don't return rows (INSERT/UPDATE/DELETE without RETURNING)
-                let res = conn_guard.execute(query, params).await;
+                let mut res = conn_guard.execute(query, params).await;
+
+                if let Some(retry_params) = retry_params.as_ref().filter(|_| auto_reconnect) {
+                    if matches!(&res, Err(e) if should_reconnect(&conn_guard, e)) {
+                        drop(conn_guard);
+                        reconnect(&client)?;
+                        conn_guard = safe_lock_arc(&connection, "query_args conn")?;
+                        res = conn_guard.execute(query, retry_params.clone()).await;
+                    }
+                }
+
+                let backoff = busy_backoff.filter(|_| conn_guard.is_autocommit());
+                if let (Some(backoff), Some(retry_params)) = (backoff, &retry_params) {
+                    let conn: &libsql::Connection = &conn_guard;
+                    res = retry_busy(backoff, started, res, move || {
+                        conn.execute(query, retry_params.clone())
+                    })
+                    .await;
+                }
 
                 match res {
                     Ok(rows_affected) => Ok(build_empty_result(env, rows_affected)),
@@ -107,7 +170,7 @@ pub fn query_args<'a>(
                         let enhanced_msg = enhance_constraint_error(&conn_guard, &error_msg)
                             .await
                             .unwrap_or(error_msg);
-                        Err(rustler::Error::Term(Box::new(enhanced_msg)))
+                        Err(statement_error(enhanced_msg))
                     }
                 }
             }
@@ -115,6 +178,176 @@ pub fn query_args<'a>(
     }
 }
 
+/// Execute a SQL query with per-call options: timing, cancellation, or both.
+///
+/// This is the path for `handle_execute/4` calls that pass `timing: true` or
+/// `cancellable: op`. The statement is prepared, run and its rows collected as
+/// separate steps, each timed in microseconds. Runs inside the transaction when
+/// `trx_id` is given.
+///
+/// With `op_id` the query runs as that cancellable operation (see `operation`): it
+/// fails with `"Operation cancelled"` if `cancel_operation_native` is called before
+/// or while it runs.
+///
+/// `SQLite` computes rows as they are stepped, so for row-returning statements most
+/// of the work of the query lands in `fetch_us`; `execute_us` covers binding and the
+/// first step. For statements without rows `fetch_us` is 0.
+///
+/// Unlike `query_args`, a failed statement is never retried: `auto_reconnect` and the
+/// `{:backoff, base_ms, max_ms}` busy policy do not apply here. A retry would re-run a
+/// statement whose operation has already finished, and would skew the timings.
+///
+/// # Arguments
+/// - `env`: Elixir environment
+/// - `conn_id`: Database connection ID
+/// - `trx_id`: Transaction to run in, or `None` to use the connection directly
+/// - `op_id`: Operation handle from `new_operation_native`, or `None` if not cancellable
+/// - `query`: SQL query string
+/// - `args`: Query parameter values
+///
+/// Returns a map with keys `columns`, `rows` and `num_rows`, plus a `timing` map with
+/// keys `prepare_us`, `execute_us`, `fetch_us` and `total_us`.
+#[rustler::nif(schedule = "DirtyIo")]
+pub fn query_with_options_native<'a>(
+    env: Env<'a>,
+    conn_id: &str,
+    trx_id: Option<String>,
+    op_id: Option<String>,
+    query: &str,
+    args: Vec<Term<'a>>,
+) -> NifResult<Term<'a>> {
+    let started = Instant::now();
+
+    ensure_sql_allowed(conn_id, query)?;
+
+    let mut params: Vec<Value> = args
+        .into_iter()
+        .map(|t| crate::utils::decode_term_to_value(t))
+        .collect::<Result<_, _>>()
+        .map_err(|e| rustler::Error::Term(Box::new(e)))?;
+    apply_bind_options(conn_id, &mut params)?;
+
+    let use_query = should_use_query(query);
+    let op_id = op_id.as_deref();
+
+    // Take the transaction first so a mismatched trx_id fails before any work
+    let trx_guard = trx_id
+        .as_deref()
+        .map(|id| TransactionEntryGuard::take(id, conn_id))
+        .transpose()?;
+
+    let connection = {
+        let conn_map = safe_lock(&CONNECTION_REGISTRY, "query_with_options_native conn_map")?;
+        let client = conn_map
+            .get(conn_id)
+            .ok_or_else(|| rustler::Error::Term(Box::new("Invalid connection ID")))?;
+        let client_guard = safe_lock_arc(client, "query_with_options_native client")?;
+        client_guard.client.clone()
+    };
+
+    // SAFETY: We use TOKIO_RUNTIME.block_on(), which runs the future synchronously on a dedicated
+    // thread pool. This prevents deadlocks that could occur if we were in a true async context
+    // with std::sync::Mutex guards held across await points.
+    #[allow(clippy::await_holding_lock)]
+    TOKIO_RUNTIME.block_on(async {
+        let outcome = match &trx_guard {
+            Some(guard) => {
+                let trx = guard.transaction()?;
+                run_operation(env, trx, op_id, query, params, use_query).await
+            }
+            None => {
+                let conn_guard = safe_lock_arc(&connection, "query_with_options_native conn")?;
+                run_operation(env, &conn_guard, op_id, query, params, use_query).await
+            }
+        };
+
+        let (result, mut timing) = match outcome {
+            Ok(timed) => timed,
+            Err(error_msg) if error_msg == CANCELLED => {
+                return Err(rustler::Error::Term(Box::new(error_msg)));
+            }
+            Err(error_msg) => {
+                let conn_guard =
+                    safe_lock_arc(&connection, "query_with_options_native conn for error")?;
+                let enhanced_msg = enhance_constraint_error(&conn_guard, &error_msg)
+                    .await
+                    .unwrap_or(error_msg);
+                return Err(statement_error(enhanced_msg));
+            }
+        };
+
+        timing.insert("total_us".to_string(), elapsed_us(started));
+
+        result.map_put("timing", timing)
+    })
+}
+
+/// Run a statement on `conn`, as operation `op_id` when given.
+///
+/// The operation is finished before returning, while the caller still holds `conn`. A
+/// statement that fails after its operation was cancelled reports `CANCELLED`.
+async fn run_operation<'a>(
+    env: Env<'a>,
+    conn: &libsql::Connection,
+    op_id: Option<&str>,
+    query: &str,
+    params: Vec<Value>,
+    use_query: bool,
+) -> Result<(Term<'a>, HashMap<String, u64>), String> {
+    if let Some(op_id) = op_id {
+        start_operation(op_id, conn)?;
+    }
+
+    let outcome = run_timed(env, conn, query, params, use_query).await;
+    let cancelled = op_id.is_some_and(finish_operation);
+
+    match outcome {
+        Err(_) if cancelled => Err(CANCELLED.to_string()),
+        other => other,
+    }
+}
+
+/// Prepare, run and collect a statement, timing each step.
+async fn run_timed<'a>(
+    env: Env<'a>,
+    conn: &libsql::Connection,
+    query: &str,
+    params: Vec<Value>,
+    use_query: bool,
+) -> Result<(Term<'a>, HashMap<String, u64>), String> {
+    let mut timing: HashMap<String, u64> = HashMap::with_capacity(4);
+
+    let step = Instant::now();
+    let stmt = conn.prepare(query).await.map_err(|e| e.to_string())?;
+    timing.insert("prepare_us".to_string(), elapsed_us(step));
+
+    let result = if use_query {
+        let step = Instant::now();
+        let rows = stmt.query(params).await.map_err(|e| e.to_string())?;
+        timing.insert("execute_us".to_string(), elapsed_us(step));
+
+        let step = Instant::now();
+        let collected = collect_rows(env, rows)
+            .await
+            .map_err(|e| format!("{e:?}"))?;
+        timing.insert("fetch_us".to_string(), elapsed_us(step));
+        collected
+    } else {
+        let step = Instant::now();
+        let rows_affected = stmt.execute(params).await.map_err(|e| e.to_string())?;
+        timing.insert("execute_us".to_string(), elapsed_us(step));
+        timing.insert("fetch_us".to_string(), 0);
+        build_empty_result(env, rows_affected as u64)
+    };
+
+    Ok((result, timing))
+}
+
+/// Microseconds elapsed since `since`, saturating at `u64::MAX`.
+fn elapsed_us(since: Instant) -> u64 {
+    u64::try_from(since.elapsed().as_micros()).unwrap_or(u64::MAX)
+}
+
 /// Manually synchronize a remote replica database with the remote primary.
 ///
 /// For remote replicas, this triggers an explicit sync operation to pull the latest
@@ -180,6 +413,8 @@ pub fn do_sync(conn_id: &str, mode: Atom) -> NifResult<(Atom, String)> {
 /// Returns a map with keys: `columns`, `rows`, `num_rows`
 #[rustler::nif(schedule = "DirtyIo")]
 pub fn pragma_query<'a>(env: Env<'a>, conn_id: &str, pragma_stmt: &str) -> NifResult<Term<'a>> {
+    ensure_sql_allowed(conn_id, pragma_stmt)?;
+
     let conn_map = safe_lock(&CONNECTION_REGISTRY, "pragma_query conn_map")?;
 
     if let Some(client) = conn_map.get(conn_id) {
@@ -208,3 +443,288 @@ pub fn pragma_query<'a>(env: Env<'a>, conn_id: &str, pragma_stmt: &str) -> NifRe
         Err(rustler::Error::Term(Box::new("Invalid connection ID")))
     }
 }
+
+/// Count the rows of a table, optionally filtered by a WHERE clause.
+///
+/// Builds `SELECT count(*) FROM "table" [WHERE ...]` with the table name quoted as
+/// an identifier, binds `args` to the clause's placeholders and returns the count
+/// directly, so callers don't have to unwrap a one-row, one-column result.
+///
+/// # Arguments
+/// - `conn_id`: Database connection ID
+/// - `table`: Table name (quoted automatically)
+/// - `where_clause`: Optional SQL condition, without the `WHERE` keyword
+/// - `args`: Values for the condition's `?` placeholders
+///
+/// Returns the number of matching rows.
+#[rustler::nif(schedule = "DirtyIo")]
+pub fn count_native(
+    conn_id: &str,
+    table: &str,
+    where_clause: Option<String>,
+    args: Vec<Term>,
+) -> NifResult<i64> {
+    let sql = build_count_sql(table, where_clause.as_deref())
+        .map_err(|e| rustler::Error::Term(Box::new(e)))?;
+    ensure_sql_allowed(conn_id, &sql)?;
+
+    let mut params: Vec<Value> = args
+        .into_iter()
+        .map(crate::utils::decode_term_to_value)
+        .collect::<Result<_, _>>()
+        .map_err(|e| rustler::Error::Term(Box::new(e)))?;
+    apply_bind_options(conn_id, &mut params)?;
+
+    let client = {
+        let conn_map = safe_lock(&CONNECTION_REGISTRY, "count_native conn_map")?;
+        conn_map
+            .get(conn_id)
+            .cloned()
+            .ok_or_else(|| rustler::Error::Term(Box::new("Invalid connection ID")))?
+    }; // Lock dropped here
+
+    // SAFETY: We're inside TOKIO_RUNTIME.block_on(), so this is synchronous execution.
+    // The std::sync::Mutex guards are safe to hold across await points here because
+    // we're not in a true async context - block_on runs the future to completion.
+    #[allow(clippy::await_holding_lock)]
+    TOKIO_RUNTIME.block_on(async {
+        let client_guard = safe_lock_arc(&client, "count_native client")?;
+        let conn_guard: std::sync::MutexGuard<libsql::Connection> =
+            safe_lock_arc(&client_guard.client, "count_native conn")?;
+
+        let mut rows = conn_guard
+            .query(&sql, params)
+            .await
+            .map_err(|e| rustler::Error::Term(Box::new(format!("Count query failed: {e}"))))?;
+
+        let row = rows
+            .next()
+            .await
+            .map_err(|e| rustler::Error::Term(Box::new(format!("Count query failed: {e}"))))?
+            .ok_or_else(|| rustler::Error::Term(Box::new("Count query returned no rows")))?;
+
+        row.get::<i64>(0)
+            .map_err(|e| rustler::Error::Term(Box::new(format!("Failed to read count: {e}"))))
+    })
+}
+
+/// Run a query and reduce one column of its rows with a built-in reducer.
+///
+/// Rows are folded in Rust as they are stepped, so only the reduced value crosses
+/// into Elixir. Useful for aggregations that are awkward in SQL or whose input is
+/// too large to materialise. NULLs are skipped.
+///
+/// # Arguments
+/// - `env`: Elixir environment
+/// - `conn_id`: Database connection ID
+/// - `sql`: Query whose rows are reduced
+/// - `args`: Query parameter values
+/// - `op`: `:sum`, `:min`, `:max` or `:distinct`
+/// - `column`: Column name (matched case-insensitively) or zero-based index
+///
+/// Returns the reduced value (`nil` if there were no non-NULL values), or for
+/// `:distinct` a list of values in order of first appearance.
+#[rustler::nif(schedule = "DirtyIo")]
+pub fn query_reduce_native<'a>(
+    env: Env<'a>,
+    conn_id: &str,
+    sql: &str,
+    args: Vec<Term<'a>>,
+    op: Atom,
+    column: Term<'a>,
+) -> NifResult<Term<'a>> {
+    ensure_sql_allowed(conn_id, sql)?;
+
+    let op = decode::decode_reduce_op(op).ok_or_else(|| {
+        rustler::Error::Term(Box::new(format!(
+            "Unknown reducer: {op:?}. Use :sum, :min, :max, or :distinct"
+        )))
+    })?;
+
+    let mut params: Vec<Value> = args
+        .into_iter()
+        .map(crate::utils::decode_term_to_value)
+        .collect::<Result<_, _>>()
+        .map_err(|e| rustler::Error::Term(Box::new(e)))?;
+    apply_bind_options(conn_id, &mut params)?;
+
+    let client = {
+        let conn_map = safe_lock(&CONNECTION_REGISTRY, "query_reduce_native conn_map")?;
+        conn_map
+            .get(conn_id)
+            .cloned()
+            .ok_or_else(|| rustler::Error::Term(Box::new("Invalid connection ID")))?
+    }; // Lock dropped here
+
+    // SAFETY: We're inside TOKIO_RUNTIME.block_on(), so this is synchronous execution.
+    // The std::sync::Mutex guards are safe to hold across await points here because
+    // we're not in a true async context - block_on runs the future to completion.
+    #[allow(clippy::await_holding_lock)]
+    let reduced = TOKIO_RUNTIME.block_on(async {
+        let client_guard = safe_lock_arc(&client, "query_reduce_native client")?;
+        let conn_guard: std::sync::MutexGuard<libsql::Connection> =
+            safe_lock_arc(&client_guard.client, "query_reduce_native conn")?;
+
+        let mut rows = conn_guard
+            .query(sql, params)
+            .await
+            .map_err(|e| rustler::Error::Term(Box::new(format!("Reduce query failed: {e}"))))?;
+
+        let index = reduce_column_index(&rows, column)?;
+        let mut reducer = ColumnReducer::new(op);
+
+        while let Some(row) = rows
+            .next()
+            .await
+            .map_err(|e| rustler::Error::Term(Box::new(format!("Reduce query failed: {e}"))))?
+        {
+            let value = row.get_value(index).map_err(|e| {
+                rustler::Error::Term(Box::new(format!("Failed to read column {index}: {e}")))
+            })?;
+            reducer
+                .push(value)
+                .map_err(|e| rustler::Error::Term(Box::new(e)))?;
+        }
+
+        Ok::<_, rustler::Error>(reducer.finish())
+    })?;
+
+    match reduced {
+        Reduced::Value(value) => value_to_term(env, &value),
+        Reduced::Distinct(values) => values
+            .iter()
+            .map(|value| value_to_term(env, value))
+            .collect::<NifResult<Vec<Term>>>()
+            .map(|terms| terms.encode(env)),
+    }
+}
+
+/// Resolve the column to reduce, given as a name or a zero-based index.
+fn reduce_column_index(rows: &libsql::Rows, column: Term) -> NifResult<i32> {
+    let count = rows.column_count();
+
+    if let Ok(index) = column.decode::<i64>() {
+        return i32::try_from(index)
+            .ok()
+            .filter(|i| (0..count).contains(i))
+            .ok_or_else(|| {
+                rustler::Error::Term(Box::new(format!(
+                    "Column index {index} out of range for {count} columns"
+                )))
+            });
+    }
+
+    let name: String = column.decode().map_err(|_| {
+        rustler::Error::Term(Box::new("column must be a name or a zero-based index"))
+    })?;
+
+    (0..count)
+        .find(|&i| {
+            rows.column_name(i)
+                .is_some_and(|c| c.eq_ignore_ascii_case(&name))
+        })
+        .ok_or_else(|| {
+            rustler::Error::Term(Box::new(format!(
+                "Column '{name}' not found in query results"
+            )))
+        })
+}
+
+/// Fetch one page of a query using keyset (seek) pagination.
+///
+/// Wraps `base_sql` as `SELECT * FROM (base) WHERE order_col > ? ORDER BY order_col LIMIT n`,
+/// which stays fast on large tables where `OFFSET` would rescan skipped rows. `order_col`
+/// must be a column of the base query's result with unique, non-NULL values.
+///
+/// # Arguments
+/// - `env`: Elixir environment
+/// - `conn_id`: Database connection ID
+/// - `base_sql`: Query to paginate, without ORDER BY or LIMIT
+/// - `order_col`: Column to order and seek by (quoted automatically)
+/// - `last_key`: Key of the last row of the previous page, or `nil` for the first page
+/// - `limit`: Page size
+/// - `args`: Values for the base query's placeholders
+///
+/// Returns `{result, next_key}` where `result` is a map with keys `columns`, `rows`,
+/// `num_rows`, and `next_key` is the `order_col` value of the last row, or `nil` when
+/// the page is short and there is nothing left to fetch.
+#[rustler::nif(schedule = "DirtyIo")]
+pub fn keyset_page_native<'a>(
+    env: Env<'a>,
+    conn_id: &str,
+    base_sql: &str,
+    order_col: &str,
+    last_key: Option<Term<'a>>,
+    limit: u64,
+    args: Vec<Term<'a>>,
+) -> NifResult<Term<'a>> {
+    let sql = build_keyset_sql(base_sql, order_col, last_key.is_some(), limit)
+        .map_err(|e| rustler::Error::Term(Box::new(e)))?;
+    ensure_sql_allowed(conn_id, &sql)?;
+
+    let mut params: Vec<Value> = args
+        .into_iter()
+        .chain(last_key)
+        .map(crate::utils::decode_term_to_value)
+        .collect::<Result<_, _>>()
+        .map_err(|e| rustler::Error::Term(Box::new(e)))?;
+    apply_bind_options(conn_id, &mut params)?;
+
+    let client = {
+        let conn_map = safe_lock(&CONNECTION_REGISTRY, "keyset_page_native conn_map")?;
+        conn_map
+            .get(conn_id)
+            .cloned()
+            .ok_or_else(|| rustler::Error::Term(Box::new("Invalid connection ID")))?
+    }; // Lock dropped here
+
+    // SAFETY: We're inside TOKIO_RUNTIME.block_on(), so this is synchronous execution.
+    // The std::sync::Mutex guards are safe to hold across await points here because
+    // we're not in a true async context - block_on runs the future to completion.
+    #[allow(clippy::await_holding_lock)]
+    let result = TOKIO_RUNTIME.block_on(async {
+        let client_guard = safe_lock_arc(&client, "keyset_page_native client")?;
+        let conn_guard: std::sync::MutexGuard<libsql::Connection> =
+            safe_lock_arc(&client_guard.client, "keyset_page_native conn")?;
+
+        let rows = conn_guard
+            .query(&sql, params)
+            .await
+            .map_err(|e| rustler::Error::Term(Box::new(format!("Keyset query failed: {e}"))))?;
+
+        collect_rows(env, rows).await
+    })?;
+
+    let next_key = keyset_next_key(env, result, order_col, limit)?;
+    Ok((result, next_key).encode(env))
+}
+
+/// Read the seek key for the next keyset page from the last row of `result`.
+///
+/// Returns `nil` when the page holds fewer than `limit` rows, since no rows remain.
+fn keyset_next_key<'a>(
+    env: Env<'a>,
+    result: Term<'a>,
+    order_col: &str,
+    limit: u64,
+) -> NifResult<Term<'a>> {
+    let rows: Vec<Vec<Term<'a>>> = result.map_get("rows".encode(env))?.decode()?;
+    if (rows.len() as u64) < limit {
+        return Ok(nil().encode(env));
+    }
+
+    let columns: Vec<String> = result.map_get("columns".encode(env))?.decode()?;
+    let index = columns
+        .iter()
+        .position(|c| c.eq_ignore_ascii_case(order_col))
+        .ok_or_else(|| {
+            rustler::Error::Term(Box::new(format!(
+                "Order column '{order_col}' not found in query results"
+            )))
+        })?;
+
+    rows.last()
+        .and_then(|row| row.get(index))
+        .copied()
+        .ok_or_else(|| rustler::Error::Term(Box::new("Failed to read keyset key from last row")))
+}
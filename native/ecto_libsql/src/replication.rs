@@ -11,7 +11,7 @@
 /// This pattern is safe because we use `TOKIO_RUNTIME.block_on()` which executes
 /// the entire async block on a dedicated thread pool, preventing deadlocks.
 use crate::constants::*;
-use crate::utils::{safe_lock, safe_lock_arc};
+use crate::utils::{record_sync_metrics, safe_lock, safe_lock_arc};
 use rustler::{Atom, NifResult};
 
 /// Get the current replication index (frame number) from a remote replica database.
@@ -93,20 +93,85 @@ pub fn sync_until(conn_id: &str, frame_no: u64) -> NifResult<Atom> {
             .map_err(|e| format!("Failed to lock client: {e:?}"))?;
 
         let timeout_duration = tokio::time::Duration::from_secs(DEFAULT_SYNC_TIMEOUT_SECS);
-        tokio::time::timeout(timeout_duration, client_guard.db.sync_until(frame_no))
-            .await
-            .map_err(|_| format!("sync_until timed out after {DEFAULT_SYNC_TIMEOUT_SECS} seconds"))?
-            .map_err(|e| format!("sync_until failed: {e}"))?;
+        let replicated =
+            tokio::time::timeout(timeout_duration, client_guard.db.sync_until(frame_no))
+                .await
+                .map_err(|_| {
+                    format!("sync_until timed out after {DEFAULT_SYNC_TIMEOUT_SECS} seconds")
+                })?
+                .map_err(|e| format!("sync_until failed: {e}"))?;
 
-        Ok::<_, String>(())
+        Ok::<_, String>(replicated.frames_synced() as u64)
     });
 
+    // The client lock taken inside block_on has been released by now
+    record_sync_metrics(&client, &result);
+
     match result {
-        Ok(()) => Ok(rustler::types::atom::ok()),
+        Ok(_) => Ok(rustler::types::atom::ok()),
         Err(e) => Err(rustler::Error::Term(Box::new(e))),
     }
 }
 
+/// Wait until a remote replica has applied at least `frame_no` frames.
+///
+/// Unlike `sync_until`, this does not pull from the primary itself: it polls the
+/// replica's replication index and returns once it reaches the target, leaving
+/// progress to the connection's automatic or explicit syncs. The connection lock is
+/// released between polls so those syncs can run.
+///
+/// # Arguments
+/// - `conn_id`: Database connection ID
+/// - `frame_no`: Target frame number, e.g. from `max_write_replication_index`
+/// - `timeout_ms`: Maximum time to wait
+///
+/// Returns `:ok` once the replica has caught up, `{:error, :wait_timeout}` if the
+/// timeout elapses first, or an error if the replication index cannot be read.
+#[rustler::nif(schedule = "DirtyIo")]
+pub fn wait_for_frame_native(conn_id: &str, frame_no: u64, timeout_ms: u64) -> NifResult<Atom> {
+    let conn_map = safe_lock(&CONNECTION_REGISTRY, "wait_for_frame conn_map")?;
+    let client = conn_map
+        .get(conn_id)
+        .ok_or_else(|| rustler::Error::Term(Box::new("Connection not found")))?
+        .clone();
+    drop(conn_map);
+
+    let deadline = tokio::time::Instant::now() + tokio::time::Duration::from_millis(timeout_ms);
+    let poll_interval = tokio::time::Duration::from_millis(FRAME_POLL_INTERVAL_MS);
+
+    // SAFETY: We use TOKIO_RUNTIME.block_on(), which runs the future synchronously on a dedicated
+    // thread pool. This prevents deadlocks that could occur if we were in a true async context
+    // with std::sync::Mutex guards held across await points.
+    #[allow(clippy::await_holding_lock)]
+    TOKIO_RUNTIME.block_on(async {
+        loop {
+            // Lock only for the read so syncs can make progress while we sleep
+            let applied = {
+                let client_guard = safe_lock_arc(&client, "wait_for_frame client")?;
+                client_guard
+                    .db
+                    .replication_index()
+                    .await
+                    .map_err(|e| {
+                        rustler::Error::Term(Box::new(format!("replication_index failed: {e}")))
+                    })?
+                    .unwrap_or(0)
+            };
+
+            if applied >= frame_no {
+                return Ok(rustler::types::atom::ok());
+            }
+
+            let now = tokio::time::Instant::now();
+            if now >= deadline {
+                return Err(rustler::Error::Term(Box::new(wait_timeout())));
+            }
+
+            tokio::time::sleep(poll_interval.min(deadline - now)).await;
+        }
+    })
+}
+
 /// Flush the replicator, pushing pending writes to the remote database.
 ///
 /// Forces any buffered writes to be sent to the remote primary database immediately.
@@ -154,6 +219,37 @@ pub fn flush_replicator(conn_id: &str) -> NifResult<u64> {
     }
 }
 
+/// Get cumulative sync metrics for a remote replica connection.
+///
+/// Counters are kept per connection and updated by every `do_sync` and
+/// `sync_until` call. For local and remote connections they stay at zero.
+///
+/// # Arguments
+/// - `conn_id`: Database connection ID
+///
+/// Returns `{total_syncs, failed_syncs, total_frames_applied, last_sync_at_ms, last_error}`,
+/// where the last two are `nil` until a sync has succeeded or failed respectively.
+#[rustler::nif(schedule = "DirtyIo")]
+pub fn replica_metrics(conn_id: &str) -> NifResult<(u64, u64, u64, Option<u64>, Option<String>)> {
+    let conn_map = safe_lock(&CONNECTION_REGISTRY, "replica_metrics conn_map")?;
+    let client = conn_map
+        .get(conn_id)
+        .ok_or_else(|| rustler::Error::Term(Box::new("Connection not found")))?
+        .clone();
+    drop(conn_map);
+
+    let client_guard = safe_lock_arc(&client, "replica_metrics client")?;
+    let metrics = client_guard.replica_metrics.clone();
+
+    Ok((
+        metrics.total_syncs,
+        metrics.failed_syncs,
+        metrics.total_frames_applied,
+        metrics.last_sync_at_ms,
+        metrics.last_error,
+    ))
+}
+
 /// Get the highest frame number from write operations on this database.
 ///
 /// This is useful for read-your-writes consistency across replicas. After performing
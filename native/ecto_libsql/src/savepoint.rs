@@ -3,6 +3,9 @@
 /// This module handles savepoints within transactions, allowing partial rollback
 /// without aborting the entire transaction. Savepoints provide a way to create
 /// checkpoints within a transaction that can be rolled back to independently.
+///
+/// Each transaction entry keeps a stack of the savepoint names opened here, mirroring
+/// `SQLite`'s own stack, so `savepoint_depth_native` can report how deeply nested it is.
 use crate::constants::*;
 use crate::decode::validate_savepoint_name;
 use crate::transaction::TransactionEntryGuard;
@@ -33,7 +36,7 @@ pub fn savepoint(conn_id: &str, trx_id: &str, name: &str) -> NifResult<Atom> {
     validate_savepoint_name(name)?;
 
     // Take transaction entry with ownership verification using guard
-    let guard = TransactionEntryGuard::take(trx_id, conn_id)?;
+    let mut guard = TransactionEntryGuard::take(trx_id, conn_id)?;
 
     let sql = format!("SAVEPOINT {name}");
 
@@ -45,6 +48,8 @@ pub fn savepoint(conn_id: &str, trx_id: &str, name: &str) -> NifResult<Atom> {
             .map_err(|e| rustler::Error::Term(Box::new(format!("Savepoint failed: {e}"))))
     })?;
 
+    guard.savepoints_mut()?.push(name.to_string());
+
     // Guard automatically re-inserts the transaction on drop
     Ok(rustler::types::atom::ok())
 }
@@ -67,7 +72,7 @@ pub fn release_savepoint(conn_id: &str, trx_id: &str, name: &str) -> NifResult<A
     validate_savepoint_name(name)?;
 
     // Take transaction entry with ownership verification using guard
-    let guard = TransactionEntryGuard::take(trx_id, conn_id)?;
+    let mut guard = TransactionEntryGuard::take(trx_id, conn_id)?;
 
     let sql = format!("RELEASE SAVEPOINT {name}");
 
@@ -79,6 +84,12 @@ pub fn release_savepoint(conn_id: &str, trx_id: &str, name: &str) -> NifResult<A
             .map_err(|e| rustler::Error::Term(Box::new(format!("Release savepoint failed: {e}"))))
     })?;
 
+    // Releasing a savepoint also releases every savepoint opened after it
+    let savepoints = guard.savepoints_mut()?;
+    if let Some(index) = innermost_savepoint(savepoints, name) {
+        savepoints.truncate(index);
+    }
+
     // Guard automatically re-inserts the transaction on drop
     Ok(rustler::types::atom::ok())
 }
@@ -101,7 +112,7 @@ pub fn rollback_to_savepoint(conn_id: &str, trx_id: &str, name: &str) -> NifResu
     validate_savepoint_name(name)?;
 
     // Take transaction entry with ownership verification using guard
-    let guard = TransactionEntryGuard::take(trx_id, conn_id)?;
+    let mut guard = TransactionEntryGuard::take(trx_id, conn_id)?;
 
     let sql = format!("ROLLBACK TO SAVEPOINT {name}");
 
@@ -115,6 +126,43 @@ pub fn rollback_to_savepoint(conn_id: &str, trx_id: &str, name: &str) -> NifResu
             })
     })?;
 
+    // The savepoint itself stays open; only those opened after it are discarded
+    let savepoints = guard.savepoints_mut()?;
+    if let Some(index) = innermost_savepoint(savepoints, name) {
+        savepoints.truncate(index + 1);
+    }
+
     // Guard automatically re-inserts the transaction on drop
     Ok(rustler::types::atom::ok())
 }
+
+/// Report how many savepoints are open in a transaction.
+///
+/// Counts the savepoints created with `savepoint` that have not been released or
+/// discarded by rolling back to an earlier one. Useful for tracking down mismatched
+/// release/rollback calls. Savepoints opened with raw `SAVEPOINT` SQL are not counted.
+///
+/// **Security**: Validates that the transaction belongs to the requesting connection.
+///
+/// # Arguments
+/// - `conn_id`: Database connection ID (for ownership validation)
+/// - `trx_id`: Transaction ID
+///
+/// Returns the depth, `0` when no savepoint is open.
+#[rustler::nif(schedule = "DirtyIo")]
+pub fn savepoint_depth_native(conn_id: &str, trx_id: &str) -> NifResult<usize> {
+    let mut guard = TransactionEntryGuard::take(trx_id, conn_id)?;
+
+    // Guard automatically re-inserts the transaction on drop
+    Ok(guard.savepoints_mut()?.len())
+}
+
+/// Find the most recently opened savepoint with `name`.
+///
+/// `SQLite` savepoint names are case-insensitive, and a repeated name refers to the
+/// innermost savepoint that uses it.
+pub(crate) fn innermost_savepoint(savepoints: &[String], name: &str) -> Option<usize> {
+    savepoints
+        .iter()
+        .rposition(|open| open.eq_ignore_ascii_case(name))
+}
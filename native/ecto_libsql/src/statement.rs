@@ -5,14 +5,19 @@
 /// - Executing prepared queries and statements
 /// - Introspecting statement structure (column count, names, parameter count)
 /// - Statement ownership verification
+/// - Re-preparing statements that were compiled against an older schema
 ///
 /// Prepared statements are cached in a registry and identified by statement IDs.
 /// Each statement is associated with a connection ID to prevent cross-connection misuse.
+/// Connections opened with `disable_statement_cache` keep only the SQL, in a registry of
+/// its own, and prepare (then finalise) a fresh statement on every use.
 use crate::{
-    constants::{CONNECTION_REGISTRY, STMT_REGISTRY, TOKIO_RUNTIME},
-    decode, utils,
+    constants::{CONNECTION_REGISTRY, STMT_REGISTRY, TOKIO_RUNTIME, UNCACHED_STMT_REGISTRY},
+    decode,
+    models::{CachedStatement, Mode},
+    utils,
 };
-use libsql::Value;
+use libsql::{Column, Value};
 use rustler::{Atom, Env, NifResult, Term};
 use std::sync::{Arc, Mutex};
 
@@ -30,6 +35,7 @@ use std::sync::{Arc, Mutex};
 pub fn prepare_statement(conn_id: &str, sql: &str) -> NifResult<String> {
     // UTF-8 validation is guaranteed by Rust's &str type and Rustler's conversion,
     // so we can rely on the type system rather than runtime checks.
+    utils::ensure_sql_allowed(conn_id, sql)?;
 
     let client = {
         let conn_map = utils::safe_lock(&CONNECTION_REGISTRY, "prepare_statement conn_map")?;
@@ -42,11 +48,28 @@ pub fn prepare_statement(conn_id: &str, sql: &str) -> NifResult<String> {
     let sql_to_prepare = sql.to_string();
 
     // Clone the inner connection Arc and drop the outer lock before async operations
-    let connection = {
+    let (connection, cache_disabled, is_remote) = {
         let client_guard = utils::safe_lock_arc(&client, "prepare_statement client")?;
-        client_guard.client.clone()
+        (
+            client_guard.client.clone(),
+            client_guard.statement_cache_disabled,
+            client_guard.mode == Mode::Remote,
+        )
     }; // Outer lock dropped here
 
+    if cache_disabled {
+        // Compile once so syntax errors surface now, then finalise; every use re-prepares
+        drop(prepare_on(&connection, &sql_to_prepare)?);
+
+        let stmt_id = uuid::Uuid::new_v4().to_string();
+        utils::safe_lock(
+            &UNCACHED_STMT_REGISTRY,
+            "prepare_statement uncached_registry",
+        )?
+        .insert(stmt_id.clone(), (conn_id.to_string(), sql_to_prepare));
+        return Ok(stmt_id);
+    }
+
     // SAFETY: We use TOKIO_RUNTIME.block_on(), which runs the future synchronously on a dedicated
     // thread pool. This prevents deadlocks that could occur if we were in a true async context
     // with std::sync::Mutex guards held across await points.
@@ -54,18 +77,37 @@ pub fn prepare_statement(conn_id: &str, sql: &str) -> NifResult<String> {
     let stmt_result = TOKIO_RUNTIME.block_on(async {
         let conn_guard = utils::safe_lock_arc(&connection, "prepare_statement conn")?;
 
-        conn_guard
+        // Read the version before preparing: DDL from another connection in between can
+        // only cause one needless re-prepare, never stale metadata. Remote connections
+        // skip it to keep prepare to a single round trip.
+        let schema_version = if is_remote {
+            None
+        } else {
+            Some(
+                utils::read_schema_version(&conn_guard)
+                    .await
+                    .map_err(|e| rustler::Error::Term(Box::new(e)))?,
+            )
+        };
+
+        let stmt = conn_guard
             .prepare(&sql_to_prepare)
             .await
-            .map_err(|e| rustler::Error::Term(Box::new(format!("Prepare failed: {e}"))))
+            .map_err(|e| utils::statement_error(format!("Prepare failed: {e}")))?;
+
+        Ok::<_, rustler::Error>(CachedStatement {
+            sql: sql_to_prepare,
+            schema_version,
+            stmt,
+        })
     });
 
     match stmt_result {
-        Ok(stmt) => {
+        Ok(cached) => {
             let stmt_id = uuid::Uuid::new_v4().to_string();
             utils::safe_lock(&STMT_REGISTRY, "prepare_statement stmt_registry")?.insert(
                 stmt_id.clone(),
-                (conn_id.to_string(), Arc::new(Mutex::new(stmt))),
+                (conn_id.to_string(), Arc::new(Mutex::new(cached))),
             );
             Ok(stmt_id)
         }
@@ -73,6 +115,143 @@ pub fn prepare_statement(conn_id: &str, sql: &str) -> NifResult<String> {
     }
 }
 
+/// Prepare a statement on `connection` without caching it.
+///
+/// The returned statement is finalised when dropped.
+fn prepare_on(
+    connection: &Arc<Mutex<libsql::Connection>>,
+    sql: &str,
+) -> Result<libsql::Statement, rustler::Error> {
+    // SAFETY: We use TOKIO_RUNTIME.block_on(), which runs the future synchronously on a dedicated
+    // thread pool. This prevents deadlocks that could occur if we were in a true async context
+    // with std::sync::Mutex guards held across await points.
+    #[allow(clippy::await_holding_lock)]
+    TOKIO_RUNTIME.block_on(async {
+        let conn_guard = utils::safe_lock_arc(connection, "prepare_on conn")?;
+        conn_guard
+            .prepare(sql)
+            .await
+            .map_err(|e| utils::statement_error(format!("Prepare failed: {e}")))
+    })
+}
+
+/// Prepare a fresh statement for a single use on a connection whose statement cache is
+/// disabled. Must be called while the caller holds no registry or connection locks.
+fn prepare_uncached(conn_id: &str, sql: &str) -> Result<libsql::Statement, rustler::Error> {
+    let client = {
+        let conn_map = utils::safe_lock(&CONNECTION_REGISTRY, "prepare_uncached conn_map")?;
+        conn_map
+            .get(conn_id)
+            .cloned()
+            .ok_or_else(|| rustler::Error::Term(Box::new("Invalid connection ID")))?
+    };
+
+    let connection = utils::safe_lock_arc(&client, "prepare_uncached client")?
+        .client
+        .clone();
+
+    prepare_on(&connection, sql)
+}
+
+/// Decode statement arguments and apply the connection's bind options.
+fn decode_statement_args(conn_id: &str, args: Vec<Term>) -> Result<Vec<Value>, rustler::Error> {
+    let mut decoded_args: Vec<Value> = args
+        .into_iter()
+        .map(|t| utils::decode_term_to_value(t))
+        .collect::<Result<_, _>>()
+        .map_err(|e| rustler::Error::Term(Box::new(e)))?;
+
+    utils::apply_bind_options(conn_id, &mut decoded_args)?;
+
+    Ok(decoded_args)
+}
+
+/// Name of the column at `idx`, or an out-of-bounds error.
+fn column_name_at(columns: &[Column], idx: usize) -> NifResult<String> {
+    columns
+        .get(idx)
+        .map(|col| col.name().to_string())
+        .ok_or_else(|| {
+            rustler::Error::Term(Box::new(format!(
+                "Column index {} out of bounds (statement has {} columns)",
+                idx,
+                columns.len()
+            )))
+        })
+}
+
+/// Build column metadata tuples: (name, origin_name, decl_type).
+fn column_metadata(columns: &[Column]) -> Vec<(String, String, Option<String>)> {
+    columns
+        .iter()
+        .map(|col| {
+            let name = col.name().to_string();
+            let origin_name = col
+                .origin_name()
+                .map_or_else(|| name.clone(), ToString::to_string);
+            let decl_type = col.decl_type().map(ToString::to_string);
+            (name, origin_name, decl_type)
+        })
+        .collect()
+}
+
+/// Re-prepare a cached statement if the schema has changed since it was prepared.
+///
+/// SQLite transparently re-prepares statements when they are stepped after a schema
+/// change, so execution is always correct. Column and parameter metadata read before
+/// execution, however, still describe the schema the statement was compiled against.
+/// Comparing `PRAGMA schema_version` with the version recorded at the last check is a
+/// cheap way to detect this and swap in a freshly prepared statement.
+///
+/// Prepare records the version on local and replica connections, so only real schema
+/// changes trigger a re-prepare. Remote connections skip that extra round trip at
+/// prepare time; their first check re-prepares once and records the current version.
+fn refresh_stale_statement(
+    conn_id: &str,
+    cached_stmt: &Arc<Mutex<CachedStatement>>,
+) -> Result<(), rustler::Error> {
+    let client = {
+        let conn_map = utils::safe_lock(&CONNECTION_REGISTRY, "refresh_stale_statement conn_map")?;
+        conn_map
+            .get(conn_id)
+            .cloned()
+            .ok_or_else(|| rustler::Error::Term(Box::new("Invalid connection ID")))?
+    };
+
+    // Clone the inner connection Arc and drop the outer lock before async operations
+    let connection = {
+        let client_guard = utils::safe_lock_arc(&client, "refresh_stale_statement client")?;
+        client_guard.client.clone()
+    }; // Outer lock dropped here
+
+    // SAFETY: We use TOKIO_RUNTIME.block_on(), which runs the future synchronously on a dedicated
+    // thread pool. This prevents deadlocks that could occur if we were in a true async context
+    // with std::sync::Mutex guards held across await points.
+    #[allow(clippy::await_holding_lock)]
+    TOKIO_RUNTIME.block_on(async {
+        let conn_guard = utils::safe_lock_arc(&connection, "refresh_stale_statement conn")?;
+        let current_version = utils::read_schema_version(&conn_guard)
+            .await
+            .map_err(|e| rustler::Error::Term(Box::new(e)))?;
+
+        let mut stmt_guard = utils::safe_lock_arc(cached_stmt, "refresh_stale_statement stmt")?;
+        if stmt_guard.schema_version == Some(current_version) {
+            return Ok(());
+        }
+
+        let fresh_stmt = conn_guard.prepare(&stmt_guard.sql).await.map_err(|e| {
+            rustler::Error::Term(Box::new(format!(
+                "Re-prepare after schema change failed: {e}"
+            )))
+        })?;
+
+        stmt_guard.stmt = fresh_stmt;
+        stmt_guard.schema_version = Some(current_version);
+
+        Ok(())
+    })
+}
+
 /// Execute a prepared SELECT query or RETURNING clause.
 ///
 /// Use this for SELECT statements or INSERT/UPDATE/DELETE with RETURNING clause.
@@ -94,6 +273,22 @@ pub fn query_prepared<'a>(
     _syncx: Atom,
     args: Vec<Term<'a>>,
 ) -> NifResult<Term<'a>> {
+    if let Some(sql) = utils::uncached_statement_sql(conn_id, stmt_id)? {
+        let decoded_args = decode_statement_args(conn_id, args)?;
+        let stmt = prepare_uncached(conn_id, &sql)?;
+
+        return TOKIO_RUNTIME.block_on(async {
+            let rows = stmt
+                .query(decoded_args)
+                .await
+                .map_err(|e| utils::statement_error(e.to_string()))?;
+
+            utils::collect_rows(env, rows)
+                .await
+                .map_err(|e| rustler::Error::Term(Box::new(format!("{e:?}"))))
+        });
+    }
+
     let conn_map = utils::safe_lock(&CONNECTION_REGISTRY, "query_prepared conn_map")?;
     let stmt_registry = utils::safe_lock(&STMT_REGISTRY, "query_prepared stmt_registry")?;
 
@@ -110,7 +305,7 @@ pub fn query_prepared<'a>(
 
     let cached_stmt = cached_stmt.clone();
 
-    let decoded_args: Vec<Value> = args
+    let mut decoded_args: Vec<Value> = args
         .into_iter()
         .map(|t| utils::decode_term_to_value(t))
         .collect::<Result<_, _>>()
@@ -119,6 +314,8 @@ pub fn query_prepared<'a>(
     drop(stmt_registry); // Release lock before async operation
     drop(conn_map); // Release lock before async operation
 
+    utils::apply_bind_options(conn_id, &mut decoded_args)?;
+
     // SAFETY: We use TOKIO_RUNTIME.block_on(), which runs the future synchronously on a dedicated
     // thread pool. This prevents deadlocks that could occur if we were in a true async context
     // with std::sync::Mutex guards held across await points.
@@ -128,9 +325,9 @@ pub fn query_prepared<'a>(
         let stmt_guard = utils::safe_lock_arc(&cached_stmt, "query_prepared stmt")?;
 
         // Reset clears any previous bindings
-        stmt_guard.reset();
+        stmt_guard.stmt.reset();
 
-        let res = stmt_guard.query(decoded_args).await;
+        let res = stmt_guard.stmt.query(decoded_args).await;
 
         match res {
             Ok(rows) => {
@@ -140,7 +337,7 @@ pub fn query_prepared<'a>(
 
                 Ok(collected)
             }
-            Err(e) => Err(rustler::Error::Term(Box::new(e.to_string()))),
+            Err(e) => Err(utils::statement_error(e.to_string())),
         }
     });
 
@@ -173,6 +370,17 @@ pub fn execute_prepared<'a>(
     sql_hint: &str, // For detecting if we need sync
     args: Vec<Term<'a>>,
 ) -> NifResult<u64> {
+    if let Some(sql) = utils::uncached_statement_sql(conn_id, stmt_id)? {
+        let decoded_args = decode_statement_args(conn_id, args)?;
+        let stmt = prepare_uncached(conn_id, &sql)?;
+
+        let affected = TOKIO_RUNTIME
+            .block_on(stmt.execute(decoded_args))
+            .map_err(|e| utils::statement_error(format!("Execute failed: {e}")))?;
+
+        return Ok(affected as u64);
+    }
+
     let conn_map = utils::safe_lock(&CONNECTION_REGISTRY, "execute_prepared conn_map")?;
     let stmt_registry = utils::safe_lock(&STMT_REGISTRY, "execute_prepared stmt_registry")?;
 
@@ -189,7 +397,7 @@ pub fn execute_prepared<'a>(
 
     let cached_stmt = cached_stmt.clone();
 
-    let decoded_args: Vec<Value> = args
+    let mut decoded_args: Vec<Value> = args
         .into_iter()
         .map(|t| utils::decode_term_to_value(t))
         .collect::<Result<_, _>>()
@@ -198,6 +406,8 @@ pub fn execute_prepared<'a>(
     drop(stmt_registry); // Release lock before async operation
     drop(conn_map); // Release lock before async operation
 
+    utils::apply_bind_options(conn_id, &mut decoded_args)?;
+
     // SAFETY: We use TOKIO_RUNTIME.block_on(), which runs the future synchronously on a dedicated
     // thread pool. This prevents deadlocks that could occur if we were in a true async context
     // with std::sync::Mutex guards held across await points.
@@ -207,12 +417,13 @@ pub fn execute_prepared<'a>(
         let stmt_guard = utils::safe_lock_arc(&cached_stmt, "execute_prepared stmt")?;
 
         // Reset clears any previous bindings
-        stmt_guard.reset();
+        stmt_guard.stmt.reset();
 
         let affected = stmt_guard
+            .stmt
             .execute(decoded_args)
             .await
-            .map_err(|e| rustler::Error::Term(Box::new(format!("Execute failed: {e}"))))?;
+            .map_err(|e| utils::statement_error(format!("Execute failed: {e}")))?;
 
         // NOTE: LibSQL automatically syncs writes to remote for embedded replicas.
         // No manual sync needed here.
@@ -233,6 +444,10 @@ pub fn execute_prepared<'a>(
 /// - `stmt_id`: Prepared statement ID
 #[rustler::nif(schedule = "DirtyIo")]
 pub fn statement_column_count(conn_id: &str, stmt_id: &str) -> NifResult<usize> {
+    if let Some(sql) = utils::uncached_statement_sql(conn_id, stmt_id)? {
+        return Ok(prepare_uncached(conn_id, &sql)?.column_count());
+    }
+
     let conn_map = utils::safe_lock(&CONNECTION_REGISTRY, "statement_column_count conn_map")?;
     let stmt_registry = utils::safe_lock(&STMT_REGISTRY, "statement_column_count stmt_registry")?;
 
@@ -252,8 +467,10 @@ pub fn statement_column_count(conn_id: &str, stmt_id: &str) -> NifResult<usize>
     drop(stmt_registry);
     drop(conn_map);
 
+    refresh_stale_statement(conn_id, &cached_stmt)?;
+
     let stmt_guard = utils::safe_lock_arc(&cached_stmt, "statement_column_count stmt")?;
-    let count = stmt_guard.column_count();
+    let count = stmt_guard.stmt.column_count();
 
     Ok(count)
 }
@@ -269,6 +486,10 @@ pub fn statement_column_count(conn_id: &str, stmt_id: &str) -> NifResult<usize>
 /// - `idx`: Column index (0-based)
 #[rustler::nif(schedule = "DirtyIo")]
 pub fn statement_column_name(conn_id: &str, stmt_id: &str, idx: usize) -> NifResult<String> {
+    if let Some(sql) = utils::uncached_statement_sql(conn_id, stmt_id)? {
+        return column_name_at(&prepare_uncached(conn_id, &sql)?.columns(), idx);
+    }
+
     let conn_map = utils::safe_lock(&CONNECTION_REGISTRY, "statement_column_name conn_map")?;
     let stmt_registry = utils::safe_lock(&STMT_REGISTRY, "statement_column_name stmt_registry")?;
 
@@ -288,20 +509,11 @@ pub fn statement_column_name(conn_id: &str, stmt_id: &str, idx: usize) -> NifRes
     drop(stmt_registry);
     drop(conn_map);
 
-    let stmt_guard = utils::safe_lock_arc(&cached_stmt, "statement_column_name stmt")?;
-    let columns = stmt_guard.columns();
-
-    if idx >= columns.len() {
-        return Err(rustler::Error::Term(Box::new(format!(
-            "Column index {} out of bounds (statement has {} columns)",
-            idx,
-            columns.len()
-        ))));
-    }
+    refresh_stale_statement(conn_id, &cached_stmt)?;
 
-    let column_name = columns[idx].name().to_string();
+    let stmt_guard = utils::safe_lock_arc(&cached_stmt, "statement_column_name stmt")?;
 
-    Ok(column_name)
+    column_name_at(&stmt_guard.stmt.columns(), idx)
 }
 
 /// Get the number of parameters in a prepared statement.
@@ -314,6 +526,10 @@ pub fn statement_column_name(conn_id: &str, stmt_id: &str, idx: usize) -> NifRes
 /// - `stmt_id`: Prepared statement ID
 #[rustler::nif(schedule = "DirtyIo")]
 pub fn statement_parameter_count(conn_id: &str, stmt_id: &str) -> NifResult<usize> {
+    if let Some(sql) = utils::uncached_statement_sql(conn_id, stmt_id)? {
+        return Ok(prepare_uncached(conn_id, &sql)?.parameter_count());
+    }
+
     let conn_map = utils::safe_lock(&CONNECTION_REGISTRY, "statement_parameter_count conn_map")?;
     let stmt_registry =
         utils::safe_lock(&STMT_REGISTRY, "statement_parameter_count stmt_registry")?;
@@ -334,8 +550,10 @@ pub fn statement_parameter_count(conn_id: &str, stmt_id: &str) -> NifResult<usiz
     drop(stmt_registry);
     drop(conn_map);
 
+    refresh_stale_statement(conn_id, &cached_stmt)?;
+
     let stmt_guard = utils::safe_lock_arc(&cached_stmt, "statement_parameter_count stmt")?;
-    let count = stmt_guard.parameter_count();
+    let count = stmt_guard.stmt.parameter_count();
 
     Ok(count)
 }
@@ -366,6 +584,11 @@ pub fn statement_parameter_name(
     stmt_id: &str,
     idx: i32,
 ) -> NifResult<Option<String>> {
+    if let Some(sql) = utils::uncached_statement_sql(conn_id, stmt_id)? {
+        let stmt = prepare_uncached(conn_id, &sql)?;
+        return Ok(stmt.parameter_name(idx).map(ToString::to_string));
+    }
+
     let conn_map = utils::safe_lock(&CONNECTION_REGISTRY, "statement_parameter_name conn_map")?;
     let stmt_registry = utils::safe_lock(&STMT_REGISTRY, "statement_parameter_name stmt_registry")?;
 
@@ -385,10 +608,12 @@ pub fn statement_parameter_name(
     drop(stmt_registry);
     drop(conn_map);
 
+    refresh_stale_statement(conn_id, &cached_stmt)?;
+
     let stmt_guard = utils::safe_lock_arc(&cached_stmt, "statement_parameter_name stmt")?;
 
     // SQLite uses 1-based parameter indices
-    let param_name = stmt_guard.parameter_name(idx).map(ToString::to_string);
+    let param_name = stmt_guard.stmt.parameter_name(idx).map(ToString::to_string);
 
     Ok(param_name)
 }
@@ -423,6 +648,11 @@ pub fn statement_parameter_name(
 /// ```
 #[rustler::nif(schedule = "DirtyIo")]
 pub fn reset_statement(conn_id: &str, stmt_id: &str) -> NifResult<Atom> {
+    // Uncached statements are finalised after every use, so there is nothing to reset
+    if utils::uncached_statement_sql(conn_id, stmt_id)?.is_some() {
+        return Ok(rustler::types::atom::ok());
+    }
+
     let conn_map = utils::safe_lock(&CONNECTION_REGISTRY, "reset_statement conn_map")?;
     let stmt_registry = utils::safe_lock(&STMT_REGISTRY, "reset_statement stmt_registry")?;
 
@@ -443,7 +673,7 @@ pub fn reset_statement(conn_id: &str, stmt_id: &str) -> NifResult<Atom> {
     drop(conn_map);
 
     let stmt_guard = utils::safe_lock_arc(&cached_stmt, "reset_statement stmt")?;
-    stmt_guard.reset();
+    stmt_guard.stmt.reset();
 
     Ok(rustler::types::atom::ok())
 }
@@ -476,6 +706,10 @@ pub fn get_statement_columns(
     conn_id: &str,
     stmt_id: &str,
 ) -> NifResult<Vec<(String, String, Option<String>)>> {
+    if let Some(sql) = utils::uncached_statement_sql(conn_id, stmt_id)? {
+        return Ok(column_metadata(&prepare_uncached(conn_id, &sql)?.columns()));
+    }
+
     let conn_map = utils::safe_lock(&CONNECTION_REGISTRY, "get_statement_columns conn_map")?;
     let stmt_registry = utils::safe_lock(&STMT_REGISTRY, "get_statement_columns stmt_registry")?;
 
@@ -495,21 +729,31 @@ pub fn get_statement_columns(
     drop(stmt_registry);
     drop(conn_map);
 
+    refresh_stale_statement(conn_id, &cached_stmt)?;
+
     let stmt_guard = utils::safe_lock_arc(&cached_stmt, "get_statement_columns stmt")?;
-    let columns = stmt_guard.columns();
 
-    // Build list of column metadata tuples: (name, origin_name, decl_type)
-    let column_info: Vec<(String, String, Option<String>)> = columns
-        .iter()
-        .map(|col| {
-            let name = col.name().to_string();
-            let origin_name = col
-                .origin_name()
-                .map_or_else(|| name.clone(), ToString::to_string);
-            let decl_type = col.decl_type().map(ToString::to_string);
-            (name, origin_name, decl_type)
-        })
-        .collect();
+    Ok(column_metadata(&stmt_guard.stmt.columns()))
+}
+
+/// Count the statements `conn_id` has cached in `STMT_REGISTRY`.
+///
+/// Always `0` for connections opened with `disable_statement_cache`.
+///
+/// # Arguments
+/// - `conn_id`: Database connection ID
+#[rustler::nif(schedule = "DirtyIo")]
+pub fn cached_statement_count(conn_id: &str) -> NifResult<usize> {
+    if !utils::safe_lock(&CONNECTION_REGISTRY, "cached_statement_count conn_map")?
+        .contains_key(conn_id)
+    {
+        return Err(rustler::Error::Term(Box::new("Invalid connection ID")));
+    }
+
+    let stmt_registry = utils::safe_lock(&STMT_REGISTRY, "cached_statement_count stmt_registry")?;
 
-    Ok(column_info)
+    Ok(stmt_registry
+        .values()
+        .filter(|(owner, _)| owner == conn_id)
+        .count())
 }
@@ -1,12 +1,14 @@
 //! Tests for constants.rs - Registry management and global state
 //!
 //! These tests verify that the global registries (for connections, transactions,
-//! statements, and cursors) are properly initialized and accessible.
+//! statements, cursors and operations) are properly initialized and accessible.
 
 // Allow unwrap() in tests for cleaner test code - see CLAUDE.md "Test Code Exception"
 #![allow(clippy::unwrap_used)]
 
-use crate::constants::{CONNECTION_REGISTRY, CURSOR_REGISTRY, STMT_REGISTRY, TXN_REGISTRY};
+use crate::constants::{
+    CONNECTION_REGISTRY, CURSOR_REGISTRY, OPERATION_REGISTRY, STMT_REGISTRY, TXN_REGISTRY,
+};
 use uuid::Uuid;
 
 #[test]
@@ -44,4 +46,10 @@ fn test_registry_initialization() {
         cursor_registry.is_ok(),
         "Cursor registry should be accessible"
     );
+
+    let operation_registry = OPERATION_REGISTRY.lock();
+    assert!(
+        operation_registry.is_ok(),
+        "Operation registry should be accessible"
+    );
 }
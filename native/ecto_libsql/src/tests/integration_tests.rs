@@ -8,6 +8,12 @@
 #![allow(clippy::unwrap_used)]
 
 use super::test_utils::{setup_test_db, TestDbGuard};
+use crate::constants::OPERATION_REGISTRY;
+use crate::models::OperationEntry;
+use crate::operation::{
+    expire_operations, finish_operation, start_operation, CANCELLED, OPERATION_TTL,
+};
+use crate::utils::read_schema_version;
 use libsql::{Builder, Value};
 
 #[tokio::test]
@@ -330,3 +336,123 @@ async fn test_null_values() {
     let email_value = row.get_value(0).unwrap();
     assert!(matches!(email_value, Value::Null));
 }
+
+#[tokio::test]
+async fn test_read_schema_version_changes_after_ddl() {
+    let db_path = setup_test_db();
+    let _guard = TestDbGuard::new(db_path.clone());
+
+    let db = Builder::new_local(db_path.to_str().unwrap())
+        .build()
+        .await
+        .unwrap();
+    let conn = db.connect().unwrap();
+
+    let initial = read_schema_version(&conn).await.unwrap();
+
+    conn.execute("CREATE TABLE versioned (id INTEGER PRIMARY KEY)", ())
+        .await
+        .unwrap();
+    let after_create = read_schema_version(&conn).await.unwrap();
+    assert!(
+        after_create > initial,
+        "schema version should increase after CREATE TABLE"
+    );
+
+    // Data changes must not bump the schema version
+    conn.execute("INSERT INTO versioned (id) VALUES (1)", ())
+        .await
+        .unwrap();
+    assert_eq!(read_schema_version(&conn).await.unwrap(), after_create);
+}
+
+fn register_operation(cancelled: bool) -> String {
+    let op_id = uuid::Uuid::new_v4().to_string();
+    OPERATION_REGISTRY.lock().unwrap().insert(
+        op_id.clone(),
+        OperationEntry {
+            cancelled,
+            running: None,
+            created_at: std::time::Instant::now(),
+        },
+    );
+    op_id
+}
+
+#[tokio::test]
+async fn test_operation_runs_once_and_is_removed() {
+    let db_path = setup_test_db();
+    let _guard = TestDbGuard::new(db_path.clone());
+
+    let db = Builder::new_local(db_path.to_str().unwrap())
+        .build()
+        .await
+        .unwrap();
+    let conn = db.connect().unwrap();
+
+    let op_id = register_operation(false);
+
+    assert_eq!(start_operation(&op_id, &conn), Ok(()));
+    assert_eq!(
+        start_operation(&op_id, &conn),
+        Err("Operation is already running".to_string())
+    );
+
+    assert!(!finish_operation(&op_id), "Operation was not cancelled");
+    assert!(!OPERATION_REGISTRY.lock().unwrap().contains_key(&op_id));
+    assert_eq!(
+        start_operation(&op_id, &conn),
+        Err("Operation not found".to_string())
+    );
+}
+
+#[tokio::test]
+async fn test_operation_cancelled_before_start_never_runs() {
+    let db_path = setup_test_db();
+    let _guard = TestDbGuard::new(db_path.clone());
+
+    let db = Builder::new_local(db_path.to_str().unwrap())
+        .build()
+        .await
+        .unwrap();
+    let conn = db.connect().unwrap();
+
+    let op_id = register_operation(true);
+
+    assert_eq!(start_operation(&op_id, &conn), Err(CANCELLED.to_string()));
+    assert!(!OPERATION_REGISTRY.lock().unwrap().contains_key(&op_id));
+}
+
+#[tokio::test]
+async fn test_idle_operations_expire() {
+    let db_path = setup_test_db();
+    let _guard = TestDbGuard::new(db_path.clone());
+
+    let db = Builder::new_local(db_path.to_str().unwrap())
+        .build()
+        .await
+        .unwrap();
+    let conn = db.connect().unwrap();
+
+    let created_at = std::time::Instant::now();
+    let entry = |running: Option<libsql::Connection>| OperationEntry {
+        cancelled: false,
+        running,
+        created_at,
+    };
+
+    let mut registry = std::collections::HashMap::new();
+    registry.insert("idle".to_string(), entry(None));
+    registry.insert("running".to_string(), entry(Some(conn)));
+
+    // Nothing expires before the TTL
+    assert_eq!(expire_operations(&mut registry, created_at), 0);
+
+    // Past it, only the handle that never started is removed
+    assert_eq!(
+        expire_operations(&mut registry, created_at + OPERATION_TTL),
+        1
+    );
+    assert!(registry.contains_key("running"));
+    assert!(!registry.contains_key("idle"));
+}
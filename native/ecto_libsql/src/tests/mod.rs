@@ -6,6 +6,7 @@
 mod constants_tests;
 mod error_handling_tests;
 mod integration_tests;
+mod models_tests;
 mod proptest_tests;
 mod test_utils;
 mod utils_tests;
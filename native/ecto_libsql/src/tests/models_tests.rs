@@ -0,0 +1,50 @@
+//! Tests for models.rs - Data structures shared across the NIF implementation
+//!
+//! These tests verify the bookkeeping performed by `ReplicaMetrics`.
+
+use crate::models::ReplicaMetrics;
+
+#[test]
+fn test_replica_metrics_start_empty() {
+    let metrics = ReplicaMetrics::default();
+
+    assert_eq!(metrics.total_syncs, 0);
+    assert_eq!(metrics.failed_syncs, 0);
+    assert_eq!(metrics.total_frames_applied, 0);
+    assert_eq!(metrics.last_sync_at_ms, None);
+    assert_eq!(metrics.last_error, None);
+}
+
+#[test]
+fn test_replica_metrics_accumulate_successes() {
+    let mut metrics = ReplicaMetrics::default();
+
+    metrics.record_success(3, 1_000);
+    metrics.record_success(5, 2_000);
+
+    assert_eq!(metrics.total_syncs, 2);
+    assert_eq!(metrics.total_frames_applied, 8);
+    assert_eq!(metrics.last_sync_at_ms, Some(2_000));
+    assert_eq!(metrics.failed_syncs, 0);
+}
+
+#[test]
+fn test_replica_metrics_record_failures_without_touching_successes() {
+    let mut metrics = ReplicaMetrics::default();
+
+    metrics.record_success(2, 1_000);
+    metrics.record_failure("Sync timeout after 30 seconds");
+
+    assert_eq!(metrics.total_syncs, 1);
+    assert_eq!(metrics.failed_syncs, 1);
+    assert_eq!(metrics.last_sync_at_ms, Some(1_000));
+    assert_eq!(
+        metrics.last_error.as_deref(),
+        Some("Sync timeout after 30 seconds")
+    );
+
+    // A later success keeps the last error for diagnosis
+    metrics.record_success(0, 3_000);
+    assert_eq!(metrics.last_sync_at_ms, Some(3_000));
+    assert!(metrics.last_error.is_some());
+}
@@ -32,7 +32,8 @@ proptest! {
     /// Property: Statements with RETURNING should use query
     #[test]
     fn returning_uses_query(
-        prefix in "(INSERT|UPDATE|DELETE)[ \t]+[^;]*",
+        // No quotes or comment markers, which would hide the keyword
+        prefix in "(INSERT|UPDATE|DELETE)[ \t]+[^;'\"`\\[/-]*",
         whitespace in r"[ \t]+",
         rest in "[^;]*"
     ) {
@@ -3,11 +3,32 @@
 //! These tests verify the correctness of:
 //! - `detect_query_type()` - Categorizes SQL statements by type
 //! - `should_use_query()` - Determines whether to use query() vs execute()
+//! - `charlist_to_text()` - Converts `{:charlist, list}` parameters to text
+//! - `quote_identifier()` - Quotes identifiers for safe interpolation
+//! - `build_count_sql()` - Builds `SELECT count(*)` statements for the count helper
+//! - `build_materialize_sql()` - Infers a table schema from a materialised query result
+//! - `build_keyset_sql()` - Builds keyset pagination queries
+//! - `build_copy_batch_sql()` - Builds keyset batches for `copy_into_native`
+//! - `pragma_mutates_state()` - Detects state-changing pragmas on read-only connections
+//! - `coerce_empty_strings_to_null()` - Binds empty text as NULL when opted in
+//! - `uses_autoincrement()` - Detects AUTOINCREMENT tables for sequence resets
+//! - `is_connection_lost()` - Recognises dropped remote connections for auto-reconnect
+//! - `is_replica_write_error()` - Recognises writes on a replica without a primary
+//! - `is_busy_error()` / `backoff_delay()` - Retry schedule for the busy backoff policy
+//! - `base64_encode()` / `row_to_json()` - Serialise rows for NDJSON table dumps
+//! - `compare_values()` / `ColumnReducer` - Built-in reducers for `query_reduce_native`
 
 // Allow unwrap() in tests for cleaner test code - see CLAUDE.md "Test Code Exception"
 #![allow(clippy::unwrap_used)]
 
-use crate::utils::{detect_query_type, should_use_query, QueryType};
+use crate::models::BusyBackoff;
+use crate::utils::{
+    backoff_delay, base64_encode, build_copy_batch_sql, build_count_sql, build_keyset_sql,
+    build_materialize_sql, charlist_to_text, coerce_empty_strings_to_null, compare_values,
+    detect_query_type, is_busy_error, is_connection_lost, is_replica_write_error,
+    pragma_mutates_state, quote_identifier, row_to_json, should_use_query, uses_autoincrement,
+    ColumnReducer, QueryType, Reduced,
+};
 
 /// Tests for query type detection
 mod query_type_detection {
@@ -64,7 +85,7 @@ mod query_type_detection {
     fn test_detect_ddl_queries() {
         assert_eq!(
             detect_query_type("CREATE TABLE users (id INTEGER)"),
-            QueryType::Create
+            QueryType::Create { temp: false }
         );
         assert_eq!(detect_query_type("DROP TABLE users"), QueryType::Drop);
         assert_eq!(
@@ -80,6 +101,39 @@ mod query_type_detection {
         assert_eq!(detect_query_type("ROLLBACK"), QueryType::Rollback);
     }
 
+    #[test]
+    fn test_detect_temp_creates() {
+        assert_eq!(
+            detect_query_type("CREATE TEMP TABLE scratch (id INTEGER)"),
+            QueryType::Create { temp: true }
+        );
+        assert_eq!(
+            detect_query_type("create temporary table scratch (id INTEGER)"),
+            QueryType::Create { temp: true }
+        );
+        assert_eq!(
+            detect_query_type("  CREATE\tTEMP VIEW recent AS SELECT 1"),
+            QueryType::Create { temp: true }
+        );
+        assert_eq!(
+            detect_query_type("CREATE TABLE temp_results (id INTEGER)"),
+            QueryType::Create { temp: false }
+        );
+        assert_eq!(
+            detect_query_type("CREATE INDEX idx_temp ON users (temp)"),
+            QueryType::Create { temp: false }
+        );
+    }
+
+    #[test]
+    fn test_detect_reindex_queries() {
+        assert_eq!(detect_query_type("REINDEX"), QueryType::Reindex);
+        assert_eq!(
+            detect_query_type("reindex \"idx_users_email\""),
+            QueryType::Reindex
+        );
+    }
+
     #[test]
     fn test_detect_unknown_query() {
         assert_eq!(
@@ -220,6 +274,22 @@ mod should_use_query_tests {
         ));
     }
 
+    #[test]
+    fn test_not_returning_inside_literals_or_comments() {
+        assert!(!should_use_query(
+            "UPDATE users SET note = 'no returning here' WHERE id = 1"
+        ));
+        assert!(!should_use_query(
+            "UPDATE users SET \"returning\" = 1 -- returning id\nWHERE id = 1"
+        ));
+        assert!(!should_use_query(
+            "DELETE FROM users /* returning id */ WHERE returning_at < ?"
+        ));
+        assert!(should_use_query(
+            "UPDATE users SET note = 'returning' WHERE id = 1 RETURNING id"
+        ));
+    }
+
     // ===== Non-SELECT, Non-RETURNING Tests =====
 
     #[test]
@@ -261,8 +331,86 @@ mod should_use_query_tests {
 
     #[test]
     fn test_pragma_statements() {
-        assert!(!should_use_query("PRAGMA table_info(users)"));
+        assert!(should_use_query("PRAGMA table_info(users)"));
+        assert!(should_use_query("PRAGMA user_version"));
+        assert!(should_use_query("pragma journal_mode;"));
         assert!(!should_use_query("PRAGMA foreign_keys = ON"));
+        assert!(!should_use_query("PRAGMA foreign_keys=ON"));
+    }
+
+    #[test]
+    fn test_schema_qualified_pragma_statements() {
+        assert!(should_use_query("PRAGMA archive.table_info(x)"));
+        assert!(should_use_query("PRAGMA archive.table_info (x)"));
+        assert!(should_use_query("PRAGMA main.user_version"));
+        assert!(!should_use_query("PRAGMA archive.foreign_keys = ON"));
+        assert!(!should_use_query(
+            "/* setup */ PRAGMA archive.foreign_keys=ON"
+        ));
+    }
+
+    // ===== WITH (CTE) Routing =====
+
+    #[test]
+    fn test_with_select() {
+        assert!(should_use_query(
+            "WITH recent AS (SELECT * FROM posts) SELECT * FROM recent"
+        ));
+        assert!(should_use_query(
+            "WITH RECURSIVE n(x) AS (SELECT 1 UNION ALL SELECT x + 1 FROM n WHERE x < 5) SELECT x FROM n"
+        ));
+    }
+
+    #[test]
+    fn test_with_update_returning() {
+        assert!(should_use_query(
+            "WITH stale AS (SELECT id FROM users WHERE seen < ?) UPDATE users SET active = 0 WHERE id IN (SELECT id FROM stale) RETURNING id"
+        ));
+    }
+
+    #[test]
+    fn test_with_update_without_returning() {
+        assert!(!should_use_query(
+            "WITH stale AS (SELECT id FROM users WHERE seen < ?) UPDATE users SET active = 0 WHERE id IN (SELECT id FROM stale)"
+        ));
+    }
+
+    #[test]
+    fn test_with_delete_and_insert() {
+        assert!(should_use_query(
+            "WITH old AS (SELECT id FROM logs) DELETE FROM logs WHERE id IN old RETURNING *"
+        ));
+        assert!(!should_use_query(
+            "WITH old AS (SELECT id FROM logs) DELETE FROM logs WHERE id IN old"
+        ));
+        assert!(!should_use_query(
+            "with src as (select 1 as v) insert into t (v) select v from src"
+        ));
+    }
+
+    #[test]
+    fn test_with_multiple_ctes_and_hints() {
+        let sql = "WITH a(x) AS MATERIALIZED (SELECT 1), \"b c\" AS NOT MATERIALIZED (SELECT ')' || x FROM a) \
+                   UPDATE t SET v = (SELECT x FROM a)";
+        assert!(!should_use_query(sql));
+
+        let returning = format!("{sql} RETURNING v");
+        assert!(should_use_query(&returning));
+    }
+
+    #[test]
+    fn test_with_cte_containing_comments_and_returning_text() {
+        // RETURNING inside a string literal in the CTE body is skipped with the body
+        assert!(!should_use_query(
+            "WITH a AS (SELECT 'x RETURNING y' /* ) */ -- )\n) UPDATE t SET v = 1"
+        ));
+    }
+
+    #[test]
+    fn test_with_unparseable_falls_back_to_query() {
+        assert!(should_use_query("WITH"));
+        assert!(should_use_query("WITH a AS (SELECT 1"));
+        assert!(should_use_query("WITH a SELECT 1"));
     }
 
     // ===== Edge Cases =====
@@ -698,3 +846,605 @@ mod should_use_query_tests {
         assert!(!should_use_query("SAVEPOINT sp1"));
     }
 }
+
+/// Tests for tagged charlist conversion in parameter binding
+mod charlist_detection {
+    use super::*;
+
+    fn codepoints(s: &str) -> Vec<u32> {
+        s.chars().map(u32::from).collect()
+    }
+
+    #[test]
+    fn test_ascii_charlist_is_text() {
+        assert_eq!(
+            charlist_to_text(&codepoints("hello")),
+            Some("hello".to_string())
+        );
+    }
+
+    #[test]
+    fn test_unicode_charlist_is_text() {
+        assert_eq!(
+            charlist_to_text(&codepoints("héllo 你好 🎉")),
+            Some("héllo 你好 🎉".to_string())
+        );
+    }
+
+    #[test]
+    fn test_whitespace_control_chars_allowed() {
+        assert_eq!(
+            charlist_to_text(&codepoints("a\tb\r\nc")),
+            Some("a\tb\r\nc".to_string())
+        );
+    }
+
+    #[test]
+    fn test_non_printable_list_is_not_text() {
+        assert_eq!(charlist_to_text(&[1, 2, 3]), None);
+        assert_eq!(charlist_to_text(&[104, 105, 0]), None);
+    }
+
+    #[test]
+    fn test_invalid_codepoint_is_not_text() {
+        // Surrogates and values beyond U+10FFFF are not valid chars
+        assert_eq!(charlist_to_text(&[0xD800]), None);
+        assert_eq!(charlist_to_text(&[0x11_0000]), None);
+    }
+
+    #[test]
+    fn test_empty_charlist_is_empty_text() {
+        assert_eq!(charlist_to_text(&[]), Some(String::new()));
+    }
+}
+
+/// Tests for identifier quoting
+mod quote_identifier_tests {
+    use super::*;
+
+    #[test]
+    fn test_plain_identifier() {
+        assert_eq!(quote_identifier("users"), "\"users\"");
+    }
+
+    #[test]
+    fn test_identifier_with_spaces_and_keywords() {
+        assert_eq!(quote_identifier("order items"), "\"order items\"");
+        assert_eq!(quote_identifier("select"), "\"select\"");
+    }
+
+    #[test]
+    fn test_embedded_quotes_are_doubled() {
+        assert_eq!(
+            quote_identifier("x\"; DROP TABLE users; --"),
+            "\"x\"\"; DROP TABLE users; --\""
+        );
+    }
+}
+
+/// Tests for count statement building
+mod build_count_sql_tests {
+    use super::*;
+
+    #[test]
+    fn test_count_without_where() {
+        assert_eq!(
+            build_count_sql("users", None).unwrap(),
+            "SELECT count(*) FROM \"users\""
+        );
+        assert_eq!(
+            build_count_sql("users", Some("  ")).unwrap(),
+            "SELECT count(*) FROM \"users\""
+        );
+    }
+
+    #[test]
+    fn test_count_with_where() {
+        assert_eq!(
+            build_count_sql("order items", Some("status = ? AND total > ?")).unwrap(),
+            "SELECT count(*) FROM \"order items\" WHERE status = ? AND total > ?"
+        );
+    }
+
+    #[test]
+    fn test_invalid_table_names_are_rejected() {
+        assert!(build_count_sql("", None).is_err());
+        assert!(build_count_sql("   ", None).is_err());
+        assert!(build_count_sql("us\0ers", None).is_err());
+    }
+}
+
+/// Tests for keyset pagination query building
+mod build_keyset_sql_tests {
+    use super::*;
+
+    #[test]
+    fn test_first_page_has_no_key_filter() {
+        assert_eq!(
+            build_keyset_sql("SELECT id, name FROM users", "id", false, 50).unwrap(),
+            "SELECT * FROM (SELECT id, name FROM users) ORDER BY \"id\" LIMIT 50"
+        );
+    }
+
+    #[test]
+    fn test_later_pages_filter_after_key() {
+        assert_eq!(
+            build_keyset_sql("SELECT * FROM events WHERE kind = ?;", "seq", true, 10).unwrap(),
+            "SELECT * FROM (SELECT * FROM events WHERE kind = ?) WHERE \"seq\" > ? ORDER BY \"seq\" LIMIT 10"
+        );
+    }
+
+    #[test]
+    fn test_invalid_input_is_rejected() {
+        assert!(build_keyset_sql("  ", "id", false, 10).is_err());
+        assert!(build_keyset_sql("SELECT * FROM t", "", false, 10).is_err());
+        assert!(build_keyset_sql("SELECT * FROM t", "i\0d", false, 10).is_err());
+        assert!(build_keyset_sql("SELECT * FROM t", "id", false, 0).is_err());
+    }
+}
+
+mod build_copy_batch_sql_tests {
+    use super::*;
+
+    #[test]
+    fn test_first_batch_skips_null_keys() {
+        let (bound, insert) =
+            build_copy_batch_sql("archive", "SELECT id, v FROM src;", "id", false, 100).unwrap();
+
+        assert_eq!(
+            bound,
+            "SELECT max(\"id\") FROM (SELECT * FROM (SELECT id, v FROM src) WHERE \"id\" IS NOT NULL ORDER BY \"id\" LIMIT 100)"
+        );
+        assert_eq!(
+            insert,
+            "INSERT INTO \"archive\" SELECT * FROM (SELECT id, v FROM src) WHERE \"id\" IS NOT NULL AND \"id\" <= ?"
+        );
+    }
+
+    #[test]
+    fn test_later_batches_start_after_the_last_key() {
+        let (bound, insert) =
+            build_copy_batch_sql("archive", "SELECT * FROM src", "id", true, 10).unwrap();
+
+        assert_eq!(
+            bound,
+            "SELECT max(\"id\") FROM (SELECT * FROM (SELECT * FROM src) WHERE \"id\" > ? ORDER BY \"id\" LIMIT 10)"
+        );
+        assert_eq!(
+            insert,
+            "INSERT INTO \"archive\" SELECT * FROM (SELECT * FROM src) WHERE \"id\" > ? AND \"id\" <= ?"
+        );
+    }
+
+    #[test]
+    fn test_invalid_input_is_rejected() {
+        assert!(build_copy_batch_sql("archive", "SELECT * FROM t", "", false, 10).is_err());
+        assert!(build_copy_batch_sql("archive", "SELECT * FROM t", "id", false, 0).is_err());
+    }
+}
+
+/// Tests for read-only pragma enforcement
+mod pragma_mutates_state_tests {
+    use super::*;
+
+    #[test]
+    fn test_introspection_pragmas_are_allowed() {
+        assert!(!pragma_mutates_state("PRAGMA table_info(users)"));
+        assert!(!pragma_mutates_state("pragma TABLE_INFO( users )"));
+        assert!(!pragma_mutates_state("PRAGMA main.index_list(users)"));
+        assert!(!pragma_mutates_state("PRAGMA integrity_check"));
+    }
+
+    #[test]
+    fn test_status_queries_are_allowed() {
+        assert!(!pragma_mutates_state("PRAGMA user_version"));
+        assert!(!pragma_mutates_state("PRAGMA foreign_keys;"));
+        assert!(!pragma_mutates_state("-- check\n PRAGMA journal_mode"));
+        assert!(!pragma_mutates_state("PRAGMA writable_schema"));
+    }
+
+    #[test]
+    fn test_assignments_are_rejected() {
+        assert!(pragma_mutates_state("PRAGMA writable_schema=ON"));
+        assert!(pragma_mutates_state("PRAGMA writable_schema = ON"));
+        assert!(pragma_mutates_state("PRAGMA main.user_version = 5"));
+        assert!(pragma_mutates_state("PRAGMA user_version(5)"));
+    }
+
+    #[test]
+    fn test_unknown_and_unparseable_pragmas_are_rejected() {
+        assert!(pragma_mutates_state("PRAGMA optimize"));
+        assert!(pragma_mutates_state("PRAGMA wal_checkpoint(TRUNCATE)"));
+        assert!(pragma_mutates_state("PRAGMA \"writable_schema\" = 1"));
+        assert!(pragma_mutates_state("PRAGMA"));
+    }
+
+    #[test]
+    fn test_non_pragma_statements_are_not_pragmas() {
+        assert!(!pragma_mutates_state("SELECT * FROM users"));
+        assert!(!pragma_mutates_state("INSERT INTO users VALUES (1)"));
+    }
+}
+
+/// Tests for empty string coercion on bind
+mod coerce_empty_strings_tests {
+    use super::*;
+    use libsql::Value;
+
+    #[test]
+    fn test_only_empty_text_becomes_null() {
+        let mut params = vec![
+            Value::Text(String::new()),
+            Value::Text(" ".to_string()),
+            Value::Text("value".to_string()),
+            Value::Integer(0),
+            Value::Blob(Vec::new()),
+            Value::Null,
+        ];
+
+        coerce_empty_strings_to_null(&mut params);
+
+        assert_eq!(
+            params,
+            vec![
+                Value::Null,
+                Value::Text(" ".to_string()),
+                Value::Text("value".to_string()),
+                Value::Integer(0),
+                Value::Blob(Vec::new()),
+                Value::Null,
+            ]
+        );
+    }
+}
+
+/// Tests for AUTOINCREMENT detection
+mod build_materialize_sql_tests {
+    use super::*;
+    use libsql::Value;
+
+    fn columns(names: &[&str]) -> Vec<String> {
+        names.iter().map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn test_types_come_from_first_non_null_value() {
+        let rows = vec![
+            vec![
+                Value::Integer(1),
+                Value::Null,
+                Value::Null,
+                Value::Blob(vec![1]),
+            ],
+            vec![
+                Value::Text("two".to_string()),
+                Value::Real(2.5),
+                Value::Null,
+                Value::Null,
+            ],
+            vec![
+                Value::Integer(3),
+                Value::Text("x".to_string()),
+                Value::Null,
+                Value::Null,
+            ],
+        ];
+
+        let (create, insert) = build_materialize_sql(
+            "snapshot",
+            &columns(&["id", "score", "note", "data"]),
+            &rows,
+        )
+        .unwrap();
+
+        assert_eq!(
+            create,
+            "CREATE TABLE \"snapshot\" (\"id\" INTEGER, \"score\" REAL, \"note\", \"data\" BLOB)"
+        );
+        assert_eq!(insert, "INSERT INTO \"snapshot\" VALUES (?, ?, ?, ?)");
+    }
+
+    #[test]
+    fn test_empty_result_has_untyped_columns() {
+        let (create, _insert) =
+            build_materialize_sql("order \"items\"", &columns(&["a b"]), &[]).unwrap();
+
+        assert_eq!(create, "CREATE TABLE \"order \"\"items\"\"\" (\"a b\")");
+    }
+
+    #[test]
+    fn test_invalid_input_is_rejected() {
+        assert!(build_materialize_sql("", &columns(&["id"]), &[]).is_err());
+        assert!(build_materialize_sql("sna\0pshot", &columns(&["id"]), &[]).is_err());
+        assert!(build_materialize_sql("snapshot", &[], &[]).is_err());
+    }
+}
+
+mod uses_autoincrement_tests {
+    use super::*;
+
+    #[test]
+    fn test_detects_autoincrement_keyword() {
+        assert!(uses_autoincrement(
+            "CREATE TABLE events (id INTEGER PRIMARY KEY AUTOINCREMENT, name TEXT)"
+        ));
+        assert!(uses_autoincrement(
+            "create table t (id integer primary key autoincrement)"
+        ));
+    }
+
+    #[test]
+    fn test_ignores_tables_without_autoincrement() {
+        assert!(!uses_autoincrement(
+            "CREATE TABLE t (id INTEGER PRIMARY KEY, name TEXT)"
+        ));
+        assert!(!uses_autoincrement(
+            "CREATE TABLE t (id INTEGER PRIMARY KEY, autoincrement_hint TEXT)"
+        ));
+    }
+}
+
+/// Tests for lost connection detection
+mod connection_lost_tests {
+    use super::*;
+
+    #[test]
+    fn test_detects_network_failures() {
+        assert!(is_connection_lost(
+            "Hrana: `http error: `error sending request for url (http://127.0.0.1:9/v2/pipeline)``"
+        ));
+        assert!(is_connection_lost(
+            "Connection reset by peer (os error 104)"
+        ));
+        assert!(is_connection_lost("Hrana: `stream expired`"));
+    }
+
+    #[test]
+    fn test_ignores_sql_errors() {
+        assert!(!is_connection_lost(
+            "SQLite failure: `no such table: users`"
+        ));
+        assert!(!is_connection_lost(
+            "SQLite failure: `UNIQUE constraint failed: users.email`"
+        ));
+    }
+}
+
+/// Tests for replica write error detection
+mod replica_write_error_tests {
+    use super::*;
+
+    #[test]
+    fn test_detects_libsql_replica_write_message() {
+        // The exact messages libsql 0.9.29 returns; if an upgrade rewords them, this fails
+        // rather than writes silently losing the `:replica_read_only` error.
+        assert!(is_replica_write_error(
+            "Cannot delegate write in local replica mode."
+        ));
+        assert!(is_replica_write_error(
+            "Cannot describe in local replica mode."
+        ));
+    }
+
+    #[test]
+    fn test_ignores_other_errors() {
+        assert!(!is_replica_write_error(
+            "SQLite failure: `attempt to write a readonly database`"
+        ));
+        assert!(!is_replica_write_error("Hrana: `stream expired`"));
+    }
+}
+
+/// Tests for the busy backoff retry schedule
+mod busy_backoff_tests {
+    use super::*;
+
+    #[test]
+    fn test_delays_double_and_stop_at_max() {
+        let backoff = BusyBackoff {
+            base_ms: 10,
+            max_ms: 100,
+        };
+
+        assert_eq!(backoff_delay(backoff, 0, 0), Some(10));
+        assert_eq!(backoff_delay(backoff, 1, 10), Some(20));
+        assert_eq!(backoff_delay(backoff, 2, 30), Some(40));
+        // Only 30ms of the budget are left
+        assert_eq!(backoff_delay(backoff, 3, 70), Some(30));
+        assert_eq!(backoff_delay(backoff, 4, 100), None);
+    }
+
+    #[test]
+    fn test_large_attempts_do_not_overflow() {
+        let backoff = BusyBackoff {
+            base_ms: u64::MAX,
+            max_ms: 50,
+        };
+
+        assert_eq!(backoff_delay(backoff, 70, 0), Some(50));
+    }
+
+    #[test]
+    fn test_zero_max_never_waits() {
+        let backoff = BusyBackoff {
+            base_ms: 5,
+            max_ms: 0,
+        };
+
+        assert_eq!(backoff_delay(backoff, 0, 0), None);
+    }
+
+    #[test]
+    fn test_detects_busy_errors() {
+        assert!(is_busy_error(&libsql::Error::SqliteFailure(
+            5,
+            "database is locked".to_string()
+        )));
+        // SQLITE_BUSY_SNAPSHOT
+        assert!(is_busy_error(&libsql::Error::SqliteFailure(
+            517,
+            "database is locked".to_string()
+        )));
+        assert!(!is_busy_error(&libsql::Error::SqliteFailure(
+            19,
+            "UNIQUE constraint failed: users.email".to_string()
+        )));
+    }
+}
+
+/// Tests for NDJSON row serialisation
+mod row_to_json_tests {
+    use super::*;
+    use libsql::Value;
+
+    #[test]
+    fn test_base64_rfc_4648_vectors() {
+        assert_eq!(base64_encode(b""), "");
+        assert_eq!(base64_encode(b"f"), "Zg==");
+        assert_eq!(base64_encode(b"fo"), "Zm8=");
+        assert_eq!(base64_encode(b"foo"), "Zm9v");
+        assert_eq!(base64_encode(b"foob"), "Zm9vYg==");
+        assert_eq!(base64_encode(b"fooba"), "Zm9vYmE=");
+        assert_eq!(base64_encode(b"foobar"), "Zm9vYmFy");
+        assert_eq!(base64_encode(&[0xff, 0xfe, 0x00]), "//4A");
+    }
+
+    #[test]
+    fn test_row_keys_and_value_types() {
+        let columns = vec![
+            "id".to_string(),
+            "name".to_string(),
+            "score".to_string(),
+            "data".to_string(),
+            "note".to_string(),
+        ];
+        let values = vec![
+            Value::Integer(1),
+            Value::Text("Alice".to_string()),
+            Value::Real(1.5),
+            Value::Blob(vec![1, 2, 3]),
+            Value::Null,
+        ];
+
+        assert_eq!(
+            row_to_json(&columns, &values),
+            r#"{"id":1,"name":"Alice","score":1.5,"data":"AQID","note":null}"#
+        );
+    }
+
+    #[test]
+    fn test_strings_are_escaped() {
+        let columns = vec!["say \"hi\"".to_string()];
+        let values = vec![Value::Text("line\nbreak\t\\ \u{1}".to_string())];
+
+        assert_eq!(
+            row_to_json(&columns, &values),
+            r#"{"say \"hi\"":"line\nbreak\t\\ \u0001"}"#
+        );
+    }
+
+    #[test]
+    fn test_non_finite_reals_become_null() {
+        let columns = vec!["x".to_string(), "y".to_string()];
+        let values = vec![Value::Real(f64::NAN), Value::Real(2.0)];
+
+        assert_eq!(row_to_json(&columns, &values), r#"{"x":null,"y":2.0}"#);
+    }
+}
+
+/// Tests for the built-in column reducers
+mod column_reducer_tests {
+    use super::*;
+    use crate::models::ReduceOp;
+    use libsql::Value;
+    use std::cmp::Ordering;
+
+    fn reduce(op: ReduceOp, values: Vec<Value>) -> Result<Reduced, String> {
+        let mut reducer = ColumnReducer::new(op);
+        for value in values {
+            reducer.push(value)?;
+        }
+        Ok(reducer.finish())
+    }
+
+    #[test]
+    fn test_sum_stays_integer_until_a_real_is_seen() {
+        let ints = vec![Value::Integer(1), Value::Null, Value::Integer(41)];
+        assert_eq!(
+            reduce(ReduceOp::Sum, ints).unwrap(),
+            Reduced::Value(Value::Integer(42))
+        );
+
+        let mixed = vec![Value::Integer(1), Value::Real(0.5)];
+        assert_eq!(
+            reduce(ReduceOp::Sum, mixed).unwrap(),
+            Reduced::Value(Value::Real(1.5))
+        );
+    }
+
+    #[test]
+    fn test_sum_of_nothing_is_null() {
+        assert_eq!(
+            reduce(ReduceOp::Sum, vec![Value::Null]).unwrap(),
+            Reduced::Value(Value::Null)
+        );
+    }
+
+    #[test]
+    fn test_sum_rejects_text_and_overflow() {
+        let text = vec![Value::Integer(1), Value::Text("2".to_string())];
+        assert!(reduce(ReduceOp::Sum, text).unwrap_err().contains("text"));
+
+        let overflow = vec![Value::Integer(i64::MAX), Value::Integer(1)];
+        assert!(reduce(ReduceOp::Sum, overflow)
+            .unwrap_err()
+            .contains("overflow"));
+    }
+
+    #[test]
+    fn test_min_and_max_follow_sqlite_ordering() {
+        let values = || {
+            vec![
+                Value::Text("b".to_string()),
+                Value::Integer(3),
+                Value::Null,
+                Value::Real(2.5),
+                Value::Blob(vec![0]),
+            ]
+        };
+
+        assert_eq!(
+            reduce(ReduceOp::Min, values()).unwrap(),
+            Reduced::Value(Value::Real(2.5))
+        );
+        assert_eq!(
+            reduce(ReduceOp::Max, values()).unwrap(),
+            Reduced::Value(Value::Blob(vec![0]))
+        );
+        assert_eq!(
+            compare_values(&Value::Integer(2), &Value::Real(2.0)),
+            Ordering::Equal
+        );
+    }
+
+    #[test]
+    fn test_distinct_keeps_first_appearance_order() {
+        let values = vec![
+            Value::Text("b".to_string()),
+            Value::Integer(1),
+            Value::Text("b".to_string()),
+            Value::Null,
+            Value::Integer(1),
+            Value::Text("a".to_string()),
+        ];
+
+        assert_eq!(
+            reduce(ReduceOp::Distinct, values).unwrap(),
+            Reduced::Distinct(vec![
+                Value::Text("b".to_string()),
+                Value::Integer(1),
+                Value::Text("a".to_string()),
+            ])
+        );
+    }
+}
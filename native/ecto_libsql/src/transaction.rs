@@ -110,6 +110,22 @@ impl TransactionEntryGuard {
             .ok_or_else(|| rustler::Error::Term(Box::new("Transaction entry is missing")))
     }
 
+    /// Get a mutable reference to the transaction's savepoint stack.
+    ///
+    /// Returns an error if the entry has already been consumed via `consume()`.
+    pub fn savepoints_mut(&mut self) -> Result<&mut Vec<String>, rustler::Error> {
+        if self.consumed {
+            return Err(rustler::Error::Term(Box::new(
+                "Transaction entry already consumed",
+            )));
+        }
+
+        self.entry
+            .as_mut()
+            .map(|e| &mut e.savepoints)
+            .ok_or_else(|| rustler::Error::Term(Box::new("Transaction entry is missing")))
+    }
+
     /// Consume the guard without re-inserting the entry.
     ///
     /// This is used for commit/rollback operations where the transaction
@@ -152,9 +168,13 @@ impl Drop for TransactionEntryGuard {
 
 /// Begin a new database transaction.
 ///
-/// Starts a transaction with the default DEFERRED behaviour, which acquires
-/// locks only when needed. Use `begin_transaction_with_behavior` for fine-grained
-/// control over transaction locking.
+/// Starts a transaction with the connection's default behaviour, set by the
+/// `default_transaction_mode` connect option. Without that option this is DEFERRED,
+/// which acquires locks only when needed. Use `begin_transaction_with_behavior` to
+/// override the default for a single transaction.
+///
+/// The transaction runs on the connection itself, so it covers every `ATTACH`ed
+/// database too: commit and rollback apply to all of them.
 ///
 /// # Arguments
 /// - `conn_id`: Database connection ID
@@ -170,21 +190,31 @@ pub fn begin_transaction(conn_id: &str) -> NifResult<String> {
     drop(conn_map); // Drop lock before async operation
 
     // Clone the inner connection Arc and drop the outer lock before async operations
-    let connection = {
+    let (connection, default_mode) = {
         let client_guard = utils::safe_lock_arc(&client, "begin_transaction client")?;
-        client_guard.client.clone()
+        (
+            client_guard.client.clone(),
+            client_guard.default_transaction_mode,
+        )
     }; // Outer lock dropped here
 
+    // Validated at connect time, so this only fails if the registry entry is corrupt
+    let trx_behavior = decode::decode_transaction_behavior(default_mode).ok_or_else(|| {
+        rustler::Error::Term(Box::new(format!(
+            "Invalid default transaction mode: {default_mode:?}"
+        )))
+    })?;
+
     // SAFETY: We use TOKIO_RUNTIME.block_on(), which runs the future synchronously on a dedicated
     // thread pool. This prevents deadlocks that could occur if we were in a true async context
     // with std::sync::Mutex guards held across await points.
     #[allow(clippy::await_holding_lock)]
     let trx = TOKIO_RUNTIME.block_on(async {
-        // Lock must be held across await because transaction() returns a Future that
-        // borrows from the Connection. We cannot drop the guard before awaiting.
+        // Lock must be held across await because transaction_with_behavior() returns a Future
+        // that borrows from the Connection. We cannot drop the guard before awaiting.
         let conn_guard = utils::safe_lock_arc(&connection, "begin_transaction conn")?;
         conn_guard
-            .transaction()
+            .transaction_with_behavior(trx_behavior)
             .await
             .map_err(|e| rustler::Error::Term(Box::new(format!("Begin failed: {e}"))))
     })?;
@@ -193,6 +223,7 @@ pub fn begin_transaction(conn_id: &str) -> NifResult<String> {
     let entry = TransactionEntry {
         conn_id: conn_id.to_string(),
         transaction: trx,
+        savepoints: Vec::new(),
     };
     utils::safe_lock(&TXN_REGISTRY, "begin_transaction txn_registry")?
         .insert(trx_id.clone(), entry);
@@ -257,6 +288,7 @@ pub fn begin_transaction_with_behavior(conn_id: &str, behavior: Atom) -> NifResu
     let entry = TransactionEntry {
         conn_id: conn_id.to_string(),
         transaction: trx,
+        savepoints: Vec::new(),
     };
     utils::safe_lock(
         &TXN_REGISTRY,
@@ -286,12 +318,15 @@ pub fn execute_with_transaction<'a>(
     query: &str,
     args: Vec<Term<'a>>,
 ) -> NifResult<u64> {
+    utils::ensure_sql_allowed(conn_id, query)?;
+
     // Decode args before locking
-    let decoded_args: Vec<libsql::Value> = args
+    let mut decoded_args: Vec<libsql::Value> = args
         .into_iter()
         .map(|t| utils::decode_term_to_value(t))
         .collect::<Result<_, _>>()
         .map_err(|e| rustler::Error::Term(Box::new(e)))?;
+    utils::apply_bind_options(conn_id, &mut decoded_args)?;
 
     // Take transaction entry with ownership verification
     let guard = TransactionEntryGuard::take(trx_id, conn_id)?;
@@ -301,7 +336,7 @@ pub fn execute_with_transaction<'a>(
 
     let result = TOKIO_RUNTIME
         .block_on(async { trx.execute(query, decoded_args).await })
-        .map_err(|e| rustler::Error::Term(Box::new(format!("Execute failed: {e}"))));
+        .map_err(|e| utils::statement_error(format!("Execute failed: {e}")));
     // Guard automatically re-inserts the entry on drop
     result
 }
@@ -328,13 +363,15 @@ pub fn query_with_trx_args<'a>(
 ) -> NifResult<Term<'a>> {
     // UTF-8 validation is guaranteed by Rust's &str type and Rustler's conversion,
     // so we can rely on the type system rather than runtime checks.
+    utils::ensure_sql_allowed(conn_id, query)?;
 
     // Decode args before locking
-    let decoded_args: Vec<libsql::Value> = args
+    let mut decoded_args: Vec<libsql::Value> = args
         .into_iter()
         .map(|t| utils::decode_term_to_value(t))
         .collect::<Result<_, _>>()
         .map_err(|e| rustler::Error::Term(Box::new(e)))?;
+    utils::apply_bind_options(conn_id, &mut decoded_args)?;
 
     // Determine whether to use query() or execute() based on statement
     let use_query = utils::should_use_query(query);
@@ -375,7 +412,7 @@ pub fn query_with_trx_args<'a>(
                     let enhanced_msg = utils::enhance_constraint_error(&conn_guard, &error_msg)
                         .await
                         .unwrap_or(error_msg);
-                    Err(rustler::Error::Term(Box::new(enhanced_msg)))
+                    Err(utils::statement_error(enhanced_msg))
                 }
             }
         } else {
@@ -392,7 +429,7 @@ pub fn query_with_trx_args<'a>(
                     let enhanced_msg = utils::enhance_constraint_error(&conn_guard, &error_msg)
                         .await
                         .unwrap_or(error_msg);
-                    Err(rustler::Error::Term(Box::new(enhanced_msg)))
+                    Err(utils::statement_error(enhanced_msg))
                 }
             }
         }
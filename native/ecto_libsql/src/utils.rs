@@ -2,13 +2,15 @@
 ///
 /// This module provides commonly used helper functions for locking, error handling,
 /// value conversion, and result processing.
-use crate::models::LibSQLConn;
+use crate::constants::{CONNECTION_REGISTRY, UNCACHED_STMT_REGISTRY};
+use crate::models::{BusyBackoff, LibSQLConn, ReduceOp};
 use libsql::{Rows, Value};
 use rustler::types::atom::nil;
-use rustler::{Binary, Encoder, Env, OwnedBinary, Term};
-use std::collections::HashMap;
+use rustler::{Binary, Encoder, Env, NifResult, OwnedBinary, Term};
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
 use std::sync::{Arc, Mutex, MutexGuard};
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 /// Safely lock a mutex with proper error handling
 ///
@@ -48,18 +50,45 @@ pub async fn sync_with_timeout(
 ) -> Result<(), String> {
     let timeout = Duration::from_secs(timeout_secs);
 
-    tokio::time::timeout(timeout, async {
+    let result = tokio::time::timeout(timeout, async {
         let client_guard =
             safe_lock_arc(client, "sync_with_timeout client").map_err(|e| format!("{e:?}"))?;
-        client_guard
+        let replicated = client_guard
             .db
             .sync()
             .await
             .map_err(|e| format!("Sync error: {e}"))?;
-        Ok::<_, String>(())
+        Ok::<_, String>(replicated.frames_synced() as u64)
     })
     .await
-    .map_err(|_| format!("Sync timeout after {timeout_secs} seconds"))?
+    .map_err(|_| format!("Sync timeout after {timeout_secs} seconds"))
+    .and_then(|r| r);
+
+    record_sync_metrics(client, &result);
+    result.map(|_| ())
+}
+
+/// Update a connection's replica sync metrics with the outcome of a sync.
+///
+/// `outcome` is the number of frames applied on success, or the error message.
+/// Best-effort: if the connection lock is poisoned the metrics are left unchanged
+/// rather than masking the sync result.
+pub fn record_sync_metrics(client: &Arc<Mutex<LibSQLConn>>, outcome: &Result<u64, String>) {
+    let Ok(mut client_guard) = safe_lock_arc(client, "record_sync_metrics client") else {
+        return;
+    };
+
+    match outcome {
+        Ok(frames_synced) => {
+            let now_ms = std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map_or(0, |d| d.as_millis() as u64);
+            client_guard
+                .replica_metrics
+                .record_success(*frames_synced, now_ms);
+        }
+        Err(error) => client_guard.replica_metrics.record_failure(error),
+    }
 }
 
 /// Build an empty result map for write operations (INSERT/UPDATE/DELETE without RETURNING)
@@ -188,9 +217,32 @@ pub async fn enhance_constraint_error(
     Ok(error_message.to_string())
 }
 
+/// Read SQLite's schema cookie (`PRAGMA schema_version`)
+///
+/// The schema version is incremented by SQLite on every schema change (CREATE, DROP,
+/// ALTER, etc.), which makes it a cheap way to detect that cached prepared statements
+/// may have been compiled against an older schema.
+pub async fn read_schema_version(conn: &libsql::Connection) -> Result<i64, String> {
+    let mut rows = conn
+        .query("PRAGMA schema_version", ())
+        .await
+        .map_err(|e| format!("Failed to query schema version: {e}"))?;
+
+    let row = rows
+        .next()
+        .await
+        .map_err(|e| format!("Failed to read schema version: {e}"))?
+        .ok_or_else(|| "PRAGMA schema_version returned no rows".to_string())?;
+
+    row.get::<i64>(0)
+        .map_err(|e| format!("Failed to decode schema version: {e}"))
+}
+
 /// Collect rows from a query result into a map of columns and rows
 ///
 /// Processes async row iterator and converts LibSQL values to Elixir terms.
+/// Values are decoded from each row's storage class rather than the column's
+/// declared type, so a NUMERIC column can yield integers and floats side by side.
 pub async fn collect_rows<'a>(env: Env<'a>, mut rows: Rows) -> Result<Term<'a>, rustler::Error> {
     let mut column_names: Vec<String> = Vec::new();
     let mut collected_rows: Vec<Vec<Term<'a>>> = Vec::new();
@@ -269,37 +321,44 @@ pub enum QueryType {
     Insert,
     Update,
     Delete,
-    Create,
+    /// `CREATE ...`; `temp` is set for `CREATE TEMP`/`CREATE TEMPORARY` objects, which live
+    /// only as long as the connection that created them
+    Create {
+        temp: bool,
+    },
     Drop,
     Alter,
     Begin,
     Commit,
     Rollback,
+    Reindex,
     Other,
 }
 
 /// Detect the query type from a SQL statement
 ///
-/// Examines the first keyword to categorize the statement.
+/// Examines the first keyword to categorize the statement, and the second to tell
+/// temporary `CREATE` statements apart.
 pub fn detect_query_type(query: &str) -> QueryType {
-    let trimmed = query.trim_start();
-    let keyword = trimmed
-        .split_whitespace()
-        .next()
-        .unwrap_or("")
-        .to_uppercase();
+    let mut words = query.split_whitespace();
+    let keyword = words.next().unwrap_or("").to_uppercase();
 
     match keyword.as_str() {
         "SELECT" => QueryType::Select,
         "INSERT" => QueryType::Insert,
         "UPDATE" => QueryType::Update,
         "DELETE" => QueryType::Delete,
-        "CREATE" => QueryType::Create,
+        "CREATE" => QueryType::Create {
+            temp: words.next().is_some_and(|word| {
+                word.eq_ignore_ascii_case("TEMP") || word.eq_ignore_ascii_case("TEMPORARY")
+            }),
+        },
         "DROP" => QueryType::Drop,
         "ALTER" => QueryType::Alter,
         "BEGIN" => QueryType::Begin,
         "COMMIT" => QueryType::Commit,
         "ROLLBACK" => QueryType::Rollback,
+        "REINDEX" => QueryType::Reindex,
         _ => QueryType::Other,
     }
 }
@@ -310,8 +369,15 @@ pub fn detect_query_type(query: &str) -> QueryType {
 /// Returns the index of the first non-whitespace, non-comment character.
 #[inline]
 fn skip_whitespace_and_comments(bytes: &[u8]) -> usize {
+    skip_whitespace_and_comments_from(bytes, 0)
+}
+
+/// Skip whitespace and SQL comments starting at `pos`.
+///
+/// Returns the index of the first non-whitespace, non-comment character at or after `pos`.
+#[inline]
+fn skip_whitespace_and_comments_from(bytes: &[u8], mut pos: usize) -> usize {
     let len = bytes.len();
-    let mut pos = 0;
 
     loop {
         // Skip whitespace
@@ -360,7 +426,8 @@ fn skip_whitespace_and_comments(bytes: &[u8]) -> usize {
 
 /// Determines if a query should use query() or execute()
 ///
-/// Returns true if should use query() (SELECT or has RETURNING clause).
+/// Returns true if should use query() (SELECT, EXPLAIN, a PRAGMA that reports rows,
+/// or has RETURNING clause).
 ///
 /// Performance optimisations:
 /// - Zero allocations (no to_uppercase())
@@ -373,6 +440,12 @@ fn skip_whitespace_and_comments(bytes: &[u8]) -> usize {
 /// This function correctly skips leading SQL comments (both `-- single line`
 /// and `/* block */` style) before checking for query keywords.
 ///
+/// ## PRAGMA Handling
+///
+/// `PRAGMA [schema.]name` and `PRAGMA [schema.]name(arg)` report rows, so they use
+/// `query()`. Only an assignment such as `PRAGMA archive.foreign_keys = ON` uses
+/// `execute()`.
+///
 /// ## Limitation: String Literal Handling
 ///
 /// This function performs simple keyword matching and does not parse SQL syntax.
@@ -429,7 +502,8 @@ pub fn should_use_query(sql: &str) -> bool {
     }
 
     // Check if starts with WITH (CTEs - Common Table Expressions)
-    // CTEs typically return rows and should use query() path
+    // Skip the CTE definitions and route on the primary statement: SELECT returns
+    // rows, while `WITH ... UPDATE/DELETE/INSERT` only does with a RETURNING clause.
     if len - start >= 4
         && (bytes[start] == b'W' || bytes[start] == b'w')
         && (bytes[start + 1] == b'I' || bytes[start + 1] == b'i')
@@ -438,47 +512,948 @@ pub fn should_use_query(sql: &str) -> bool {
         // Verify it's followed by whitespace or end of string
         && (start + 4 >= len || bytes[start + 4].is_ascii_whitespace())
     {
-        return true;
+        return match skip_cte_definitions(bytes, start + 4) {
+            Some(pos) if is_dml_keyword(bytes, pos) => contains_returning(bytes, pos),
+            // SELECT/VALUES, or a clause we could not parse: the query path is always safe
+            _ => true,
+        };
     }
 
-    // Check for RETURNING clause (case-insensitive)
-    if len >= 9 {
-        let target = b"RETURNING";
-        let mut i = 0;
-
-        while i <= len - 9 {
-            // Only check if preceded by whitespace or it's at the start
-            if i == 0 || bytes[i - 1].is_ascii_whitespace() {
-                let mut matches = true;
-                for j in 0..9 {
-                    let c = bytes[i + j];
-                    let t = target[j];
-                    // Case-insensitive comparison for ASCII
-                    if c != t && c != t.to_ascii_lowercase() {
-                        matches = false;
-                        break;
-                    }
+    if starts_with_keyword(bytes, start, b"PRAGMA") {
+        let (_, name_end) = pragma_name_span(bytes, start + 6);
+        return bytes.get(skip_whitespace_and_comments_from(bytes, name_end)) != Some(&b'=');
+    }
+
+    contains_returning(bytes, 0)
+}
+
+/// Scan for a standalone `RETURNING` keyword (case-insensitive) at or after `from`.
+///
+/// Quoted literals, quoted identifiers and comments are skipped, so `returning`
+/// inside a string or a name such as `returning_at` does not count.
+#[inline]
+fn contains_returning(bytes: &[u8], from: usize) -> bool {
+    let mut i = from;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\'' | b'"' | b'`' | b'[' => match skip_quoted(bytes, i) {
+                Some(next) => i = next,
+                // Unterminated literal: nothing after it is a keyword
+                None => return false,
+            },
+            b'-' | b'/' => {
+                let next = skip_whitespace_and_comments_from(bytes, i);
+                // Not a comment: just a minus or division operator
+                i = if next == i { i + 1 } else { next };
+            }
+            b if is_identifier_byte(b) => {
+                let word_start = i;
+                while bytes.get(i).is_some_and(|&b| is_identifier_byte(b)) {
+                    i += 1;
+                }
+                if bytes[word_start..i].eq_ignore_ascii_case(b"RETURNING") {
+                    return true;
+                }
+            }
+            _ => i += 1,
+        }
+    }
+
+    false
+}
+
+/// Check whether `bytes[pos..]` starts with `keyword` (case-insensitive) as a whole word.
+#[inline]
+fn starts_with_keyword(bytes: &[u8], pos: usize, keyword: &[u8]) -> bool {
+    let end = pos + keyword.len();
+    bytes
+        .get(pos..end)
+        .is_some_and(|word| word.eq_ignore_ascii_case(keyword))
+        && bytes.get(end).is_none_or(|&b| !is_identifier_byte(b))
+}
+
+#[inline]
+fn is_identifier_byte(b: u8) -> bool {
+    b.is_ascii_alphanumeric() || b == b'_' || b == b'$' || b >= 0x80
+}
+
+/// Whether the statement at `pos` is a data-modifying statement that may carry RETURNING.
+#[inline]
+fn is_dml_keyword(bytes: &[u8], pos: usize) -> bool {
+    [b"INSERT".as_slice(), b"UPDATE", b"DELETE", b"REPLACE"]
+        .iter()
+        .any(|keyword| starts_with_keyword(bytes, pos, keyword))
+}
+
+/// Skip a quoted literal or identifier opened at `pos`, returning the index after it.
+///
+/// Handles `'string'`, `"identifier"` and `` `identifier` `` (with doubled-quote escapes)
+/// and `[identifier]`. Returns `None` if the literal is unterminated.
+fn skip_quoted(bytes: &[u8], pos: usize) -> Option<usize> {
+    let close = match bytes.get(pos)? {
+        b'\'' => b'\'',
+        b'"' => b'"',
+        b'`' => b'`',
+        b'[' => b']',
+        _ => return None,
+    };
+
+    let mut i = pos + 1;
+    while i < bytes.len() {
+        if bytes[i] == close {
+            // A doubled quote is an escaped quote, not the end (not applicable to [..])
+            if close != b']' && bytes.get(i + 1) == Some(&close) {
+                i += 2;
+                continue;
+            }
+            return Some(i + 1);
+        }
+        i += 1;
+    }
+
+    None
+}
+
+/// Skip a parenthesised group opened at `pos`, returning the index after the matching `)`.
+///
+/// Nested parentheses, quoted literals and comments inside the group are skipped.
+fn skip_parenthesised(bytes: &[u8], pos: usize) -> Option<usize> {
+    if bytes.get(pos) != Some(&b'(') {
+        return None;
+    }
+
+    let mut depth = 0usize;
+    let mut i = pos;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'(' => {
+                depth += 1;
+                i += 1;
+            }
+            b')' => {
+                depth -= 1;
+                i += 1;
+                if depth == 0 {
+                    return Some(i);
                 }
+            }
+            b'\'' | b'"' | b'`' | b'[' => i = skip_quoted(bytes, i)?,
+            b'-' | b'/' => {
+                let next = skip_whitespace_and_comments_from(bytes, i);
+                // Not a comment: just a minus or division operator
+                i = if next == i { i + 1 } else { next };
+            }
+            _ => i += 1,
+        }
+    }
+
+    None
+}
+
+/// Skip the CTE definitions of a `WITH` clause.
+///
+/// `pos` must point just past the `WITH` keyword. Returns the index of the primary
+/// statement keyword that follows the last CTE, or `None` if the clause could not be
+/// parsed, in which case callers should fall back to the query path.
+fn skip_cte_definitions(bytes: &[u8], pos: usize) -> Option<usize> {
+    let mut pos = skip_whitespace_and_comments_from(bytes, pos);
+
+    if starts_with_keyword(bytes, pos, b"RECURSIVE") {
+        pos += b"RECURSIVE".len();
+    }
+
+    loop {
+        // CTE name, bare or quoted
+        pos = skip_whitespace_and_comments_from(bytes, pos);
+        let name_start = pos;
+        if matches!(bytes.get(pos), Some(b'"' | b'`' | b'[')) {
+            pos = skip_quoted(bytes, pos)?;
+        } else {
+            while bytes.get(pos).is_some_and(|&b| is_identifier_byte(b)) {
+                pos += 1;
+            }
+        }
+        if pos == name_start {
+            return None;
+        }
+
+        // Optional column list: name(a, b)
+        pos = skip_whitespace_and_comments_from(bytes, pos);
+        if bytes.get(pos) == Some(&b'(') {
+            pos = skip_parenthesised(bytes, pos)?;
+            pos = skip_whitespace_and_comments_from(bytes, pos);
+        }
+
+        if !starts_with_keyword(bytes, pos, b"AS") {
+            return None;
+        }
+        pos = skip_whitespace_and_comments_from(bytes, pos + b"AS".len());
+
+        // Optional [NOT] MATERIALIZED hint
+        if starts_with_keyword(bytes, pos, b"NOT") {
+            pos = skip_whitespace_and_comments_from(bytes, pos + b"NOT".len());
+        }
+        if starts_with_keyword(bytes, pos, b"MATERIALIZED") {
+            pos = skip_whitespace_and_comments_from(bytes, pos + b"MATERIALIZED".len());
+        }
+
+        // CTE body
+        pos = skip_parenthesised(bytes, pos)?;
+        pos = skip_whitespace_and_comments_from(bytes, pos);
+
+        if bytes.get(pos) == Some(&b',') {
+            pos += 1;
+            continue;
+        }
+
+        return Some(pos);
+    }
+}
+
+/// Quote a SQL identifier (table, column or index name) for safe interpolation.
+///
+/// Wraps the name in double quotes and doubles any embedded double quotes, so
+/// arbitrary names cannot break out of the identifier.
+pub fn quote_identifier(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\"\""))
+}
+
+/// Build a `SELECT count(*)` statement for `table` with an optional WHERE clause.
+///
+/// The table name is validated and quoted; the WHERE clause is caller-supplied SQL
+/// and should use `?` placeholders for values. A blank clause is treated as absent.
+pub fn build_count_sql(table: &str, where_clause: Option<&str>) -> Result<String, String> {
+    if table.trim().is_empty() {
+        return Err("Table name cannot be empty".to_string());
+    }
+    if table.contains('\0') {
+        return Err("Table name cannot contain NUL bytes".to_string());
+    }
+
+    let base = format!("SELECT count(*) FROM {}", quote_identifier(table));
+
+    match where_clause.map(str::trim) {
+        Some(clause) if !clause.is_empty() => Ok(format!("{base} WHERE {clause}")),
+        _ => Ok(base),
+    }
+}
+
+/// Build the `CREATE TABLE` and `INSERT` statements for a materialised query result.
+///
+/// Each column's declared type comes from its first non-NULL value in `rows`:
+/// `INTEGER`, `REAL`, `TEXT` or `BLOB`. A column with no non-NULL value gets no
+/// declared type. The INSERT takes one `?` placeholder per column.
+pub fn build_materialize_sql(
+    table: &str,
+    columns: &[String],
+    rows: &[Vec<Value>],
+) -> Result<(String, String), String> {
+    if table.trim().is_empty() {
+        return Err("Table name cannot be empty".to_string());
+    }
+    if table.contains('\0') {
+        return Err("Table name cannot contain NUL bytes".to_string());
+    }
+    if columns.is_empty() {
+        return Err("Query returned no columns".to_string());
+    }
+
+    let column_defs: Vec<String> = columns
+        .iter()
+        .enumerate()
+        .map(|(i, column)| {
+            let declared = rows.iter().find_map(|row| match row.get(i) {
+                Some(Value::Integer(_)) => Some("INTEGER"),
+                Some(Value::Real(_)) => Some("REAL"),
+                Some(Value::Text(_)) => Some("TEXT"),
+                Some(Value::Blob(_)) => Some("BLOB"),
+                Some(Value::Null) | None => None,
+            });
+
+            match declared {
+                Some(declared) => format!("{} {declared}", quote_identifier(column)),
+                None => quote_identifier(column),
+            }
+        })
+        .collect();
+
+    let table = quote_identifier(table);
+    let placeholders = vec!["?"; columns.len()].join(", ");
+
+    Ok((
+        format!("CREATE TABLE {table} ({})", column_defs.join(", ")),
+        format!("INSERT INTO {table} VALUES ({placeholders})"),
+    ))
+}
+
+/// Whether a `CREATE TABLE` statement declares an AUTOINCREMENT column.
+///
+/// Matches `AUTOINCREMENT` as a whole word, case-insensitively, so names that merely
+/// contain it (e.g. `autoincrement_hint`) do not count.
+pub fn uses_autoincrement(create_sql: &str) -> bool {
+    create_sql
+        .split(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+        .any(|word| word.eq_ignore_ascii_case("AUTOINCREMENT"))
+}
+
+/// Build one keyset page over `base_sql`, ordered by `order_col`.
+///
+/// The base query is wrapped as a subquery so it may carry its own WHERE, joins or
+/// placeholders. When `after_key` is set a trailing `?` placeholder is added for the
+/// last-seen key, which the caller binds after the base query's own parameters.
+pub fn build_keyset_sql(
+    base_sql: &str,
+    order_col: &str,
+    after_key: bool,
+    limit: u64,
+) -> Result<String, String> {
+    let base = base_sql.trim().trim_end_matches(';').trim_end();
+    if base.is_empty() {
+        return Err("Base query cannot be empty".to_string());
+    }
+    if order_col.trim().is_empty() {
+        return Err("Order column cannot be empty".to_string());
+    }
+    if order_col.contains('\0') {
+        return Err("Order column cannot contain NUL bytes".to_string());
+    }
+    if limit == 0 {
+        return Err("Page size must be greater than zero".to_string());
+    }
+
+    let col = quote_identifier(order_col);
+    let filter = if after_key {
+        format!(" WHERE {col} > ?")
+    } else {
+        String::new()
+    };
+
+    Ok(format!(
+        "SELECT * FROM ({base}){filter} ORDER BY {col} LIMIT {limit}"
+    ))
+}
+
+/// Build the statements for one keyset batch of a batched `copy_into`.
+///
+/// Returns `(bound_sql, insert_sql)`. `bound_sql` finds the largest key among the
+/// next `batch_size` rows (NULL once the source is exhausted); `insert_sql` copies
+/// every row whose key is in `(last_key, bound]`. Both take the base query's
+/// parameters, then the last-seen key when `after_key` is set, and `insert_sql`
+/// finally the bound. Rows sharing the bound key all land in the same batch, so
+/// non-unique keys never skip or repeat rows; rows with a NULL key are not copied.
+///
+/// The first batch filters out NULL keys explicitly: they sort first, so a page of
+/// only NULL keys would otherwise have a NULL bound and end the copy early.
+pub fn build_copy_batch_sql(
+    dest_table: &str,
+    select_sql: &str,
+    key: &str,
+    after_key: bool,
+    batch_size: u64,
+) -> Result<(String, String), String> {
+    // Validates the base query, key and batch size the same way keyset pages do
+    build_keyset_sql(select_sql, key, after_key, batch_size)?;
+
+    let base = select_sql.trim().trim_end_matches(';').trim_end();
+    let col = quote_identifier(key);
+    let lower = if after_key {
+        format!("{col} > ?")
+    } else {
+        format!("{col} IS NOT NULL")
+    };
+
+    Ok((
+        format!(
+            "SELECT max({col}) FROM (SELECT * FROM ({base}) WHERE {lower} ORDER BY {col} LIMIT {batch_size})"
+        ),
+        format!(
+            "INSERT INTO {} SELECT * FROM ({base}) WHERE {lower} AND {col} <= ?",
+            quote_identifier(dest_table)
+        ),
+    ))
+}
+
+/// Pragmas that only report state when run without a value, e.g. `PRAGMA user_version`.
+const READ_ONLY_PRAGMAS: &[&[u8]] = &[
+    b"application_id",
+    b"auto_vacuum",
+    b"automatic_index",
+    b"busy_timeout",
+    b"cache_size",
+    b"cache_spill",
+    b"cell_size_check",
+    b"collation_list",
+    b"compile_options",
+    b"data_version",
+    b"database_list",
+    b"defer_foreign_keys",
+    b"encoding",
+    b"foreign_keys",
+    b"freelist_count",
+    b"function_list",
+    b"journal_mode",
+    b"journal_size_limit",
+    b"locking_mode",
+    b"max_page_count",
+    b"mmap_size",
+    b"module_list",
+    b"page_count",
+    b"page_size",
+    b"pragma_list",
+    b"query_only",
+    b"read_uncommitted",
+    b"recursive_triggers",
+    b"schema_version",
+    b"secure_delete",
+    b"synchronous",
+    b"temp_store",
+    b"user_version",
+    b"writable_schema",
+];
+
+/// Introspection pragmas that may also take an argument, e.g. `PRAGMA table_info(users)`.
+const READ_ONLY_PRAGMA_FUNCTIONS: &[&[u8]] = &[
+    b"foreign_key_check",
+    b"foreign_key_list",
+    b"index_info",
+    b"index_list",
+    b"index_xinfo",
+    b"integrity_check",
+    b"quick_check",
+    b"table_info",
+    b"table_list",
+    b"table_xinfo",
+];
+
+/// Locate the pragma name following the `PRAGMA` keyword that ends at `pos`.
+///
+/// Skips an optional schema prefix, so `PRAGMA archive.table_info(x)` yields the span of
+/// `table_info`. Returns `(name_start, name_end)`; the span is empty when no identifier
+/// follows.
+fn pragma_name_span(bytes: &[u8], pos: usize) -> (usize, usize) {
+    let mut pos = skip_whitespace_and_comments_from(bytes, pos);
+    let mut name_start = pos;
+    while bytes.get(pos).is_some_and(|&b| is_identifier_byte(b)) {
+        pos += 1;
+    }
 
-                if matches {
-                    // Verify it's followed by whitespace or end of string
-                    if i + 9 >= len || bytes[i + 9].is_ascii_whitespace() {
-                        return true;
+    // Schema-qualified form: PRAGMA main.table_info(users)
+    if bytes.get(pos) == Some(&b'.') {
+        pos += 1;
+        name_start = pos;
+        while bytes.get(pos).is_some_and(|&b| is_identifier_byte(b)) {
+            pos += 1;
+        }
+    }
+
+    (name_start, pos)
+}
+
+/// Whether `sql` is a PRAGMA that could change connection or database state.
+///
+/// Used to enforce read-only connections. Only allowlisted pragmas are accepted:
+/// status queries without a value (`PRAGMA user_version`) and introspection calls
+/// (`PRAGMA table_info(users)`). Any assignment (`PRAGMA writable_schema = ON`),
+/// unknown pragma, or name we cannot parse counts as mutating. Non-PRAGMA
+/// statements return `false`; the read-only open flags cover ordinary writes.
+pub fn pragma_mutates_state(sql: &str) -> bool {
+    let bytes = sql.as_bytes();
+    let start = skip_whitespace_and_comments(bytes);
+
+    if !starts_with_keyword(bytes, start, b"PRAGMA") {
+        return false;
+    }
+
+    let (name_start, pos) = pragma_name_span(bytes, start + 6);
+    let name = &bytes[name_start..pos];
+    if name.is_empty() {
+        return true;
+    }
+
+    let listed = |list: &[&[u8]]| list.iter().any(|pragma| pragma.eq_ignore_ascii_case(name));
+
+    match bytes.get(skip_whitespace_and_comments_from(bytes, pos)) {
+        None | Some(b';') => !listed(READ_ONLY_PRAGMAS) && !listed(READ_ONLY_PRAGMA_FUNCTIONS),
+        Some(b'(') => !listed(READ_ONLY_PRAGMA_FUNCTIONS),
+        Some(_) => true,
+    }
+}
+
+/// Reject `sql` before it reaches the connection if the connect options forbid it.
+///
+/// - `{:error, :sql_too_long}` if `sql` is longer than the connection's `max_sql_length`.
+/// - `{:error, :read_only}` if `conn_id` is a read-only connection and any statement in
+///   it is a state-changing PRAGMA (see [`pragma_mutates_state`]).
+///
+/// Statements are split naively on `;` so multi-statement batches are covered too; a
+/// semicolon inside a literal can only make the check stricter. Unknown connections
+/// pass, leaving the caller to report its usual "connection not found" error.
+///
+/// Must be called before the caller locks the connection.
+pub fn ensure_sql_allowed(conn_id: &str, sql: &str) -> Result<(), rustler::Error> {
+    use crate::constants::{read_only, sql_too_long};
+
+    let client = {
+        let conn_map = safe_lock(&CONNECTION_REGISTRY, "ensure_sql_allowed conn_map")?;
+        match conn_map.get(conn_id) {
+            Some(client) => client.clone(),
+            None => return Ok(()),
+        }
+    };
+
+    let (is_read_only, max_sql_length) = {
+        let conn = safe_lock_arc(&client, "ensure_sql_allowed client")?;
+        (conn.read_only, conn.max_sql_length)
+    };
+
+    if max_sql_length.is_some_and(|limit| sql.len() > limit) {
+        return Err(rustler::Error::Term(Box::new(sql_too_long())));
+    }
+
+    if is_read_only && sql.split(';').any(pragma_mutates_state) {
+        return Err(rustler::Error::Term(Box::new(read_only())));
+    }
+
+    Ok(())
+}
+
+/// Replace empty text values with `NULL`, leaving every other value untouched.
+pub fn coerce_empty_strings_to_null(params: &mut [Value]) {
+    for value in params.iter_mut() {
+        if matches!(value, Value::Text(text) if text.is_empty()) {
+            *value = Value::Null;
+        }
+    }
+}
+
+/// Apply the bind-time options of `conn_id` to already decoded parameters.
+///
+/// With the `empty_string_as_null` connect option set, empty text parameters are
+/// bound as `NULL`. Unknown connections pass unchanged, leaving the caller to report
+/// its usual "connection not found" error.
+///
+/// Must be called while the caller holds no registry or connection locks.
+pub fn apply_bind_options(conn_id: &str, params: &mut [Value]) -> Result<(), rustler::Error> {
+    let client = {
+        let conn_map = safe_lock(&CONNECTION_REGISTRY, "apply_bind_options conn_map")?;
+        match conn_map.get(conn_id) {
+            Some(client) => client.clone(),
+            None => return Ok(()),
+        }
+    };
+
+    if safe_lock_arc(&client, "apply_bind_options client")?.empty_string_as_null {
+        coerce_empty_strings_to_null(params);
+    }
+
+    Ok(())
+}
+
+/// Look up the SQL of a statement prepared while its connection had the statement cache
+/// disabled.
+///
+/// Returns `Ok(None)` for statement IDs not in `UNCACHED_STMT_REGISTRY`, so the caller
+/// falls through to `STMT_REGISTRY`, and an error if the statement belongs to another
+/// connection. Only the uncached registry is locked, never a connection.
+pub fn uncached_statement_sql(
+    conn_id: &str,
+    stmt_id: &str,
+) -> Result<Option<String>, rustler::Error> {
+    let registry = safe_lock(&UNCACHED_STMT_REGISTRY, "uncached_statement_sql registry")?;
+
+    match registry.get(stmt_id) {
+        Some((owner, sql)) => {
+            crate::decode::verify_statement_ownership(owner, conn_id)?;
+            Ok(Some(sql.clone()))
+        }
+        None => Ok(None),
+    }
+}
+
+/// Forget a statement prepared on a connection with its statement cache disabled.
+///
+/// Returns `true` if `stmt_id` was an uncached statement.
+pub fn forget_uncached_statement(stmt_id: &str) -> Result<bool, rustler::Error> {
+    Ok(safe_lock(
+        &UNCACHED_STMT_REGISTRY,
+        "forget_uncached_statement registry",
+    )?
+    .remove(stmt_id)
+    .is_some())
+}
+
+/// Error message fragments that mean a remote connection was lost rather than a query failing.
+const CONNECTION_LOST_MARKERS: &[&str] = &[
+    "broken pipe",
+    "connection aborted",
+    "connection closed",
+    "connection refused",
+    "connection reset",
+    "error sending request",
+    "error trying to connect",
+    "stream closed",
+    "stream expired",
+    "stream not found",
+    "unexpected eof",
+];
+
+/// Whether an error message reports a lost connection (network failure or expired stream).
+pub fn is_connection_lost(message: &str) -> bool {
+    let message = message.to_lowercase();
+    CONNECTION_LOST_MARKERS
+        .iter()
+        .any(|marker| message.contains(marker))
+}
+
+/// Error message fragments libsql reports when an embedded replica has no primary to
+/// forward a write to: running the write, or preparing it (the primary describes it).
+const REPLICA_WRITE_MARKERS: &[&str] = &[
+    "Cannot delegate write",
+    "Cannot describe in local replica mode",
+];
+
+/// Whether an error message reports a write on a replica without a primary.
+pub fn is_replica_write_error(message: &str) -> bool {
+    REPLICA_WRITE_MARKERS
+        .iter()
+        .any(|marker| message.contains(marker))
+}
+
+/// Build the error returned for a statement that failed with `message`.
+///
+/// Writes rejected because a replica has no primary become `{:error, :replica_read_only}`
+/// so callers can match on them; any other message is returned unchanged.
+pub fn statement_error(message: String) -> rustler::Error {
+    if is_replica_write_error(&message) {
+        rustler::Error::Term(Box::new(crate::constants::replica_read_only()))
+    } else {
+        rustler::Error::Term(Box::new(message))
+    }
+}
+
+/// Whether a query that failed with `error` on `conn` should be retried after reconnecting.
+///
+/// Only connection-lost errors outside a transaction qualify: reconnecting mid-transaction
+/// would silently discard the transaction's earlier writes.
+pub fn should_reconnect(conn: &libsql::Connection, error: &libsql::Error) -> bool {
+    conn.is_autocommit() && is_connection_lost(&error.to_string())
+}
+
+/// Whether `error` is `SQLITE_BUSY` (or one of its extended codes).
+pub fn is_busy_error(error: &libsql::Error) -> bool {
+    matches!(error, libsql::Error::SqliteFailure(code, _) if code & 0xff == 5)
+}
+
+/// Delay in milliseconds before retry number `attempt` (0-based) of a busy statement,
+/// or `None` once `waited_ms` (time since the first attempt started) has used up the
+/// schedule's `max_ms`.
+///
+/// Delays double from `base_ms` and the last one is cut short so the total wait never
+/// exceeds `max_ms`.
+pub fn backoff_delay(backoff: BusyBackoff, attempt: u32, waited_ms: u64) -> Option<u64> {
+    let remaining = backoff.max_ms.saturating_sub(waited_ms);
+    if remaining == 0 {
+        return None;
+    }
+
+    let delay = backoff.base_ms.saturating_mul(2u64.saturating_pow(attempt));
+    Some(delay.min(remaining))
+}
+
+/// Re-run a statement that failed with `SQLITE_BUSY` on the `backoff` schedule.
+///
+/// `first` is the result of the initial attempt, which began at `started`, and `run`
+/// performs each retry. The budget counts wall-clock time since `started`, so time
+/// `SQLite` already spent in its own busy handler is not waited again. Returns the first
+/// result that is not a busy error, or the last busy error once the schedule is spent.
+pub async fn retry_busy<T, F, Fut>(
+    backoff: BusyBackoff,
+    started: Instant,
+    first: Result<T, libsql::Error>,
+    mut run: F,
+) -> Result<T, libsql::Error>
+where
+    F: FnMut() -> Fut,
+    Fut: std::future::Future<Output = Result<T, libsql::Error>>,
+{
+    let mut result = first;
+    let mut attempt = 0;
+
+    while matches!(&result, Err(e) if is_busy_error(e)) {
+        let waited_ms = u64::try_from(started.elapsed().as_millis()).unwrap_or(u64::MAX);
+        let Some(delay) = backoff_delay(backoff, attempt, waited_ms) else {
+            break;
+        };
+
+        tokio::time::sleep(Duration::from_millis(delay)).await;
+        attempt += 1;
+        result = run().await;
+    }
+
+    result
+}
+
+/// Replace a connection's `libsql` connection with a fresh one from its database handle.
+///
+/// The database handle keeps the URL, token and encryption settings from `connect`, so the
+/// new connection talks to the same server. Must be called while the caller holds no lock on
+/// `client` or its inner connection.
+pub fn reconnect(client: &Arc<Mutex<LibSQLConn>>) -> Result<(), rustler::Error> {
+    let client_guard = safe_lock_arc(client, "reconnect client")?;
+    let fresh = client_guard
+        .db
+        .connect()
+        .map_err(|e| rustler::Error::Term(Box::new(format!("Reconnect failed: {e}"))))?;
+    *safe_lock_arc(&client_guard.client, "reconnect conn")? = fresh;
+    Ok(())
+}
+
+/// Encode bytes as standard base64 (RFC 4648, with `=` padding).
+pub fn base64_encode(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+    let mut out = String::with_capacity(bytes.len().div_ceil(3) * 4);
+
+    for chunk in bytes.chunks(3) {
+        let b0 = chunk[0];
+        let b1 = chunk.get(1).copied().unwrap_or(0);
+        let b2 = chunk.get(2).copied().unwrap_or(0);
+
+        out.push(ALPHABET[(b0 >> 2) as usize] as char);
+        out.push(ALPHABET[(((b0 & 0x03) << 4) | (b1 >> 4)) as usize] as char);
+        out.push(if chunk.len() > 1 {
+            ALPHABET[(((b1 & 0x0f) << 2) | (b2 >> 6)) as usize] as char
+        } else {
+            '='
+        });
+        out.push(if chunk.len() > 2 {
+            ALPHABET[(b2 & 0x3f) as usize] as char
+        } else {
+            '='
+        });
+    }
+
+    out
+}
+
+/// Append `s` to `out` as a quoted JSON string.
+fn push_json_string(out: &mut String, s: &str) {
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+/// Serialise one row as a single-line JSON object keyed by column name.
+///
+/// Text becomes a JSON string, integers and finite reals JSON numbers, blobs base64
+/// strings, and NULL (or a non-finite real, which JSON cannot represent) `null`.
+pub fn row_to_json(columns: &[String], values: &[Value]) -> String {
+    let mut out = String::from("{");
+
+    for (i, (column, value)) in columns.iter().zip(values).enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        push_json_string(&mut out, column);
+        out.push(':');
+
+        match value {
+            Value::Null => out.push_str("null"),
+            Value::Integer(n) => out.push_str(&n.to_string()),
+            Value::Real(f) if f.is_finite() => out.push_str(&format!("{f:?}")),
+            Value::Real(_) => out.push_str("null"),
+            Value::Text(text) => push_json_string(&mut out, text),
+            Value::Blob(bytes) => push_json_string(&mut out, &base64_encode(bytes)),
+        }
+    }
+
+    out.push('}');
+    out
+}
+
+/// Encode a single `libsql` value as an Elixir term.
+///
+/// Text becomes a binary, integers and reals numbers, blobs binaries and NULL `nil`.
+pub fn value_to_term<'a>(env: Env<'a>, value: &Value) -> NifResult<Term<'a>> {
+    match value {
+        Value::Text(s) => Ok(s.encode(env)),
+        Value::Integer(i) => Ok(i.encode(env)),
+        Value::Real(f) => Ok(f.encode(env)),
+        Value::Blob(b) => OwnedBinary::new(b.len())
+            .ok_or_else(|| {
+                rustler::Error::Term(Box::new("Failed to allocate binary for blob data"))
+            })
+            .map(|mut owned| {
+                owned.as_mut_slice().copy_from_slice(b);
+                Binary::from_owned(owned, env).encode(env)
+            }),
+        Value::Null => Ok(nil().encode(env)),
+    }
+}
+
+/// Compare two values the way `SQLite` sorts them with the `BINARY` collation.
+///
+/// NULL sorts first, then numbers (integers and reals compared by value), then text,
+/// then blobs.
+pub fn compare_values(a: &Value, b: &Value) -> Ordering {
+    fn storage_class(value: &Value) -> u8 {
+        match value {
+            Value::Null => 0,
+            Value::Integer(_) | Value::Real(_) => 1,
+            Value::Text(_) => 2,
+            Value::Blob(_) => 3,
+        }
+    }
+
+    match (a, b) {
+        (Value::Integer(x), Value::Integer(y)) => x.cmp(y),
+        (Value::Integer(x), Value::Real(y)) => (*x as f64).total_cmp(y),
+        (Value::Real(x), Value::Integer(y)) => x.total_cmp(&(*y as f64)),
+        (Value::Real(x), Value::Real(y)) => x.total_cmp(y),
+        (Value::Text(x), Value::Text(y)) => x.cmp(y),
+        (Value::Blob(x), Value::Blob(y)) => x.cmp(y),
+        _ => storage_class(a).cmp(&storage_class(b)),
+    }
+}
+
+/// Hashable form of a non-NULL value, used to detect repeats for `ReduceOp::Distinct`.
+///
+/// Values are distinct by type as well as content, so `1` and `1.0` are both kept.
+#[derive(Debug, PartialEq, Eq, Hash)]
+enum DistinctKey {
+    Integer(i64),
+    Real(u64),
+    Text(String),
+    Blob(Vec<u8>),
+}
+
+/// Result of running a `ColumnReducer` to completion
+#[derive(Debug, PartialEq)]
+pub enum Reduced {
+    /// The sum, minimum or maximum; `Value::Null` if the column had no non-NULL values
+    Value(Value),
+    /// Distinct values in order of first appearance
+    Distinct(Vec<Value>),
+}
+
+/// Running state of a `ReduceOp` over one column, fed a row at a time.
+///
+/// NULLs are skipped, as in the equivalent SQL aggregates.
+#[derive(Debug)]
+pub struct ColumnReducer {
+    op: ReduceOp,
+    acc: Option<Value>,
+    distinct: Vec<Value>,
+    seen: HashSet<DistinctKey>,
+}
+
+impl ColumnReducer {
+    pub fn new(op: ReduceOp) -> Self {
+        Self {
+            op,
+            acc: None,
+            distinct: Vec::new(),
+            seen: HashSet::new(),
+        }
+    }
+
+    /// Fold one value into the reduction.
+    ///
+    /// Returns an error if `sum` sees text or a blob, or its integer total overflows.
+    pub fn push(&mut self, value: Value) -> Result<(), String> {
+        if value == Value::Null {
+            return Ok(());
+        }
+
+        match self.op {
+            ReduceOp::Sum => {
+                self.acc = Some(match (self.acc.take(), value) {
+                    (None, value @ (Value::Integer(_) | Value::Real(_))) => value,
+                    (Some(Value::Integer(a)), Value::Integer(b)) => Value::Integer(
+                        a.checked_add(b)
+                            .ok_or_else(|| "sum failed: integer overflow".to_string())?,
+                    ),
+                    (Some(Value::Integer(a)), Value::Real(b)) => Value::Real(a as f64 + b),
+                    (Some(Value::Real(a)), Value::Integer(b)) => Value::Real(a + b as f64),
+                    (Some(Value::Real(a)), Value::Real(b)) => Value::Real(a + b),
+                    (_, Value::Text(_)) => {
+                        return Err("sum requires numeric values, got text".into())
                     }
+                    (_, _) => return Err("sum requires numeric values, got a blob".into()),
+                });
+            }
+            ReduceOp::Min | ReduceOp::Max => {
+                let wanted = if self.op == ReduceOp::Min {
+                    Ordering::Less
+                } else {
+                    Ordering::Greater
+                };
+                let replace = self
+                    .acc
+                    .as_ref()
+                    .is_none_or(|best| compare_values(&value, best) == wanted);
+                if replace {
+                    self.acc = Some(value);
+                }
+            }
+            ReduceOp::Distinct => {
+                let key = match &value {
+                    Value::Integer(i) => DistinctKey::Integer(*i),
+                    Value::Real(f) => DistinctKey::Real(f.to_bits()),
+                    Value::Text(s) => DistinctKey::Text(s.clone()),
+                    Value::Blob(b) => DistinctKey::Blob(b.clone()),
+                    Value::Null => return Ok(()),
+                };
+                if self.seen.insert(key) {
+                    self.distinct.push(value);
                 }
             }
-            i += 1;
+        }
+
+        Ok(())
+    }
+
+    /// Finish the reduction.
+    pub fn finish(self) -> Reduced {
+        match self.op {
+            ReduceOp::Distinct => Reduced::Distinct(self.distinct),
+            _ => Reduced::Value(self.acc.unwrap_or(Value::Null)),
         }
     }
+}
 
-    false
+/// Convert the codepoints of a `{:charlist, list}` parameter to text.
+///
+/// A bare list of integers is ambiguous (`'hello'` and `[104, 101, 108, 108, 111]`
+/// are the same term) and keeps binding as a byte BLOB. Callers opt in to text by
+/// tagging the list, and the tagged list must be valid Unicode codepoints. Control
+/// characters other than tabs and newlines are rejected, mirroring
+/// `:io_lib.printable_unicode_list/1`, so a mistagged byte list fails loudly.
+pub fn charlist_to_text(codepoints: &[u32]) -> Option<String> {
+    codepoints
+        .iter()
+        .map(|&cp| {
+            char::from_u32(cp).filter(|c| !c.is_control() || matches!(c, '\t' | '\n' | '\r'))
+        })
+        .collect()
 }
 
 /// Decode an Elixir term to a LibSQL Value
 ///
-/// Supports integers, floats, booleans, strings, blobs, nil/null, and binary data.
+/// Supports integers, floats, booleans, strings, tagged charlists, blobs, nil/null, and
+/// binary data. Bare integer lists bind as a BLOB of bytes.
 pub fn decode_term_to_value(term: Term) -> Result<Value, String> {
-    use crate::constants::{blob, nil};
+    use crate::constants::{blob, charlist, nil};
 
     // Check for nil atom first (represents NULL in SQL)
     if let Ok(atom) = term.decode::<rustler::Atom>() {
@@ -497,6 +1472,16 @@ pub fn decode_term_to_value(term: Term) -> Result<Value, String> {
         Ok(Value::Integer(if v { 1 } else { 0 }))
     } else if let Ok(v) = term.decode::<String>() {
         Ok(Value::Text(v))
+    } else if let Some(codepoints) = term
+        .decode::<(rustler::Atom, Vec<u32>)>()
+        .ok()
+        .filter(|(atom, _)| *atom == charlist())
+        .map(|(_, codepoints)| codepoints)
+    {
+        // {:charlist, 'hello'} opts a charlist in to binding as text
+        charlist_to_text(&codepoints)
+            .map(Value::Text)
+            .ok_or_else(|| format!("Invalid charlist: {codepoints:?}"))
     } else if let Ok((atom, data)) = term.decode::<(rustler::Atom, Vec<u8>)>() {
         // Handle {:blob, data} tuple from Ecto binary dumper
         if atom == blob() {
@@ -81,6 +81,48 @@ defmodule EctoLibSql.AdvancedFeaturesTest do
       result = EctoLibSql.Native.get_max_write_frame("invalid-connection-id")
       assert {:error, _reason} = result
     end
+
+    test "replica_metrics are zero for a local connection" do
+      {:ok, state} = EctoLibSql.connect(database: ":memory:")
+
+      # Local connections never sync, so nothing is recorded
+      assert {:ok, metrics} = EctoLibSql.Native.get_replica_metrics(state)
+
+      assert metrics == %{
+               total_syncs: 0,
+               failed_syncs: 0,
+               total_frames_applied: 0,
+               last_sync_at: nil,
+               last_error: nil
+             }
+
+      EctoLibSql.disconnect([], state)
+    end
+
+    test "replica_metrics returns error for invalid connection" do
+      assert {:error, _reason} = EctoLibSql.Native.get_replica_metrics("invalid-connection-id")
+    end
+
+    test "wait_for_frame returns immediately for a reached frame" do
+      {:ok, state} = EctoLibSql.connect(database: ":memory:")
+
+      assert :ok = EctoLibSql.Native.wait_for_frame(state, 0, 100)
+
+      EctoLibSql.disconnect([], state)
+    end
+
+    test "wait_for_frame times out when the frame is never applied" do
+      {:ok, state} = EctoLibSql.connect(database: ":memory:")
+
+      # Local connections stay at frame 0
+      assert {:error, :wait_timeout} = EctoLibSql.Native.wait_for_frame(state, 1, 100)
+
+      EctoLibSql.disconnect([], state)
+    end
+
+    test "wait_for_frame returns error for invalid connection" do
+      assert {:error, _reason} = EctoLibSql.Native.wait_for_frame("invalid-connection-id", 1, 10)
+    end
   end
 
   # ============================================================================
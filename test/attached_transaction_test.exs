@@ -0,0 +1,100 @@
+defmodule EctoLibSql.AttachedTransactionTest do
+  @moduledoc """
+  Transactions spanning the main database and an `ATTACH`ed one.
+
+  SQLite runs attached databases on the same connection, so a single
+  transaction covers all of them: COMMIT and ROLLBACK apply to every file.
+  """
+  use ExUnit.Case
+
+  setup do
+    suffix = :erlang.unique_integer([:positive])
+    main_db = "z_ecto_libsql_test-attached_main_#{suffix}.db"
+    archive_db = "z_ecto_libsql_test-attached_archive_#{suffix}.db"
+
+    {:ok, state} = EctoLibSql.connect(database: main_db)
+
+    {:ok, _query, _result, state} =
+      EctoLibSql.handle_execute("ATTACH DATABASE '#{archive_db}' AS archive", [], [], state)
+
+    {:ok, _results} =
+      EctoLibSql.Native.execute_batch_sql(state, """
+      CREATE TABLE orders (id INTEGER PRIMARY KEY, total INTEGER);
+      CREATE TABLE archive.order_log (id INTEGER PRIMARY KEY, order_id INTEGER);
+      """)
+
+    on_exit(fn ->
+      EctoLibSql.disconnect([], state)
+      EctoLibSql.TestHelpers.cleanup_db_files(main_db)
+      EctoLibSql.TestHelpers.cleanup_db_files(archive_db)
+    end)
+
+    {:ok, state: state}
+  end
+
+  defp write_both(state) do
+    {:ok, _query, _result, state} =
+      EctoLibSql.handle_execute("INSERT INTO orders (id, total) VALUES (1, 100)", [], [], state)
+
+    {:ok, _query, _result, state} =
+      EctoLibSql.handle_execute(
+        "INSERT INTO archive.order_log (order_id) VALUES (1)",
+        [],
+        [],
+        state
+      )
+
+    state
+  end
+
+  defp counts(state) do
+    {:ok, orders} = EctoLibSql.Native.query_scalar(state, "SELECT count(*) FROM orders")
+
+    {:ok, log} =
+      EctoLibSql.Native.query_scalar(state, "SELECT count(*) FROM archive.order_log")
+
+    {orders, log}
+  end
+
+  test "rollback discards writes to the main and attached databases", %{state: state} do
+    {:ok, :begin, trx_state} = EctoLibSql.handle_begin([], state)
+    trx_state = write_both(trx_state)
+
+    # Both writes are visible inside the transaction
+    assert {1, 1} = counts(trx_state)
+
+    {:ok, _, _state} = EctoLibSql.handle_rollback([], trx_state)
+
+    assert {0, 0} = counts(state)
+  end
+
+  test "commit keeps writes to the main and attached databases", %{state: state} do
+    {:ok, :begin, trx_state} = EctoLibSql.handle_begin([], state)
+    trx_state = write_both(trx_state)
+    {:ok, _, _state} = EctoLibSql.handle_commit([], trx_state)
+
+    assert {1, 1} = counts(state)
+  end
+
+  test "rollback still spans both databases in WAL mode", %{state: state} do
+    {:ok, _} = EctoLibSql.Pragma.query(state, "PRAGMA journal_mode = WAL")
+    {:ok, _} = EctoLibSql.Pragma.query(state, "PRAGMA archive.journal_mode = WAL")
+
+    {:ok, :begin, trx_state} = EctoLibSql.handle_begin([], state)
+    trx_state = write_both(trx_state)
+    {:ok, _, _state} = EctoLibSql.handle_rollback([], trx_state)
+
+    assert {0, 0} = counts(state)
+  end
+
+  test "ATTACH is rejected inside a transaction", %{state: state} do
+    {:ok, :begin, trx_state} = EctoLibSql.handle_begin([], state)
+
+    assert {:error, %EctoLibSql.Error{message: message}, trx_state} =
+             EctoLibSql.handle_execute("ATTACH DATABASE ':memory:' AS scratch", [], [], trx_state)
+
+    assert message =~ "cannot ATTACH database within transaction"
+
+    {:ok, _, _state} = EctoLibSql.handle_rollback([], trx_state)
+  end
+end
@@ -250,4 +250,693 @@ defmodule EctoLibSql.BatchFeaturesTest do
       EctoLibSql.disconnect([], state)
     end
   end
+
+  defp table_names(state) do
+    {:ok, _query, result, _state} =
+      EctoLibSql.handle_execute(
+        "SELECT name FROM sqlite_master WHERE type = 'table' ORDER BY name",
+        [],
+        [],
+        state
+      )
+
+    List.flatten(result.rows)
+  end
+
+  defp insert_event_id(state) do
+    EctoLibSql.Native.query_scalar(state, "INSERT INTO events (name) VALUES ('x') RETURNING id")
+  end
+
+  describe "drop_tables" do
+    test "drops existing tables and skips missing ones with if_exists", %{database: database} do
+      {:ok, state} = EctoLibSql.connect(database: database)
+
+      {:ok, _results} =
+        EctoLibSql.Native.execute_batch_sql(state, """
+        CREATE TABLE users (id INTEGER PRIMARY KEY);
+        CREATE TABLE posts (id INTEGER PRIMARY KEY);
+        CREATE TABLE "order items" (id INTEGER PRIMARY KEY);
+        CREATE TABLE keep_me (id INTEGER PRIMARY KEY);
+        """)
+
+      assert {:ok, 3} =
+               EctoLibSql.Native.drop_tables(
+                 state,
+                 ["users", :posts, "order items", "never_created", "also_missing"],
+                 true
+               )
+
+      assert table_names(state) == ["keep_me"]
+
+      EctoLibSql.disconnect([], state)
+    end
+
+    test "if_exists defaults to true", %{database: database} do
+      {:ok, state} = EctoLibSql.connect(database: database)
+
+      {:ok, _query, _result, state} =
+        EctoLibSql.handle_execute("CREATE TABLE t1 (id INTEGER)", [], [], state)
+
+      assert {:ok, 1} = EctoLibSql.Native.drop_tables(state, ["t1", "missing"])
+      assert {:ok, 0} = EctoLibSql.Native.drop_tables(state, ["t1"])
+
+      EctoLibSql.disconnect([], state)
+    end
+
+    test "without if_exists a missing table rolls back every drop", %{database: database} do
+      {:ok, state} = EctoLibSql.connect(database: database)
+
+      {:ok, _query, _result, state} =
+        EctoLibSql.handle_execute("CREATE TABLE t1 (id INTEGER)", [], [], state)
+
+      assert {:error, reason} = EctoLibSql.Native.drop_tables(state, ["t1", "missing"], false)
+      assert reason =~ "missing"
+
+      # t1 was dropped inside the transaction, then restored by the rollback
+      assert table_names(state) == ["t1"]
+
+      EctoLibSql.disconnect([], state)
+    end
+
+    test "quotes names so they cannot inject SQL", %{database: database} do
+      {:ok, state} = EctoLibSql.connect(database: database)
+
+      {:ok, _query, _result, state} =
+        EctoLibSql.handle_execute("CREATE TABLE victims (id INTEGER)", [], [], state)
+
+      assert {:ok, 0} = EctoLibSql.Native.drop_tables(state, ["x\"; DROP TABLE victims; --"])
+      assert table_names(state) == ["victims"]
+
+      EctoLibSql.disconnect([], state)
+    end
+  end
+
+  describe "truncate" do
+    test "deletes every row from a table", %{database: database} do
+      {:ok, state} = EctoLibSql.connect(database: database)
+
+      {:ok, _results} =
+        EctoLibSql.Native.execute_batch_sql(state, """
+        CREATE TABLE cache (key TEXT PRIMARY KEY, value TEXT);
+        INSERT INTO cache VALUES ('a', '1'), ('b', '2'), ('c', '3');
+        """)
+
+      assert {:ok, 3} = EctoLibSql.Native.truncate(state, "cache")
+      assert {:ok, 0} = EctoLibSql.Native.count(state, "cache")
+
+      EctoLibSql.disconnect([], state)
+    end
+
+    test "reset_sequence restarts AUTOINCREMENT ids", %{database: database} do
+      {:ok, state} = EctoLibSql.connect(database: database)
+
+      {:ok, _results} =
+        EctoLibSql.Native.execute_batch_sql(state, """
+        CREATE TABLE events (id INTEGER PRIMARY KEY AUTOINCREMENT, name TEXT);
+        INSERT INTO events (name) VALUES ('a'), ('b');
+        """)
+
+      # Without a reset, AUTOINCREMENT never reuses ids
+      assert {:ok, 2} = EctoLibSql.Native.truncate(state, :events)
+      assert {:ok, 3} = insert_event_id(state)
+
+      assert {:ok, 1} = EctoLibSql.Native.truncate(state, :events, true)
+      assert {:ok, 1} = insert_event_id(state)
+
+      EctoLibSql.disconnect([], state)
+    end
+
+    test "reset_sequence is harmless for tables without AUTOINCREMENT", %{database: database} do
+      {:ok, state} = EctoLibSql.connect(database: database)
+
+      {:ok, _results} =
+        EctoLibSql.Native.execute_batch_sql(state, """
+        CREATE TABLE plain (id INTEGER PRIMARY KEY);
+        INSERT INTO plain VALUES (1), (2);
+        """)
+
+      assert {:ok, 2} = EctoLibSql.Native.truncate(state, "plain", true)
+
+      EctoLibSql.disconnect([], state)
+    end
+
+    test "missing tables return an error", %{database: database} do
+      {:ok, state} = EctoLibSql.connect(database: database)
+
+      assert {:error, reason} = EctoLibSql.Native.truncate(state, "missing")
+      assert reason =~ "missing"
+
+      EctoLibSql.disconnect([], state)
+    end
+  end
+
+  describe "reset_sequence" do
+    test "next AUTOINCREMENT id continues from the reset value", %{database: database} do
+      {:ok, state} = EctoLibSql.connect(database: database)
+
+      {:ok, _results} =
+        EctoLibSql.Native.execute_batch_sql(state, """
+        CREATE TABLE events (id INTEGER PRIMARY KEY AUTOINCREMENT, name TEXT);
+        INSERT INTO events (name) VALUES ('a'), ('b'), ('c');
+        DELETE FROM events;
+        """)
+
+      assert :ok = EctoLibSql.Native.reset_sequence(state, "events")
+      assert {:ok, 1} = insert_event_id(state)
+
+      assert :ok = EctoLibSql.Native.reset_sequence(state, :events, 100)
+      assert {:ok, 101} = insert_event_id(state)
+
+      EctoLibSql.disconnect([], state)
+    end
+
+    test "works before the first insert", %{database: database} do
+      {:ok, state} = EctoLibSql.connect(database: database)
+
+      {:ok, _results} =
+        EctoLibSql.Native.execute_batch_sql(state, """
+        CREATE TABLE events (id INTEGER PRIMARY KEY AUTOINCREMENT, name TEXT);
+        """)
+
+      assert :ok = EctoLibSql.Native.reset_sequence(state, "events", 41)
+      assert {:ok, 42} = insert_event_id(state)
+
+      EctoLibSql.disconnect([], state)
+    end
+
+    test "rejects tables without AUTOINCREMENT and missing tables", %{database: database} do
+      {:ok, state} = EctoLibSql.connect(database: database)
+
+      {:ok, _results} =
+        EctoLibSql.Native.execute_batch_sql(state, """
+        CREATE TABLE plain (id INTEGER PRIMARY KEY);
+        """)
+
+      assert {:error, message} = EctoLibSql.Native.reset_sequence(state, "plain")
+      assert message =~ "does not use AUTOINCREMENT"
+
+      assert {:error, message} = EctoLibSql.Native.reset_sequence(state, "missing")
+      assert message =~ "Table not found"
+
+      EctoLibSql.disconnect([], state)
+    end
+  end
+
+  describe "reindex" do
+    test "rebuilds targeted and all indexes", %{database: database} do
+      {:ok, state} = EctoLibSql.connect(database: database)
+
+      {:ok, _results} =
+        EctoLibSql.Native.execute_batch_sql(state, """
+        CREATE TABLE users (id INTEGER PRIMARY KEY, email TEXT);
+        CREATE INDEX idx_users_email ON users (email);
+        INSERT INTO users (email) VALUES ('a@example.com'), ('b@example.com');
+        """)
+
+      assert :ok = EctoLibSql.Native.reindex(state, "idx_users_email")
+      assert :ok = EctoLibSql.Native.reindex(state, :users)
+      assert :ok = EctoLibSql.Native.reindex(state)
+
+      assert {:ok, 2} =
+               EctoLibSql.Native.query_scalar(
+                 state,
+                 "SELECT id FROM users INDEXED BY idx_users_email WHERE email = ?",
+                 ["b@example.com"]
+               )
+
+      EctoLibSql.disconnect([], state)
+    end
+
+    test "quotes the target and rejects unknown names", %{database: database} do
+      {:ok, state} = EctoLibSql.connect(database: database)
+
+      {:ok, _results} =
+        EctoLibSql.Native.execute_batch_sql(state, """
+        CREATE TABLE users (id INTEGER PRIMARY KEY, email TEXT);
+        """)
+
+      assert {:error, message} = EctoLibSql.Native.reindex(state, "users; DROP TABLE users")
+      assert message =~ "Reindex failed"
+      assert {:ok, true} = EctoLibSql.Native.table_exists(state, "users")
+
+      assert {:error, "Reindex target cannot be empty"} = EctoLibSql.Native.reindex(state, "")
+
+      EctoLibSql.disconnect([], state)
+    end
+  end
+
+  describe "copy_into" do
+    setup %{database: database} do
+      {:ok, state} = EctoLibSql.connect(database: database)
+
+      {:ok, _results} =
+        EctoLibSql.Native.execute_batch_sql(state, """
+        CREATE TABLE source (id INTEGER PRIMARY KEY, value INTEGER);
+        CREATE TABLE dest (id INTEGER PRIMARY KEY, value INTEGER);
+        WITH RECURSIVE n(i) AS (SELECT 1 UNION ALL SELECT i + 1 FROM n WHERE i < 10000)
+        INSERT INTO source SELECT i, i * 2 FROM n;
+        """)
+
+      on_exit(fn -> EctoLibSql.disconnect([], state) end)
+
+      {:ok, state: state}
+    end
+
+    test "copies 10,000 rows in batches", %{state: state} do
+      assert {:ok, 10_000} =
+               EctoLibSql.Native.copy_into(
+                 state,
+                 "dest",
+                 "SELECT id, value FROM source ORDER BY id",
+                 [],
+                 1_000
+               )
+
+      assert {:ok, 10_000} = EctoLibSql.Native.count(state, "dest")
+      assert {:ok, 20_000} = EctoLibSql.Native.query_scalar(state, "SELECT max(value) FROM dest")
+    end
+
+    test "copies in a single statement with parameters", %{state: state} do
+      assert {:ok, 10} =
+               EctoLibSql.Native.copy_into(
+                 state,
+                 :dest,
+                 "SELECT id, value FROM source WHERE id <= ?",
+                 [10]
+               )
+
+      assert {:ok, 10} = EctoLibSql.Native.count(state, "dest")
+    end
+
+    test "handles a final partial batch", %{state: state} do
+      assert {:ok, 25} =
+               EctoLibSql.Native.copy_into(
+                 state,
+                 "dest",
+                 "SELECT id, value FROM source WHERE id <= ? ORDER BY id;",
+                 [25],
+                 10
+               )
+
+      assert {:ok, 25} = EctoLibSql.Native.count(state, "dest")
+    end
+
+    test "copies in keyset batches", %{state: state} do
+      assert {:ok, 10_000} =
+               EctoLibSql.Native.copy_into(
+                 state,
+                 "dest",
+                 "SELECT id, value FROM source",
+                 [],
+                 999,
+                 key: :id
+               )
+
+      assert {:ok, 10_000} = EctoLibSql.Native.count(state, "dest")
+      assert {:ok, 20_000} = EctoLibSql.Native.query_scalar(state, "SELECT max(value) FROM dest")
+    end
+
+    test "keyset batches keep rows that share a key together", %{state: state} do
+      create_sql = "CREATE TABLE grouped (id INTEGER, value INTEGER)"
+      {:ok, _query, _result, state} = EctoLibSql.handle_execute(create_sql, [], [], state)
+
+      # Every key appears twice, so a batch of 3 rows must stretch to cover both of a pair
+      assert {:ok, 20} =
+               EctoLibSql.Native.copy_into(
+                 state,
+                 "grouped",
+                 "SELECT (id + 1) / 2 AS bucket, value FROM source WHERE id <= ?",
+                 [20],
+                 3,
+                 key: "bucket"
+               )
+
+      {:ok, _query, result, _state} =
+        EctoLibSql.handle_execute(
+          "SELECT count(DISTINCT value), count(DISTINCT id) FROM grouped",
+          [],
+          [],
+          state
+        )
+
+      assert result.rows == [[20, 10]]
+    end
+
+    test "keyset batches skip leading NULL keys", %{state: state} do
+      create_sql = "CREATE TABLE sparse (k INTEGER, value INTEGER)"
+      {:ok, _query, _result, state} = EctoLibSql.handle_execute(create_sql, [], [], state)
+
+      # The first 5 rows have NULL keys, more than one batch of 3
+      select_sql =
+        "SELECT CASE WHEN id <= 5 THEN NULL ELSE id END AS k, value FROM source WHERE id <= ?"
+
+      assert {:ok, 15} =
+               EctoLibSql.Native.copy_into(state, "sparse", select_sql, [20], 3, key: "k")
+
+      assert {:ok, 15} = EctoLibSql.Native.count(state, "sparse")
+    end
+
+    test "batched copies are rejected inside a transaction", %{state: state} do
+      {:ok, trx_state} = EctoLibSql.Native.begin(state)
+
+      assert {:error, "copy_into with a batch_size cannot run inside a transaction"} =
+               EctoLibSql.Native.copy_into(trx_state, "dest", "SELECT * FROM source", [], 10)
+
+      assert {:ok, 10_000} =
+               EctoLibSql.Native.copy_into(trx_state, "dest", "SELECT * FROM source")
+
+      {:ok, _state} = EctoLibSql.Native.rollback(trx_state)
+    end
+
+    test "key requires a batch size", %{state: state} do
+      assert {:error, "key requires a batch_size"} =
+               EctoLibSql.Native.copy_into(
+                 state,
+                 "dest",
+                 "SELECT * FROM source",
+                 [],
+                 nil,
+                 key: :id
+               )
+    end
+  end
+
+  describe "query_pipeline" do
+    setup %{database: database} do
+      {:ok, state} = EctoLibSql.connect(database: database)
+
+      {:ok, _results} =
+        EctoLibSql.Native.execute_batch_sql(state, """
+        CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT);
+        INSERT INTO users (id, name) VALUES (1, 'Alice'), (2, 'Bob'), (3, 'Carol');
+        """)
+
+      on_exit(fn -> EctoLibSql.disconnect([], state) end)
+
+      {:ok, state: state}
+    end
+
+    test "returns every result set in order", %{state: state} do
+      queries = [
+        {"SELECT count(*) FROM users", []},
+        {"SELECT name FROM users WHERE id = ?", [2]},
+        {"SELECT id FROM users WHERE id > ? ORDER BY id", [1]}
+      ]
+
+      assert {:ok, [count, user, ids]} = EctoLibSql.Native.query_pipeline(state, queries)
+
+      assert %EctoLibSql.Result{command: :select, rows: [[3]], num_rows: 1} = count
+      assert %EctoLibSql.Result{columns: ["name"], rows: [["Bob"]]} = user
+      assert %EctoLibSql.Result{rows: [[2], [3]], num_rows: 2} = ids
+    end
+
+    test "stops at the first failing query and reports its index", %{state: state} do
+      queries = [
+        {"SELECT 1", []},
+        {"SELECT * FROM missing_table", []},
+        {"SELECT 3", []}
+      ]
+
+      assert {:error, {1, reason}} = EctoLibSql.Native.query_pipeline(state, queries)
+      assert reason =~ "no such table"
+    end
+
+    test "continues past failures with continue_on_error", %{state: state} do
+      queries = [
+        {"SELECT 1", []},
+        {"SELECT * FROM missing_table", []},
+        {"SELECT 3", []}
+      ]
+
+      assert {:ok, [%{rows: [[1]]}, {:error, reason}, %{rows: [[3]]}]} =
+               EctoLibSql.Native.query_pipeline(state, queries, continue_on_error: true)
+
+      assert reason =~ "no such table"
+
+      # A query's own option overrides the pipeline default
+      queries = List.replace_at(queries, 1, {"SELECT * FROM missing_table", [], []})
+
+      assert {:error, {1, _reason}} =
+               EctoLibSql.Native.query_pipeline(state, queries, continue_on_error: true)
+    end
+
+    test "rejects malformed queries before running any", %{state: state} do
+      assert {:error, {1, "Invalid pipeline query: :oops"}} =
+               EctoLibSql.Native.query_pipeline(state, [{"SELECT 1", []}, :oops])
+
+      assert {:ok, []} = EctoLibSql.Native.query_pipeline(state, [])
+    end
+  end
+
+  describe "dump_table_ndjson" do
+    setup %{database: database} do
+      {:ok, state} = EctoLibSql.connect(database: database)
+      dest = "z_ecto_libsql_test-dump_#{:erlang.unique_integer([:positive])}.ndjson"
+
+      {:ok, _results} =
+        EctoLibSql.Native.execute_batch_sql(state, """
+        CREATE TABLE dump_items (id INTEGER PRIMARY KEY, name TEXT, price REAL, data BLOB);
+        INSERT INTO dump_items VALUES (1, 'Widget', 9.5, x'010203');
+        INSERT INTO dump_items VALUES (2, 'Say "hi"', NULL, NULL);
+        """)
+
+      on_exit(fn ->
+        EctoLibSql.disconnect([], state)
+        File.rm(dest)
+      end)
+
+      {:ok, state: state, dest: dest}
+    end
+
+    test "writes one JSON object per row", %{state: state, dest: dest} do
+      assert {:ok, 2} = EctoLibSql.Native.dump_table_ndjson(state, "dump_items", dest)
+
+      lines = dest |> File.read!() |> String.split("\n", trim: true)
+      assert length(lines) == 2
+
+      assert [first, second] = Enum.map(lines, &Jason.decode!/1)
+      assert Enum.sort(Map.keys(first)) == ["data", "id", "name", "price"]
+
+      assert first == %{
+               "id" => 1,
+               "name" => "Widget",
+               "price" => 9.5,
+               "data" => Base.encode64(<<1, 2, 3>>)
+             }
+
+      assert second == %{"id" => 2, "name" => ~s(Say "hi"), "price" => nil, "data" => nil}
+    end
+
+    test "writes an empty file for an empty table", %{state: state, dest: dest} do
+      {:ok, _query, _result, state} =
+        EctoLibSql.handle_execute("DELETE FROM dump_items", [], [], state)
+
+      assert {:ok, 0} = EctoLibSql.Native.dump_table_ndjson(state, :dump_items, dest)
+      assert File.read!(dest) == ""
+    end
+
+    test "reports a missing table", %{state: state, dest: dest} do
+      assert {:error, _reason} = EctoLibSql.Native.dump_table_ndjson(state, "missing", dest)
+    end
+  end
+
+  describe "materialize_to_db" do
+    setup %{database: database} do
+      {:ok, state} = EctoLibSql.connect(database: database)
+      dest = "z_ecto_libsql_test-materialize_#{:erlang.unique_integer([:positive])}.db"
+
+      {:ok, _results} =
+        EctoLibSql.Native.execute_batch_sql(state, """
+        CREATE TABLE authors (id INTEGER PRIMARY KEY, name TEXT);
+        CREATE TABLE books (id INTEGER PRIMARY KEY, author_id INTEGER, title TEXT, price REAL);
+        INSERT INTO authors VALUES (1, 'Le Guin'), (2, 'Butler');
+        INSERT INTO books VALUES (1, 1, 'The Dispossessed', NULL), (2, 2, 'Kindred', 12.5);
+        INSERT INTO books VALUES (3, 1, 'Lathe of Heaven', 9.0);
+        """)
+
+      on_exit(fn ->
+        EctoLibSql.disconnect([], state)
+        EctoLibSql.TestHelpers.cleanup_db_files(dest)
+      end)
+
+      {:ok, state: state, dest: dest}
+    end
+
+    test "writes a join result to a new database", %{state: state, dest: dest} do
+      sql = """
+      SELECT b.id, b.title, a.name AS author, b.price
+      FROM books b JOIN authors a ON a.id = b.author_id
+      WHERE b.id >= ?
+      ORDER BY b.id
+      """
+
+      assert {:ok, 3} =
+               EctoLibSql.Native.materialize_to_db(state, sql, [1], dest, "book_snapshot")
+
+      {:ok, snapshot} = EctoLibSql.connect(database: dest)
+
+      {:ok, _query, result, snapshot} =
+        EctoLibSql.handle_execute("SELECT * FROM book_snapshot ORDER BY id", [], [], snapshot)
+
+      assert result.columns == ["id", "title", "author", "price"]
+
+      assert result.rows == [
+               [1, "The Dispossessed", "Le Guin", nil],
+               [2, "Kindred", "Butler", 12.5],
+               [3, "Lathe of Heaven", "Le Guin", 9.0]
+             ]
+
+      # The first non-NULL value decides each column's type
+      {:ok, _query, schema, snapshot} =
+        EctoLibSql.handle_execute(
+          "SELECT name, type FROM pragma_table_info('book_snapshot') ORDER BY cid",
+          [],
+          [],
+          snapshot
+        )
+
+      assert schema.rows == [
+               ["id", "INTEGER"],
+               ["title", "TEXT"],
+               ["author", "TEXT"],
+               ["price", "REAL"]
+             ]
+
+      EctoLibSql.disconnect([], snapshot)
+    end
+
+    test "creates an empty table for an empty result", %{state: state, dest: dest} do
+      assert {:ok, 0} =
+               EctoLibSql.Native.materialize_to_db(
+                 state,
+                 "SELECT id, title FROM books WHERE price > 100",
+                 [],
+                 dest,
+                 :expensive_books
+               )
+
+      {:ok, snapshot} = EctoLibSql.connect(database: dest)
+
+      {:ok, _query, result, snapshot} =
+        EctoLibSql.handle_execute("SELECT * FROM expensive_books", [], [], snapshot)
+
+      assert result.columns == ["id", "title"]
+      assert result.rows == []
+
+      EctoLibSql.disconnect([], snapshot)
+    end
+
+    test "refuses to overwrite an existing file", %{state: state, dest: dest} do
+      File.write!(dest, "keep me")
+
+      assert {:error, "Destination already exists" <> _} =
+               EctoLibSql.Native.materialize_to_db(state, "SELECT 1 AS one", [], dest, "t")
+
+      assert File.read!(dest) == "keep me"
+    end
+
+    test "removes the file when the snapshot cannot be written", %{state: state, dest: dest} do
+      # Both columns are named "id", which cannot be a table definition
+      sql = "SELECT b.id, a.id FROM books b JOIN authors a ON a.id = b.author_id"
+
+      assert {:error, _reason} = EctoLibSql.Native.materialize_to_db(state, sql, [], dest, "t")
+      refute File.exists?(dest)
+    end
+  end
+
+  describe "insert_blob_from_file" do
+    setup %{database: database} do
+      {:ok, state} = EctoLibSql.connect(database: database)
+      path = "z_ecto_libsql_test-blob_#{:erlang.unique_integer([:positive])}.bin"
+
+      {:ok, _results} =
+        EctoLibSql.Native.execute_batch_sql(state, """
+        CREATE TABLE attachments (id INTEGER PRIMARY KEY, name TEXT, data BLOB);
+        """)
+
+      on_exit(fn ->
+        EctoLibSql.disconnect([], state)
+        File.rm(path)
+      end)
+
+      {:ok, state: state, path: path}
+    end
+
+    test "inserts a 10MB file and reads it back intact", %{state: state, path: path} do
+      contents = :crypto.strong_rand_bytes(10 * 1024 * 1024)
+      File.write!(path, contents)
+
+      assert {:ok, 1} =
+               EctoLibSql.Native.insert_blob_from_file(
+                 state,
+                 "INSERT INTO attachments (name, data) VALUES (?, ?)",
+                 ["big.bin", :file],
+                 path
+               )
+
+      assert {:ok, ["big.bin", stored]} =
+               EctoLibSql.Native.query_one(state, "SELECT name, data FROM attachments")
+
+      assert byte_size(stored) == byte_size(contents)
+      assert :crypto.hash(:sha256, stored) == :crypto.hash(:sha256, contents)
+    end
+
+    test "rejects a missing file", %{state: state, path: path} do
+      assert {:error, message} =
+               EctoLibSql.Native.insert_blob_from_file(
+                 state,
+                 "INSERT INTO attachments (data) VALUES (?)",
+                 [:file],
+                 path
+               )
+
+      assert message =~ "Cannot read"
+      assert {:ok, 0} = EctoLibSql.Native.count(state, "attachments")
+    end
+
+    test "rejects files over max_bytes", %{state: state, path: path} do
+      File.write!(path, "data")
+
+      assert {:error, message} =
+               EctoLibSql.Native.insert_blob_from_file(
+                 state,
+                 "INSERT INTO attachments (data) VALUES (?)",
+                 [:file],
+                 path,
+                 max_bytes: 3
+               )
+
+      assert message =~ "over the 3 byte limit"
+      assert {:ok, 0} = EctoLibSql.Native.count(state, "attachments")
+
+      assert {:ok, 1} =
+               EctoLibSql.Native.insert_blob_from_file(
+                 state,
+                 "INSERT INTO attachments (data) VALUES (?)",
+                 [:file],
+                 path,
+                 max_bytes: 4
+               )
+    end
+
+    test "requires exactly one :file placeholder", %{state: state, path: path} do
+      File.write!(path, "data")
+
+      assert {:error, "params must contain exactly one :file placeholder"} =
+               EctoLibSql.Native.insert_blob_from_file(
+                 state,
+                 "INSERT INTO attachments (name) VALUES (?)",
+                 ["no placeholder"],
+                 path
+               )
+
+      assert {:error, "params must contain exactly one :file placeholder"} =
+               EctoLibSql.Native.insert_blob_from_file(
+                 state,
+                 "INSERT INTO attachments (name, data) VALUES (?, ?)",
+                 [:file, :file],
+                 path
+               )
+    end
+  end
 end
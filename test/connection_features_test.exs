@@ -1,6 +1,7 @@
 defmodule EctoLibSql.ConnectionFeaturesTest do
   @moduledoc """
-  Tests for connection-level features including busy_timeout, reset, and interrupt.
+  Tests for connection-level features including busy_timeout, busy handler policies, reset,
+  interrupt, secure_delete, threads, optimize, empty_string_as_null, and SQLite memory statistics.
 
   These features control connection behaviour and lifecycle management.
   Tests marked with @tag :skip are for features not yet implemented.
@@ -75,6 +76,143 @@ defmodule EctoLibSql.ConnectionFeaturesTest do
     end
   end
 
+  # ============================================================================
+  # Busy handler policies - IMPLEMENTED ✅
+  # ============================================================================
+
+  # Opens a connection that holds the write lock until `release/1` is called on it
+  defp hold_write_lock(database) do
+    {:ok, holder} = EctoLibSql.connect(database: database)
+
+    {:ok, _query, _result, holder} =
+      EctoLibSql.handle_execute("CREATE TABLE IF NOT EXISTS jobs (id INTEGER)", [], [], holder)
+
+    {:ok, trx_state} = EctoLibSql.Native.begin(holder, behavior: :immediate)
+    trx_state
+  end
+
+  defp release(trx_state) do
+    {:ok, _} = EctoLibSql.Native.commit(trx_state)
+    EctoLibSql.disconnect([], trx_state)
+  end
+
+  defp timed_insert(state) do
+    :timer.tc(
+      fn -> EctoLibSql.handle_execute("INSERT INTO jobs (id) VALUES (1)", [], [], state) end,
+      :millisecond
+    )
+  end
+
+  describe "busy handler" do
+    setup %{database: database} do
+      holder = hold_write_lock(database)
+      {:ok, state} = EctoLibSql.connect(database: database)
+
+      on_exit(fn -> EctoLibSql.disconnect([], state) end)
+
+      {:ok, holder: holder, state: state}
+    end
+
+    test ":fail_fast returns at once", %{holder: holder, state: state} do
+      assert :ok = EctoLibSql.Native.set_busy_handler(state, :fail_fast)
+
+      {elapsed, result} = timed_insert(state)
+
+      assert {:error, %EctoLibSql.Error{message: message}, _state} = result
+      assert message =~ "database is locked"
+      assert elapsed < 200
+
+      release(holder)
+    end
+
+    test "{:timeout, ms} waits for the timeout", %{holder: holder, state: state} do
+      assert :ok = EctoLibSql.Native.set_busy_handler(state, {:timeout, 300})
+
+      {elapsed, result} = timed_insert(state)
+
+      assert {:error, %EctoLibSql.Error{message: message}, _state} = result
+      assert message =~ "database is locked"
+      assert elapsed >= 250
+
+      release(holder)
+    end
+
+    test "{:backoff, base, max} gives up after max", %{holder: holder, state: state} do
+      assert :ok = EctoLibSql.Native.set_busy_handler(state, {:backoff, 20, 300})
+
+      {elapsed, result} = timed_insert(state)
+
+      assert {:error, %EctoLibSql.Error{message: message}, _state} = result
+      assert message =~ "database is locked"
+      assert elapsed >= 300
+
+      release(holder)
+    end
+
+    test "{:backoff, base, max} succeeds once the lock is released", %{
+      holder: holder,
+      state: state
+    } do
+      assert :ok = EctoLibSql.Native.set_busy_handler(state, {:backoff, 10, 5_000})
+
+      releaser =
+        Task.async(fn ->
+          Process.sleep(150)
+          release(holder)
+        end)
+
+      {elapsed, result} = timed_insert(state)
+
+      assert {:ok, _query, %EctoLibSql.Result{num_rows: 1}, _state} = result
+      assert elapsed >= 100
+
+      Task.await(releaser)
+    end
+
+    test "{:backoff, base, max} waits inside a raw transaction", %{
+      holder: holder,
+      state: state
+    } do
+      assert :ok = EctoLibSql.Native.set_busy_handler(state, {:backoff, 10, 300})
+      {:ok, _query, _result, state} = EctoLibSql.handle_execute("BEGIN", [], [], state)
+
+      {elapsed, result} = timed_insert(state)
+
+      assert {:error, %EctoLibSql.Error{message: message}, state} = result
+      assert message =~ "database is locked"
+      assert elapsed >= 250
+
+      {:ok, _query, _result, _state} = EctoLibSql.handle_execute("ROLLBACK", [], [], state)
+      release(holder)
+    end
+
+    test "{:backoff, base, max} waits on paths without statement retries", %{
+      holder: holder,
+      state: state
+    } do
+      assert :ok = EctoLibSql.Native.set_busy_handler(state, {:backoff, 10, 300})
+
+      sql = "INSERT INTO jobs (id) VALUES (1)"
+
+      {elapsed, result} =
+        :timer.tc(fn -> EctoLibSql.Native.execute_batch_sql(state, sql) end, :millisecond)
+
+      assert {:error, _reason} = result
+      assert elapsed >= 250
+
+      release(holder)
+    end
+
+    test "rejects invalid policies", %{holder: holder, state: state} do
+      for policy <- [:sometimes, {:backoff, 0, 100}, {:timeout, -1}, {:backoff, 10}] do
+        assert {:error, message} = EctoLibSql.Native.set_busy_handler(state, policy)
+        assert message =~ "Invalid busy policy"
+      end
+
+      release(holder)
+    end
+  end
+
   # ============================================================================
   # Connection reset - IMPLEMENTED ✅
   # ============================================================================
@@ -442,6 +580,545 @@ defmodule EctoLibSql.ConnectionFeaturesTest do
     end
   end
 
+  # ============================================================================
+  # default_transaction_mode - IMPLEMENTED ✅
+  # ============================================================================
+
+  defp insert_account(state) do
+    EctoLibSql.handle_execute("INSERT INTO accounts (balance) VALUES (100)", [], [], state)
+  end
+
+  describe "default_transaction_mode" do
+    setup %{database: database} do
+      {:ok, setup_state} = EctoLibSql.connect(database: database)
+
+      {:ok, _query, _result, _state} =
+        EctoLibSql.handle_execute(
+          "CREATE TABLE accounts (id INTEGER PRIMARY KEY, balance INTEGER)",
+          [],
+          [],
+          setup_state
+        )
+
+      EctoLibSql.disconnect([], setup_state)
+      :ok
+    end
+
+    test "immediate default makes transactions without a mode block writers", %{
+      database: database
+    } do
+      {:ok, state} = EctoLibSql.connect(database: database, default_transaction_mode: :immediate)
+      {:ok, writer} = EctoLibSql.connect(database: database, busy_timeout: 0)
+
+      # No :behavior given, so the connection default (IMMEDIATE) applies and
+      # the write lock is taken at BEGIN, before any statement runs.
+      {:ok, trx_state} = EctoLibSql.Native.begin(state)
+
+      assert {:error, %EctoLibSql.Error{message: message}, _state} = insert_account(writer)
+      assert message =~ "locked"
+
+      {:ok, _} = EctoLibSql.Native.rollback(trx_state)
+
+      # Once the transaction ends the writer can proceed.
+      assert {:ok, _query, _result, _state} = insert_account(writer)
+
+      EctoLibSql.disconnect([], writer)
+      EctoLibSql.disconnect([], state)
+    end
+
+    test "handle_begin honours the connection default", %{database: database} do
+      {:ok, state} = EctoLibSql.connect(database: database, default_transaction_mode: :immediate)
+      {:ok, writer} = EctoLibSql.connect(database: database, busy_timeout: 0)
+
+      {:ok, :begin, trx_state} = EctoLibSql.handle_begin([], state)
+
+      assert {:error, %EctoLibSql.Error{}, _state} = insert_account(writer)
+
+      {:ok, _result, _state} = EctoLibSql.handle_rollback([], trx_state)
+      EctoLibSql.disconnect([], writer)
+      EctoLibSql.disconnect([], state)
+    end
+
+    test "explicit behaviour overrides the connection default", %{database: database} do
+      {:ok, state} = EctoLibSql.connect(database: database, default_transaction_mode: :immediate)
+      {:ok, writer} = EctoLibSql.connect(database: database, busy_timeout: 0)
+
+      # DEFERRED takes no lock at BEGIN, so the writer is not blocked.
+      {:ok, trx_state} = EctoLibSql.Native.begin(state, behavior: :deferred)
+
+      assert {:ok, _query, _result, _state} = insert_account(writer)
+
+      {:ok, _} = EctoLibSql.Native.rollback(trx_state)
+      EctoLibSql.disconnect([], writer)
+      EctoLibSql.disconnect([], state)
+    end
+
+    test "without the option transactions stay deferred", %{database: database} do
+      {:ok, state} = EctoLibSql.connect(database: database)
+      {:ok, writer} = EctoLibSql.connect(database: database, busy_timeout: 0)
+
+      {:ok, trx_state} = EctoLibSql.Native.begin(state)
+
+      assert {:ok, _query, _result, _state} = insert_account(writer)
+
+      {:ok, _} = EctoLibSql.Native.rollback(trx_state)
+      EctoLibSql.disconnect([], writer)
+      EctoLibSql.disconnect([], state)
+    end
+
+    test "invalid default_transaction_mode is rejected at connect", %{database: database} do
+      assert {:error, message} =
+               EctoLibSql.connect(database: database, default_transaction_mode: :concurrent)
+
+      assert message =~ "Invalid default_transaction_mode"
+    end
+  end
+
+  # ============================================================================
+  # memory statistics - IMPLEMENTED ✅
+  # ============================================================================
+
+  describe "memory statistics" do
+    test "memory_used reports positive usage after queries", %{database: database} do
+      {:ok, state} = EctoLibSql.connect(database: database)
+
+      {:ok, _query, _result, state} =
+        EctoLibSql.handle_execute("CREATE TABLE blobs (data BLOB)", [], [], state)
+
+      {:ok, _query, _result, state} =
+        EctoLibSql.handle_execute(
+          """
+          WITH RECURSIVE n(i) AS (SELECT 1 UNION ALL SELECT i + 1 FROM n WHERE i < 100)
+          INSERT INTO blobs SELECT randomblob(1024) FROM n
+          """,
+          [],
+          [],
+          state
+        )
+
+      {:ok, _query, _result, state} =
+        EctoLibSql.handle_execute("SELECT count(*) FROM blobs", [], [], state)
+
+      assert {:ok, %{used: used, highwater: highwater}} = EctoLibSql.Native.memory_used()
+      assert used > 0
+      assert highwater >= used
+
+      EctoLibSql.disconnect([], state)
+    end
+
+    test "release_memory does not error" do
+      assert {:ok, freed} = EctoLibSql.Native.release_memory()
+      assert is_integer(freed) and freed >= 0
+
+      assert {:ok, _freed} = EctoLibSql.Native.release_memory(1024)
+    end
+  end
+
+  # ============================================================================
+  # secure_delete - IMPLEMENTED ✅
+  # ============================================================================
+
+  describe "secure_delete" do
+    test "can be set and read back", %{database: database} do
+      {:ok, state} = EctoLibSql.connect(database: database)
+
+      assert {:ok, :off} = EctoLibSql.Native.get_secure_delete(state)
+
+      assert :ok = EctoLibSql.Native.set_secure_delete(state, :on)
+      assert {:ok, :on} = EctoLibSql.Native.get_secure_delete(state)
+
+      assert :ok = EctoLibSql.Native.set_secure_delete(state, :fast)
+      assert {:ok, :fast} = EctoLibSql.Native.get_secure_delete(state)
+
+      EctoLibSql.disconnect([], state)
+    end
+
+    test "rejects unknown modes", %{database: database} do
+      {:ok, state} = EctoLibSql.connect(database: database)
+
+      assert {:error, message} = EctoLibSql.Native.set_secure_delete(state, :sometimes)
+      assert message =~ "Invalid secure_delete mode"
+      assert {:ok, :off} = EctoLibSql.Native.get_secure_delete(state)
+
+      EctoLibSql.disconnect([], state)
+    end
+
+    test "is applied by the connect option", %{database: database} do
+      {:ok, state} = EctoLibSql.connect(database: database, secure_delete: :on)
+
+      assert {:ok, :on} = EctoLibSql.Native.get_secure_delete(state)
+
+      EctoLibSql.disconnect([], state)
+    end
+
+    test "connect option rejects unknown modes", %{database: database} do
+      assert {:error, message} = EctoLibSql.connect(database: database, secure_delete: :always)
+      assert message =~ "Invalid secure_delete"
+    end
+  end
+
+  # ============================================================================
+  # threads - IMPLEMENTED ✅
+  # ============================================================================
+
+  describe "threads" do
+    test "set_threads is reflected by PRAGMA threads", %{database: database} do
+      {:ok, state} = EctoLibSql.connect(database: database)
+
+      assert {:ok, applied} = EctoLibSql.Native.set_threads(state, 2)
+      # SQLite caps the value at its compile-time maximum
+      assert applied in 0..2
+
+      assert {:ok, %{rows: [[^applied]]}} = EctoLibSql.Pragma.query(state, "PRAGMA threads")
+
+      assert {:ok, 0} = EctoLibSql.Native.set_threads(state, 0)
+      assert {:ok, %{rows: [[0]]}} = EctoLibSql.Pragma.query(state, "PRAGMA threads")
+
+      EctoLibSql.disconnect([], state)
+    end
+
+    test "rejects negative values", %{database: database} do
+      {:ok, state} = EctoLibSql.connect(database: database)
+
+      assert {:error, message} = EctoLibSql.Native.set_threads(state, -1)
+      assert message =~ "non-negative"
+
+      assert {:error, message} = EctoLibSql.connect(database: database, threads: -1)
+      assert message =~ "non-negative"
+
+      EctoLibSql.disconnect([], state)
+    end
+
+    test "connect option applies the limit and large sorts stay correct", %{database: database} do
+      {:ok, state} = EctoLibSql.connect(database: database, threads: 4)
+
+      {:ok, %{rows: [[applied]]}} = EctoLibSql.Pragma.query(state, "PRAGMA threads")
+      assert applied in 0..4
+
+      {:ok, _query, _result, state} =
+        EctoLibSql.handle_execute("CREATE TABLE numbers (n INTEGER)", [], [], state)
+
+      {:ok, _query, _result, state} =
+        EctoLibSql.handle_execute(
+          """
+          WITH RECURSIVE seq(i) AS (SELECT 1 UNION ALL SELECT i + 1 FROM seq WHERE i < 50000)
+          INSERT INTO numbers SELECT (i * 7919) % 50000 FROM seq
+          """,
+          [],
+          [],
+          state
+        )
+
+      {:ok, _query, result, _state} =
+        EctoLibSql.handle_execute("SELECT n FROM numbers ORDER BY n DESC", [], [], state)
+
+      values = Enum.map(result.rows, &hd/1)
+      assert length(values) == 50_000
+      assert values == Enum.sort(values, :desc)
+
+      EctoLibSql.disconnect([], state)
+    end
+  end
+
+  # ============================================================================
+  # optimize - IMPLEMENTED ✅
+  # ============================================================================
+
+  defp build_indexed_table(state) do
+    {:ok, _results} =
+      EctoLibSql.Native.execute_batch_sql(state, """
+      CREATE TABLE items (id INTEGER PRIMARY KEY, category TEXT, price INTEGER);
+      CREATE INDEX idx_items_category ON items (category);
+      CREATE INDEX idx_items_price ON items (price);
+      WITH RECURSIVE seq(i) AS (SELECT 1 UNION ALL SELECT i + 1 FROM seq WHERE i < 1000)
+      INSERT INTO items (category, price) SELECT 'c' || (i % 10), i FROM seq;
+      """)
+
+    # Queries that use the indexes mark the table as worth analysing
+    {:ok, _query, _result, state} =
+      EctoLibSql.handle_execute(
+        "SELECT count(*) FROM items WHERE category = ? AND price > ?",
+        ["c3", 500],
+        [],
+        state
+      )
+
+    state
+  end
+
+  describe "optimize" do
+    test "optimize_on_close runs PRAGMA optimize when closing", %{database: database} do
+      {:ok, state} = EctoLibSql.connect(database: database, optimize_on_close: true)
+      state = build_indexed_table(state)
+
+      assert {:ok, false} = EctoLibSql.Native.table_exists(state, "sqlite_stat1")
+      assert :ok = EctoLibSql.disconnect([], state)
+
+      {:ok, state} = EctoLibSql.connect(database: database)
+      assert {:ok, true} = EctoLibSql.Native.table_exists(state, "sqlite_stat1")
+
+      assert {:ok, _query, %{rows: [[count]]}, _state} =
+               EctoLibSql.handle_execute(
+                 "SELECT count(*) FROM sqlite_stat1 WHERE tbl = 'items'",
+                 [],
+                 [],
+                 state
+               )
+
+      assert count > 0
+
+      EctoLibSql.disconnect([], state)
+    end
+
+    test "optimize/2 runs with and without a mask", %{database: database} do
+      {:ok, state} = EctoLibSql.connect(database: database)
+      state = build_indexed_table(state)
+
+      assert :ok = EctoLibSql.Native.optimize(state)
+      assert :ok = EctoLibSql.Native.optimize(state, 0x10002)
+      assert {:ok, true} = EctoLibSql.Native.table_exists(state, "sqlite_stat1")
+
+      EctoLibSql.disconnect([], state)
+    end
+
+    test "rejects invalid masks and options", %{database: database} do
+      {:ok, state} = EctoLibSql.connect(database: database)
+
+      assert {:error, "optimize mask must be a non-negative integer or nil"} =
+               EctoLibSql.Native.optimize(state, -1)
+
+      assert {:error, "optimize_on_close must be a boolean"} =
+               EctoLibSql.connect(database: database, optimize_on_close: :yes)
+
+      assert {:error, "optimize_on_close is not supported for read_only connections"} =
+               EctoLibSql.connect(database: database, read_only: true, optimize_on_close: true)
+
+      EctoLibSql.disconnect([], state)
+    end
+  end
+
+  # ============================================================================
+  # empty_string_as_null - IMPLEMENTED ✅
+  # ============================================================================
+
+  describe "empty_string_as_null" do
+    setup %{database: database} do
+      {:ok, setup_state} = EctoLibSql.connect(database: database)
+
+      {:ok, _query, _result, _state} =
+        EctoLibSql.handle_execute(
+          "CREATE TABLE contacts (id INTEGER PRIMARY KEY, phone TEXT)",
+          [],
+          [],
+          setup_state
+        )
+
+      EctoLibSql.disconnect([], setup_state)
+      :ok
+    end
+
+    test "binds empty strings as NULL when enabled", %{database: database} do
+      {:ok, state} = EctoLibSql.connect(database: database, empty_string_as_null: true)
+
+      {:ok, _query, _result, state} =
+        EctoLibSql.handle_execute("INSERT INTO contacts (phone) VALUES (?)", [""], [], state)
+
+      {:ok, stmt_id} = EctoLibSql.Native.prepare(state, "INSERT INTO contacts (phone) VALUES (?)")
+      {:ok, 1} = EctoLibSql.Native.execute_stmt(state, stmt_id, "INSERT INTO contacts", [""])
+      :ok = EctoLibSql.Native.close_stmt(stmt_id)
+
+      {:ok, _query, result, _state} =
+        EctoLibSql.handle_execute("SELECT phone FROM contacts ORDER BY id", [], [], state)
+
+      assert result.rows == [[nil], [nil]]
+
+      EctoLibSql.disconnect([], state)
+    end
+
+    test "keeps empty strings by default", %{database: database} do
+      {:ok, state} = EctoLibSql.connect(database: database)
+
+      {:ok, _query, _result, state} =
+        EctoLibSql.handle_execute("INSERT INTO contacts (phone) VALUES (?)", [""], [], state)
+
+      {:ok, _query, result, _state} =
+        EctoLibSql.handle_execute("SELECT phone, phone IS NULL FROM contacts", [], [], state)
+
+      assert result.rows == [["", 0]]
+
+      EctoLibSql.disconnect([], state)
+    end
+
+    test "leaves non-empty strings untouched", %{database: database} do
+      {:ok, state} = EctoLibSql.connect(database: database, empty_string_as_null: true)
+
+      {:ok, _query, _result, state} =
+        EctoLibSql.handle_execute("INSERT INTO contacts (phone) VALUES (?)", [" "], [], state)
+
+      {:ok, _query, result, _state} =
+        EctoLibSql.handle_execute("SELECT phone FROM contacts", [], [], state)
+
+      assert result.rows == [[" "]]
+
+      EctoLibSql.disconnect([], state)
+    end
+  end
+
+  describe "disable_statement_cache" do
+    test "repeated prepares leave no cached statements", %{database: database} do
+      {:ok, state} = EctoLibSql.connect(database: database, disable_statement_cache: true)
+
+      {:ok, _query, _result, state} =
+        EctoLibSql.handle_execute(
+          "CREATE TABLE items (id INTEGER PRIMARY KEY, name TEXT)",
+          [],
+          [],
+          state
+        )
+
+      for name <- ["a", "b", "c"] do
+        {:ok, stmt_id} = EctoLibSql.Native.prepare(state, "INSERT INTO items (name) VALUES (?)")
+        {:ok, 1} = EctoLibSql.Native.execute_stmt(state, stmt_id, "INSERT INTO items", [name])
+        :ok = EctoLibSql.Native.close_stmt(stmt_id)
+      end
+
+      results =
+        for _ <- 1..3 do
+          {:ok, stmt_id} =
+            EctoLibSql.Native.prepare(state, "SELECT name FROM items WHERE id <= ? ORDER BY id")
+
+          {:ok, 1} = EctoLibSql.Native.stmt_column_count(state, stmt_id)
+          {:ok, result} = EctoLibSql.Native.query_stmt(state, stmt_id, [2])
+          result.rows
+        end
+
+      assert results == List.duplicate([["a"], ["b"]], 3)
+      assert {:ok, 0} = EctoLibSql.Native.statement_cache_size(state)
+
+      EctoLibSql.disconnect([], state)
+    end
+
+    test "reports prepare errors and unknown statements", %{database: database} do
+      {:ok, state} = EctoLibSql.connect(database: database, disable_statement_cache: true)
+
+      assert {:error, _reason} = EctoLibSql.Native.prepare(state, "SELEC nonsense")
+
+      {:ok, stmt_id} = EctoLibSql.Native.prepare(state, "SELECT 1")
+      :ok = EctoLibSql.Native.close_stmt(stmt_id)
+
+      assert {:error, _reason} = EctoLibSql.Native.query_stmt(state, stmt_id, [])
+
+      EctoLibSql.disconnect([], state)
+    end
+
+    test "uncached statements stay tied to their connection", %{database: database} do
+      {:ok, owner} = EctoLibSql.connect(database: database, disable_statement_cache: true)
+      {:ok, other} = EctoLibSql.connect(database: database, disable_statement_cache: true)
+
+      {:ok, stmt_id} = EctoLibSql.Native.prepare(owner, "SELECT 1")
+
+      assert {:error, "Statement does not belong to connection"} =
+               EctoLibSql.Native.query_stmt(other, stmt_id, [])
+
+      assert {:ok, %{rows: [[1]]}} = EctoLibSql.Native.query_stmt(owner, stmt_id, [])
+      assert :ok = EctoLibSql.Native.close_stmt(stmt_id)
+      assert {:error, "Statement not found"} = EctoLibSql.Native.close_stmt(stmt_id)
+
+      EctoLibSql.disconnect([], owner)
+      EctoLibSql.disconnect([], other)
+    end
+
+    test "caches statements by default", %{database: database} do
+      {:ok, state} = EctoLibSql.connect(database: database)
+
+      {:ok, stmt_id} = EctoLibSql.Native.prepare(state, "SELECT 1")
+      assert {:ok, 1} = EctoLibSql.Native.statement_cache_size(state)
+
+      :ok = EctoLibSql.Native.close_stmt(stmt_id)
+      assert {:ok, 0} = EctoLibSql.Native.statement_cache_size(state)
+
+      EctoLibSql.disconnect([], state)
+    end
+
+    test "rejects non-boolean values", %{database: database} do
+      assert {:error, message} =
+               EctoLibSql.connect(database: database, disable_statement_cache: 1)
+
+      assert message =~ "boolean"
+    end
+  end
+
+  describe "auto_reconnect" do
+    test "is rejected for local connections", %{database: database} do
+      assert {:error, "auto_reconnect is only supported for remote connections"} =
+               EctoLibSql.connect(database: database, auto_reconnect: true)
+    end
+
+    test "must be a boolean", %{database: database} do
+      assert {:error, "auto_reconnect must be a boolean"} =
+               EctoLibSql.connect(database: database, auto_reconnect: :yes)
+    end
+  end
+
+  describe "max_sql_length" do
+    test "rejects longer SQL before preparing it", %{database: database} do
+      {:ok, state} = EctoLibSql.connect(database: database, max_sql_length: 20)
+
+      long_sql = "SELECT 1 AS a_rather_long_column_name"
+
+      assert {:error, %EctoLibSql.Error{sqlite: %{code: :sql_too_long}}, state} =
+               EctoLibSql.handle_execute(long_sql, [], [], state)
+
+      assert {:error, :sql_too_long} = EctoLibSql.Native.prepare(state, long_sql)
+      assert {:error, :sql_too_long} = EctoLibSql.Native.execute_batch_sql(state, long_sql)
+
+      assert {:ok, _query, %{rows: [[1]]}, state} =
+               EctoLibSql.handle_execute("SELECT 1", [], [], state)
+
+      EctoLibSql.disconnect([], state)
+    end
+
+    test "applies to count, keyset_page and copy_into", %{database: database} do
+      {:ok, state} = EctoLibSql.connect(database: database, max_sql_length: 40)
+      long_where = "id > 0 AND id < 1000000"
+      long_select = "SELECT id FROM items WHERE id > 0 AND id < 1000000"
+
+      assert {:error, :sql_too_long} = EctoLibSql.Native.count(state, "items", long_where)
+
+      assert {:error, :sql_too_long} =
+               EctoLibSql.Native.keyset_page(state, long_select, "id", nil, 10)
+
+      assert {:error, :sql_too_long} = EctoLibSql.Native.copy_into(state, "copy", long_select)
+
+      EctoLibSql.disconnect([], state)
+    end
+
+    test "applies inside transactions", %{database: database} do
+      {:ok, state} = EctoLibSql.connect(database: database, max_sql_length: 20)
+      {:ok, :begin, trx_state} = EctoLibSql.handle_begin([], state)
+
+      assert {:error, %EctoLibSql.Error{sqlite: %{code: :sql_too_long}}, trx_state} =
+               EctoLibSql.handle_execute("SELECT 1 AS a_rather_long_alias", [], [], trx_state)
+
+      {:ok, _, state} = EctoLibSql.handle_rollback([], trx_state)
+      EctoLibSql.disconnect([], state)
+    end
+
+    test "is unlimited by default", %{database: database} do
+      {:ok, state} = EctoLibSql.connect(database: database)
+      sql = "SELECT " <> Enum.map_join(1..10_000, ", ", &Integer.to_string/1)
+
+      assert {:ok, _query, %{num_rows: 1}, state} = EctoLibSql.handle_execute(sql, [], [], state)
+
+      EctoLibSql.disconnect([], state)
+    end
+
+    test "must be a positive integer", %{database: database} do
+      assert {:error, "max_sql_length must be a positive integer or nil"} =
+               EctoLibSql.connect(database: database, max_sql_length: 0)
+    end
+  end
+
   # ============================================================================
   # Integration tests
   # ============================================================================
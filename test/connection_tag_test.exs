@@ -0,0 +1,86 @@
+defmodule EctoLibSql.ConnectionTagTest do
+  use ExUnit.Case
+
+  setup do
+    suffix = :erlang.unique_integer([:positive])
+    test_db = "z_ecto_libsql_test-connection_tag_#{suffix}.db"
+    tag_a = "tenant-a-#{suffix}"
+    tag_b = "tenant-b-#{suffix}"
+
+    {:ok, conn_a} = EctoLibSql.connect(database: test_db, tag: tag_a)
+    {:ok, conn_b} = EctoLibSql.connect(database: test_db, tag: tag_b)
+
+    handler_id = "connection-tag-test-#{suffix}"
+    test_pid = self()
+
+    :ok =
+      :telemetry.attach(
+        handler_id,
+        [:ecto_libsql, :query],
+        fn _event, _measurements, metadata, _config ->
+          # Handlers run in the calling process; ignore queries from concurrent tests
+          if self() == test_pid, do: send(test_pid, {:query_event, metadata})
+        end,
+        nil
+      )
+
+    on_exit(fn ->
+      :telemetry.detach(handler_id)
+      EctoLibSql.disconnect([], conn_a)
+      EctoLibSql.disconnect([], conn_b)
+      EctoLibSql.TestHelpers.cleanup_db_files(test_db)
+    end)
+
+    {:ok, conn_a: conn_a, conn_b: conn_b, tag_a: tag_a, tag_b: tag_b}
+  end
+
+  test "telemetry events carry the connection's tag", context do
+    %{conn_a: conn_a, conn_b: conn_b, tag_a: tag_a, tag_b: tag_b} = context
+
+    {:ok, _query, _result, _state} = EctoLibSql.handle_execute("SELECT 1", [], [], conn_a)
+    assert_receive {:query_event, %{statement: "SELECT 1", tag: ^tag_a}}
+
+    {:ok, _query, _result, _state} = EctoLibSql.handle_execute("SELECT 2", [], [], conn_b)
+    assert_receive {:query_event, %{statement: "SELECT 2", tag: ^tag_b}}
+  end
+
+  test "the tag survives transactions", %{conn_a: conn_a, tag_a: tag_a} do
+    {:ok, :begin, trx_state} = EctoLibSql.handle_begin([], conn_a)
+    {:ok, _query, _result, trx_state} = EctoLibSql.handle_execute("SELECT 1", [], [], trx_state)
+    assert_receive {:query_event, %{tag: ^tag_a}}
+
+    {:ok, _result, state} = EctoLibSql.handle_commit([], trx_state)
+    {:ok, _query, _result, _state} = EctoLibSql.handle_execute("SELECT 2", [], [], state)
+    assert_receive {:query_event, %{statement: "SELECT 2", tag: ^tag_a}}
+  end
+
+  test "untagged connections report nil" do
+    {:ok, state} = EctoLibSql.connect(database: ":memory:")
+
+    {:ok, _query, _result, _state} = EctoLibSql.handle_execute("SELECT 3", [], [], state)
+    assert_receive {:query_event, %{statement: "SELECT 3", tag: nil}}
+    assert {:ok, %{tag: nil}} = EctoLibSql.Native.connection_info(state)
+
+    EctoLibSql.disconnect([], state)
+  end
+
+  test "connection_info and list_connections report tags", context do
+    %{conn_a: conn_a, conn_b: conn_b, tag_a: tag_a} = context
+
+    assert {:ok, %{conn_id: conn_id, mode: :local, tag: ^tag_a, read_only: false}} =
+             EctoLibSql.Native.connection_info(conn_a)
+
+    assert conn_id == conn_a.conn_id
+
+    assert {:ok, [%{conn_id: ^conn_id, tag: ^tag_a}]} =
+             EctoLibSql.Native.list_connections(tag: tag_a)
+
+    assert {:ok, all} = EctoLibSql.Native.list_connections()
+    assert conn_b.conn_id in Enum.map(all, & &1.conn_id)
+  end
+
+  test "rejects a non-string tag" do
+    assert {:error, "tag must be a string"} =
+             EctoLibSql.connect(database: ":memory:", tag: 42)
+  end
+end
@@ -0,0 +1,60 @@
+defmodule EctoLibSql.CountTest do
+  use ExUnit.Case
+
+  alias EctoLibSql.Native
+
+  setup do
+    test_db = "z_ecto_libsql_test-count_#{:erlang.unique_integer([:positive])}.db"
+
+    {:ok, state} = EctoLibSql.connect(database: test_db)
+
+    {:ok, _query, _result, state} =
+      EctoLibSql.handle_execute(
+        "CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT, age INTEGER)",
+        [],
+        [],
+        state
+      )
+
+    {:ok, _query, _result, state} =
+      EctoLibSql.handle_execute(
+        "INSERT INTO users (name, age) VALUES ('Alice', 30), ('Bob', 25), ('Carol', 41)",
+        [],
+        [],
+        state
+      )
+
+    on_exit(fn ->
+      EctoLibSql.disconnect([], state)
+      EctoLibSql.TestHelpers.cleanup_db_files(test_db)
+    end)
+
+    {:ok, state: state}
+  end
+
+  test "counts all rows", %{state: state} do
+    assert {:ok, 3} = Native.count(state, "users")
+    assert {:ok, 3} = Native.count(state, :users)
+  end
+
+  test "counts rows matching a parameterised WHERE clause", %{state: state} do
+    assert {:ok, 2} = Native.count(state, "users", "age >= ?", [30])
+    assert {:ok, 1} = Native.count(state, "users", "age >= ? AND name = ?", [30, "Carol"])
+    assert {:ok, 0} = Native.count(state, "users", "name = ?", ["Nobody"])
+  end
+
+  test "quotes table names that need it", %{state: state} do
+    {:ok, _query, _result, state} =
+      EctoLibSql.handle_execute("CREATE TABLE \"order items\" (id INTEGER)", [], [], state)
+
+    {:ok, _query, _result, state} =
+      EctoLibSql.handle_execute("INSERT INTO \"order items\" VALUES (1), (2)", [], [], state)
+
+    assert {:ok, 2} = Native.count(state, "order items")
+  end
+
+  test "rejects invalid table names", %{state: state} do
+    assert {:error, "Table name cannot be empty"} = Native.count(state, "")
+    assert {:error, _reason} = Native.count(state, "missing_table")
+  end
+end
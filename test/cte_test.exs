@@ -283,5 +283,41 @@ defmodule EctoLibSql.CTETest do
       assert result.num_rows == 1
       assert result.rows == [[2]]
     end
+
+    test "CTE followed by UPDATE ... RETURNING returns the updated rows" do
+      TestRepo.insert!(%Employee{name: "Alice", level: 1})
+      TestRepo.insert!(%Employee{name: "Bob", level: 2})
+
+      result =
+        Ecto.Adapters.SQL.query!(
+          TestRepo,
+          """
+          WITH juniors AS (SELECT id FROM employees WHERE level = 1)
+          UPDATE employees SET level = level + 1
+          WHERE id IN (SELECT id FROM juniors)
+          RETURNING name, level
+          """
+        )
+
+      assert result.num_rows == 1
+      assert result.rows == [["Alice", 2]]
+    end
+
+    test "CTE followed by DELETE without RETURNING reports affected rows" do
+      TestRepo.insert!(%Employee{name: "Alice", level: 1})
+      TestRepo.insert!(%Employee{name: "Bob", level: 2})
+
+      result =
+        Ecto.Adapters.SQL.query!(
+          TestRepo,
+          """
+          WITH seniors AS (SELECT id FROM employees WHERE level >= 2)
+          DELETE FROM employees WHERE id IN (SELECT id FROM seniors)
+          """
+        )
+
+      assert result.num_rows == 1
+      assert [%{name: "Alice"}] = TestRepo.all(Employee)
+    end
   end
 end
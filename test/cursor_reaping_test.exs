@@ -0,0 +1,66 @@
+defmodule EctoLibSql.CursorReapingTest do
+  # Not async: reaping is global across connections, so concurrently running
+  # tests could otherwise lose their cursors.
+  use ExUnit.Case, async: false
+
+  alias EctoLibSql.Native
+
+  setup do
+    test_db = "z_ecto_libsql_test-cursor_reap_#{:erlang.unique_integer([:positive])}.db"
+
+    {:ok, state} = EctoLibSql.connect(database: test_db)
+
+    {:ok, _query, _result, state} =
+      EctoLibSql.handle_execute(
+        "CREATE TABLE items (id INTEGER PRIMARY KEY, name TEXT)",
+        [],
+        [],
+        state
+      )
+
+    {:ok, _query, _result, state} =
+      EctoLibSql.handle_execute("INSERT INTO items (name) VALUES ('a'), ('b')", [], [], state)
+
+    on_exit(fn ->
+      EctoLibSql.disconnect([], state)
+      EctoLibSql.TestHelpers.cleanup_db_files(test_db)
+    end)
+
+    {:ok, state: state}
+  end
+
+  defp declare(%EctoLibSql.State{conn_id: conn_id}) do
+    cursor_id = Native.declare_cursor(conn_id, "SELECT * FROM items", [])
+    true = is_binary(cursor_id)
+    cursor_id
+  end
+
+  test "reaps cursors older than the threshold and keeps fresh ones", %{state: state} do
+    old1 = declare(state)
+    old2 = declare(state)
+
+    Process.sleep(300)
+
+    fresh = declare(state)
+
+    on_exit(fn -> Native.close(fresh, :cursor_id) end)
+
+    assert {:ok, reaped} = Native.reap_stale_cursors(200)
+    assert reaped >= 2
+
+    assert {:error, "Cursor not found"} = Native.fetch_cursor(state.conn_id, old1, 10)
+    assert {:error, "Cursor not found"} = Native.fetch_cursor(state.conn_id, old2, 10)
+
+    assert {["id", "name"], rows, 2} = Native.fetch_cursor(state.conn_id, fresh, 10)
+    assert length(rows) == 2
+  end
+
+  test "returns zero when nothing is old enough", %{state: state} do
+    cursor_id = declare(state)
+
+    on_exit(fn -> Native.close(cursor_id, :cursor_id) end)
+
+    assert {:ok, 0} = Native.reap_stale_cursors(:timer.hours(1))
+    assert {_columns, _rows, 2} = Native.fetch_cursor(state.conn_id, cursor_id, 10)
+  end
+end
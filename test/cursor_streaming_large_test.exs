@@ -320,6 +320,57 @@ defmodule EctoLibSql.CursorStreamingLargeTest do
     end
   end
 
+  describe "cursor buffering" do
+    test "local cursors buffer the result set when declared", %{state: state} do
+      state = insert_rows(state, 1, 300, 1)
+
+      query = %EctoLibSql.Query{statement: "SELECT id FROM large_data ORDER BY id"}
+      {:ok, ^query, cursor, state} = EctoLibSql.handle_declare(query, [], [], state)
+
+      assert {:ok, 300} = EctoLibSql.Native.cursor_buffer_size(state, cursor.ref)
+
+      {:cont, %{num_rows: 100}, state} =
+        EctoLibSql.handle_fetch(query, cursor, [max_rows: 100], state)
+
+      assert {:ok, 200} = EctoLibSql.Native.cursor_buffer_size(state, cursor.ref)
+    end
+
+    test "prefetching cursors read ahead a bounded number of batches", %{state: state} do
+      state = insert_rows(state, 1, 3000, 1)
+
+      query = %EctoLibSql.Query{statement: "SELECT id FROM large_data ORDER BY id"}
+      opts = [max_rows: 100, prefetch: 2]
+      {:ok, ^query, cursor, state} = EctoLibSql.handle_declare(query, [], opts, state)
+
+      # Two batches wait in the buffer and the reader holds a third until one drains
+      cap = 300
+      Process.sleep(200)
+      assert {:ok, ^cap} = EctoLibSql.Native.cursor_buffer_size(state, cursor.ref)
+
+      assert drain_slowly(state, cursor, query, opts, cap, []) == Enum.to_list(1..3000)
+    end
+
+    test "prefetching cursors work inside transactions", %{state: state} do
+      state = insert_rows(state, 1, 250, 1)
+      {:ok, :begin, trx_state} = EctoLibSql.handle_begin([], state)
+
+      query = %EctoLibSql.Query{statement: "SELECT id FROM large_data ORDER BY id"}
+      opts = [max_rows: 100, prefetch: 1]
+      {:ok, ^query, cursor, trx_state} = EctoLibSql.handle_declare(query, [], opts, trx_state)
+
+      assert fetch_all_rows(trx_state, cursor, query, opts) == 250
+
+      {:ok, _, _state} = EctoLibSql.handle_commit([], trx_state)
+    end
+
+    test "prefetch must be positive", %{state: state} do
+      query = %EctoLibSql.Query{statement: "SELECT id FROM large_data"}
+
+      assert {:error, "batch_size and prefetch must be positive", _state} =
+               EctoLibSql.handle_declare(query, [], [prefetch: 0], state)
+    end
+  end
+
   describe "cursor error handling and edge cases" do
     test "handle_declare with malformed SQL returns error", %{state: state} do
       query = %EctoLibSql.Query{statement: "SELEKT * FORM nonexistent_table"}
@@ -709,6 +760,22 @@ defmodule EctoLibSql.CursorStreamingLargeTest do
     end
   end
 
+  # Fetches one batch at a time with a pause in between, checking that the
+  # cursor never holds more than `cap` rows while the reader runs ahead.
+  defp drain_slowly(state, cursor, query, opts, cap, acc) do
+    Process.sleep(10)
+    {:ok, buffered} = EctoLibSql.Native.cursor_buffer_size(state, cursor.ref)
+    assert buffered <= cap
+
+    case EctoLibSql.handle_fetch(query, cursor, opts, state) do
+      {:cont, result, state} ->
+        drain_slowly(state, cursor, query, opts, cap, [result.rows | acc])
+
+      {:halt, _result, _state} ->
+        acc |> Enum.reverse() |> Enum.concat() |> Enum.map(fn [id] -> id end)
+    end
+  end
+
   defp fetch_all_rows(state, cursor, query, opts) do
     case EctoLibSql.handle_fetch(query, cursor, opts, state) do
       {:cont, result, next_state} ->
@@ -147,6 +147,45 @@ defmodule EctoLibSql.QueryEncodingTest do
     end
   end
 
+  describe "encode/3 range tuple expansion" do
+    setup do
+      query = %Query{statement: "SELECT * FROM test WHERE x BETWEEN ? AND ?"}
+      {:ok, query: query}
+    end
+
+    test "expands {low, high} into two parameters in order", %{query: query} do
+      assert [10, 20] = DBConnection.Query.encode(query, [{10, 20}], [])
+      assert ["a", 1, 2, "z"] = DBConnection.Query.encode(query, ["a", {1, 2}, "z"], [])
+    end
+
+    test "encodes each end of the range", %{query: query} do
+      params = [{~D[2024-01-01], ~D[2024-12-31]}, {Decimal.new("1.5"), 3}]
+
+      assert ["2024-01-01", "2024-12-31", "1.5", 3] =
+               DBConnection.Query.encode(query, params, [])
+    end
+
+    test "keeps {:blob, data} as a single parameter", %{query: query} do
+      assert [{:blob, <<1, 2>>}] = DBConnection.Query.encode(query, [{:blob, <<1, 2>>}], [])
+    end
+
+    test "rejects tuples with the wrong arity", %{query: query} do
+      assert_raise ArgumentError, ~r/two-element/, fn ->
+        DBConnection.Query.encode(query, [{1, 2, 3}], [])
+      end
+    end
+
+    test "rejects nil and mismatched range ends", %{query: query} do
+      assert_raise ArgumentError, ~r/Range tuple/, fn ->
+        DBConnection.Query.encode(query, [{nil, 5}], [])
+      end
+
+      assert_raise ArgumentError, ~r/Range tuple/, fn ->
+        DBConnection.Query.encode(query, [{1, "10"}], [])
+      end
+    end
+  end
+
   describe "decode/3 result pass-through" do
     setup do
       query = %Query{statement: "SELECT * FROM test"}
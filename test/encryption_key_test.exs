@@ -0,0 +1,46 @@
+defmodule EctoLibSql.EncryptionKeyTest do
+  use ExUnit.Case
+
+  alias EctoLibSql.Native
+
+  @key "correct-horse-battery-staple-0123456789"
+
+  setup do
+    test_db = "z_ecto_libsql_test-encryption_key_#{:erlang.unique_integer([:positive])}.db"
+
+    {:ok, state} = EctoLibSql.connect(database: test_db, encryption_key: @key)
+
+    {:ok, _query, _result, state} =
+      EctoLibSql.handle_execute("CREATE TABLE secrets (data TEXT)", [], [], state)
+
+    EctoLibSql.disconnect([], state)
+
+    on_exit(fn -> EctoLibSql.TestHelpers.cleanup_db_files(test_db) end)
+
+    {:ok, database: test_db}
+  end
+
+  test "accepts the key the database was created with", %{database: database} do
+    assert :ok = Native.verify_encryption_key(database, @key)
+    assert :ok = Native.verify_encryption_key(database, @key, :aes256cbc)
+  end
+
+  test "rejects a wrong key", %{database: database} do
+    assert {:error, :invalid_encryption_key} =
+             Native.verify_encryption_key(database, "wrong-key-wrong-key-wrong-key-0000")
+  end
+
+  test "does not create a missing file" do
+    path = "z_ecto_libsql_test-encryption_key_missing.db"
+
+    assert {:error, "Database file not found: " <> ^path} =
+             Native.verify_encryption_key(path, @key)
+
+    refute File.exists?(path)
+  end
+
+  test "rejects an unsupported cipher", %{database: database} do
+    assert {:error, "Invalid cipher: " <> _} =
+             Native.verify_encryption_key(database, @key, :chacha20)
+  end
+end
@@ -0,0 +1,89 @@
+defmodule EctoLibSql.KeysetPaginationTest do
+  use ExUnit.Case
+
+  alias EctoLibSql.Native
+
+  @row_count 10_000
+  @page_size 750
+
+  setup do
+    test_db = "z_ecto_libsql_test-keyset_#{:erlang.unique_integer([:positive])}.db"
+
+    {:ok, state} = EctoLibSql.connect(database: test_db)
+
+    {:ok, _query, _result, state} =
+      EctoLibSql.handle_execute(
+        "CREATE TABLE items (id INTEGER PRIMARY KEY, bucket INTEGER, label TEXT)",
+        [],
+        [],
+        state
+      )
+
+    {:ok, _query, _result, state} =
+      EctoLibSql.handle_execute(
+        """
+        WITH RECURSIVE n(i) AS (SELECT 1 UNION ALL SELECT i + 1 FROM n WHERE i < #{@row_count})
+        INSERT INTO items (id, bucket, label) SELECT i, i % 3, 'item-' || i FROM n
+        """,
+        [],
+        [],
+        state
+      )
+
+    on_exit(fn ->
+      EctoLibSql.disconnect([], state)
+      EctoLibSql.TestHelpers.cleanup_db_files(test_db)
+    end)
+
+    {:ok, state: state}
+  end
+
+  defp collect_pages(state, sql, params, last_key \\ nil, acc \\ []) do
+    {:ok, page, next_key} = Native.keyset_page(state, sql, "id", last_key, @page_size, params)
+    ids = Enum.map(page.rows, &hd/1)
+    acc = [ids | acc]
+
+    case next_key do
+      nil -> Enum.reverse(acc)
+      key -> collect_pages(state, sql, params, key, acc)
+    end
+  end
+
+  test "pages through the whole table contiguously without overlap", %{state: state} do
+    pages = collect_pages(state, "SELECT id, label FROM items", [])
+
+    assert length(pages) == div(@row_count, @page_size) + 1
+
+    Enum.each(Enum.drop(pages, -1), fn ids -> assert length(ids) == @page_size end)
+
+    # Each page starts right after the previous one ended
+    pages
+    |> Enum.chunk_every(2, 1, :discard)
+    |> Enum.each(fn [previous, next] -> assert hd(next) == List.last(previous) + 1 end)
+
+    assert List.flatten(pages) == Enum.to_list(1..@row_count)
+  end
+
+  test "binds base query parameters before the key", %{state: state} do
+    ids =
+      state
+      |> collect_pages("SELECT id FROM items WHERE bucket = ?", [0])
+      |> List.flatten()
+
+    assert ids == Enum.filter(1..@row_count, &(rem(&1, 3) == 0))
+  end
+
+  test "returns the last key of a full page", %{state: state} do
+    assert {:ok, page, 750} = Native.keyset_page(state, "SELECT * FROM items", :id, nil, 750)
+    assert page.num_rows == 750
+    assert page.columns == ["id", "bucket", "label"]
+
+    assert {:ok, page, nil} = Native.keyset_page(state, "SELECT * FROM items", :id, 9_900, 750)
+    assert page.num_rows == 100
+  end
+
+  test "errors when the order column is not in the results", %{state: state} do
+    assert {:error, _reason} =
+             Native.keyset_page(state, "SELECT label FROM items", "id", nil, 10)
+  end
+end
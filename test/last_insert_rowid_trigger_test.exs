@@ -0,0 +1,74 @@
+defmodule EctoLibSql.LastInsertRowidTriggerTest do
+  @moduledoc """
+  `last_insert_rowid` versus `RETURNING` when an AFTER INSERT trigger inserts
+  into another table.
+
+  SQLite reports the trigger's own inserts only while the trigger runs and
+  restores the outer statement's rowid once it finishes, so both approaches
+  agree after a successful insert. They diverge when the outer INSERT writes
+  nothing: `last_insert_rowid` keeps its previous value, while `RETURNING`
+  reports that no row was inserted.
+  """
+  use ExUnit.Case
+
+  alias EctoLibSql.Native
+
+  setup do
+    test_db = "z_ecto_libsql_test-rowid_trigger_#{:erlang.unique_integer([:positive])}.db"
+
+    {:ok, state} = EctoLibSql.connect(database: test_db)
+
+    {:ok, _results} =
+      Native.execute_batch_sql(state, """
+      CREATE TABLE orders (id INTEGER PRIMARY KEY, sku TEXT UNIQUE);
+      CREATE TABLE audit_log (id INTEGER PRIMARY KEY, order_id INTEGER);
+      INSERT INTO audit_log (id, order_id) VALUES (100, NULL);
+      CREATE TRIGGER log_order AFTER INSERT ON orders
+      BEGIN
+        INSERT INTO audit_log (order_id) VALUES (NEW.id);
+      END;
+      """)
+
+    on_exit(fn ->
+      EctoLibSql.disconnect([], state)
+      EctoLibSql.TestHelpers.cleanup_db_files(test_db)
+    end)
+
+    {:ok, state: state}
+  end
+
+  test "RETURNING id gives the main table's row, not the trigger's", %{state: state} do
+    sql = "INSERT INTO orders (sku) VALUES (?) RETURNING id"
+    assert {:ok, 1} = Native.query_scalar(state, sql, ["A-1"])
+
+    # The trigger's insert got its own, different id
+    assert {:ok, [101, 1]} =
+             Native.query_one(state, "SELECT id, order_id FROM audit_log WHERE id > 100")
+  end
+
+  test "last_insert_rowid reverts to the main insert once the trigger ends", %{state: state} do
+    {:ok, _query, _result, state} =
+      EctoLibSql.handle_execute("INSERT INTO orders (sku) VALUES (?)", ["A-1"], [], state)
+
+    assert Native.get_last_insert_rowid(state) == 1
+    assert {:ok, 101} = Native.query_scalar(state, "SELECT max(id) FROM audit_log")
+  end
+
+  test "a no-op insert leaves last_insert_rowid stale but RETURNING empty", %{state: state} do
+    {:ok, _query, _result, state} =
+      EctoLibSql.handle_execute("INSERT INTO orders (sku) VALUES (?)", ["A-1"], [], state)
+
+    {:ok, _query, result, state} =
+      EctoLibSql.handle_execute(
+        "INSERT OR IGNORE INTO orders (sku) VALUES (?) RETURNING id",
+        ["A-1"],
+        [],
+        state
+      )
+
+    assert result.rows == []
+
+    # Still the id from the previous statement, although nothing was inserted
+    assert Native.get_last_insert_rowid(state) == 1
+  end
+end
@@ -0,0 +1,70 @@
+defmodule EctoLibSql.OperationCancelTest do
+  use ExUnit.Case
+
+  # Counts to `n` with a recursive CTE; large `n` keeps the statement busy
+  @count_sql """
+  WITH RECURSIVE c(x) AS (SELECT 1 UNION ALL SELECT x + 1 FROM c WHERE x < ?)
+  SELECT count(*) FROM c
+  """
+
+  setup do
+    test_db = "z_ecto_libsql_test-operation_cancel_#{:erlang.unique_integer([:positive])}.db"
+
+    {:ok, state} = EctoLibSql.connect(database: test_db)
+
+    on_exit(fn ->
+      EctoLibSql.disconnect([], state)
+      EctoLibSql.TestHelpers.cleanup_db_files(test_db)
+    end)
+
+    {:ok, state: state}
+  end
+
+  defp count_async(state, n, op) do
+    Task.async(fn -> EctoLibSql.handle_execute(@count_sql, [n], [cancellable: op], state) end)
+  end
+
+  test "cancelling a running query leaves the other one alone", %{state: state} do
+    {:ok, slow_op} = EctoLibSql.Native.new_operation()
+    {:ok, other_op} = EctoLibSql.Native.new_operation()
+
+    slow = count_async(state, 500_000_000, slow_op)
+    Process.sleep(100)
+    other = count_async(state, 1_000, other_op)
+
+    assert :ok = EctoLibSql.Native.cancel(slow_op)
+
+    assert {:error, %EctoLibSql.Error{message: "Operation cancelled"}, _state} =
+             Task.await(slow, 30_000)
+
+    assert {:ok, _query, %{rows: [[1_000]]}, _state} = Task.await(other, 30_000)
+  end
+
+  test "cancelling a waiting query stops it before it runs", %{state: state} do
+    {:ok, running_op} = EctoLibSql.Native.new_operation()
+    {:ok, waiting_op} = EctoLibSql.Native.new_operation()
+
+    running = count_async(state, 2_000_000, running_op)
+    Process.sleep(50)
+    waiting = count_async(state, 1_000, waiting_op)
+
+    assert :ok = EctoLibSql.Native.cancel(waiting_op)
+
+    assert {:error, %EctoLibSql.Error{message: "Operation cancelled"}, _state} =
+             Task.await(waiting, 30_000)
+
+    assert {:ok, _query, %{rows: [[2_000_000]]}, _state} = Task.await(running, 30_000)
+  end
+
+  test "handles are single-use", %{state: state} do
+    {:ok, op} = EctoLibSql.Native.new_operation()
+
+    assert {:ok, _query, %{rows: [[10]]}, _state} =
+             EctoLibSql.handle_execute(@count_sql, [10], [cancellable: op], state)
+
+    assert {:error, "Operation not found"} = EctoLibSql.Native.cancel(op)
+
+    assert {:error, %EctoLibSql.Error{message: "Operation not found"}, _state} =
+             EctoLibSql.handle_execute(@count_sql, [10], [cancellable: op], state)
+  end
+end
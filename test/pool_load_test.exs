@@ -39,6 +39,25 @@ defmodule EctoLibSql.PoolLoadTest do
     {:ok, test_db: test_db}
   end
 
+  # Holds the only pooled connection until told to release it
+  defp hold_connection(pool) do
+    parent = self()
+
+    holder =
+      Task.async(fn ->
+        DBConnection.run(pool, fn _conn ->
+          send(parent, :checked_out)
+
+          receive do
+            :release -> :ok
+          end
+        end)
+      end)
+
+    assert_receive :checked_out, 5_000
+    holder
+  end
+
   # ============================================================================
   # HELPER FUNCTIONS FOR EDGE CASE DATA
   # ============================================================================
@@ -1157,6 +1176,60 @@ defmodule EctoLibSql.PoolLoadTest do
     end
   end
 
+  describe "pool checkout waits" do
+    test "a blocked checkout succeeds once a connection is checked in", %{test_db: test_db} do
+      {:ok, pool} =
+        DBConnection.start_link(EctoLibSql,
+          database: test_db,
+          pool_size: 1,
+          queue_target: 5_000,
+          queue_interval: 5_000
+        )
+
+      holder = hold_connection(pool)
+
+      waiter =
+        Task.async(fn ->
+          DBConnection.execute(pool, %EctoLibSql.Query{statement: "SELECT 1"}, [])
+        end)
+
+      # The waiter is queued behind the held connection
+      refute Task.yield(waiter, 200)
+
+      send(holder.pid, :release)
+      Task.await(holder)
+
+      assert {:ok, _query, %EctoLibSql.Result{rows: [[1]]}} = Task.await(waiter)
+
+      GenServer.stop(pool)
+    end
+
+    test "a blocked checkout gives up after the queue timeout", %{test_db: test_db} do
+      {:ok, pool} =
+        DBConnection.start_link(EctoLibSql,
+          database: test_db,
+          pool_size: 1,
+          queue_target: 50,
+          queue_interval: 100
+        )
+
+      holder = hold_connection(pool)
+
+      {elapsed_us, result} =
+        :timer.tc(fn ->
+          DBConnection.execute(pool, %EctoLibSql.Query{statement: "SELECT 1"}, [])
+        end)
+
+      assert {:error, %DBConnection.ConnectionError{}} = result
+      assert elapsed_us < 2_000_000
+
+      send(holder.pid, :release)
+      Task.await(holder)
+
+      GenServer.stop(pool)
+    end
+  end
+
   describe "transaction rollback under load" do
     @tag :slow
     @tag :flaky
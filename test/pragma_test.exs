@@ -13,7 +13,7 @@ defmodule EctoLibSql.PragmaTest do
       EctoLibSql.TestHelpers.cleanup_db_files(test_db)
     end)
 
-    {:ok, state: state}
+    {:ok, state: state, database: test_db}
   end
 
   describe "foreign_keys" do
@@ -56,6 +56,85 @@ defmodule EctoLibSql.PragmaTest do
     end
   end
 
+  describe "defer_foreign_keys" do
+    setup %{state: state} do
+      :ok = Pragma.enable_foreign_keys(state)
+
+      {:ok, _results} =
+        EctoLibSql.Native.execute_batch_sql(state, """
+        CREATE TABLE authors (id INTEGER PRIMARY KEY, name TEXT);
+        CREATE TABLE books (id INTEGER PRIMARY KEY, author_id INTEGER REFERENCES authors (id));
+        CREATE TABLE reviews (
+          id INTEGER PRIMARY KEY,
+          book_id INTEGER REFERENCES books (id) DEFERRABLE INITIALLY DEFERRED
+        );
+        """)
+
+      :ok
+    end
+
+    test "allows inserting out of dependency order until commit", %{state: state} do
+      {:ok, trx_state} = EctoLibSql.Native.begin(state)
+      assert :ok = Pragma.set_defer_foreign_keys(trx_state, true)
+      assert {:ok, %{rows: [[1]]}} = Pragma.defer_foreign_keys(trx_state)
+
+      {:ok, _query, _result, trx_state} =
+        EctoLibSql.handle_execute("INSERT INTO books VALUES (1, 10)", [], [], trx_state)
+
+      {:ok, _query, _result, trx_state} =
+        EctoLibSql.handle_execute("INSERT INTO authors VALUES (10, 'Le Guin')", [], [], trx_state)
+
+      assert {:ok, _} = EctoLibSql.Native.commit(trx_state)
+
+      # The setting does not outlive the transaction
+      assert {:ok, %{rows: [[0]]}} = Pragma.defer_foreign_keys(state)
+      assert {:ok, 1} = EctoLibSql.Native.count(state, "books")
+    end
+
+    test "DEFERRABLE INITIALLY DEFERRED constraints wait for commit", %{state: state} do
+      {:ok, trx_state} = EctoLibSql.Native.begin(state)
+
+      {:ok, _query, _result, trx_state} =
+        EctoLibSql.handle_execute("INSERT INTO reviews VALUES (1, 5)", [], [], trx_state)
+
+      {:ok, _query, _result, trx_state} =
+        EctoLibSql.handle_execute("INSERT INTO authors VALUES (1, 'Tolkien')", [], [], trx_state)
+
+      {:ok, _query, _result, trx_state} =
+        EctoLibSql.handle_execute("INSERT INTO books VALUES (5, 1)", [], [], trx_state)
+
+      assert {:ok, _} = EctoLibSql.Native.commit(trx_state)
+      assert {:ok, 1} = EctoLibSql.Native.count(state, "reviews")
+    end
+
+    test "violations left at commit fail it and roll back", %{state: state} do
+      {:ok, trx_state} = EctoLibSql.Native.begin(state)
+      :ok = Pragma.set_defer_foreign_keys(trx_state, true)
+
+      {:ok, _query, _result, trx_state} =
+        EctoLibSql.handle_execute("INSERT INTO books VALUES (1, 99)", [], [], trx_state)
+
+      assert {:error, message} = EctoLibSql.Native.commit(trx_state)
+      assert message =~ "FOREIGN KEY constraint failed"
+
+      assert EctoLibSql.Native.get_is_autocommit(state)
+      assert {:ok, 0} = EctoLibSql.Native.count(state, "books")
+    end
+
+    test "without deferral the constraint is checked immediately", %{state: state} do
+      {:ok, trx_state} = EctoLibSql.Native.begin(state)
+
+      assert {:error, %EctoLibSql.Error{}, trx_state} =
+               EctoLibSql.handle_execute("INSERT INTO books VALUES (1, 99)", [], [], trx_state)
+
+      {:ok, _} = EctoLibSql.Native.rollback(trx_state)
+    end
+
+    test "requires a transaction", %{state: state} do
+      assert {:error, "No active transaction" <> _} = Pragma.set_defer_foreign_keys(state, true)
+    end
+  end
+
   describe "journal_mode" do
     test "set_journal_mode changes journal mode to WAL", %{state: state} do
       {:ok, result} = Pragma.set_journal_mode(state, :wal)
@@ -84,6 +163,34 @@ defmodule EctoLibSql.PragmaTest do
       {:ok, result} = Pragma.journal_mode(state)
       assert result.rows == [["wal"]]
     end
+
+    test "set_journal_mode WAL persists on a local filesystem" do
+      test_db = "z_ecto_libsql_test-pragma_wal_#{:erlang.unique_integer([:positive])}.db"
+      {:ok, state} = EctoLibSql.connect(database: test_db)
+
+      on_exit(fn -> EctoLibSql.TestHelpers.cleanup_db_files(test_db) end)
+
+      assert {:ok, _result} = Pragma.set_journal_mode(state, :wal)
+      EctoLibSql.disconnect([], state)
+
+      # WAL is persistent - a fresh connection to the same file should still see it.
+      {:ok, state2} = EctoLibSql.connect(database: test_db)
+      {:ok, result} = Pragma.journal_mode(state2)
+      assert result.rows == [["wal"]]
+      EctoLibSql.disconnect([], state2)
+    end
+
+    test "set_journal_mode returns :wal_not_supported when WAL does not stick" do
+      # In-memory databases cannot use WAL and always report "memory".
+      {:ok, mem_state} = EctoLibSql.connect(database: ":memory:")
+
+      try do
+        assert {:error, {:wal_not_supported, "memory"}} =
+                 Pragma.set_journal_mode(mem_state, :wal)
+      after
+        EctoLibSql.disconnect([], mem_state)
+      end
+    end
   end
 
   describe "synchronous" do
@@ -235,6 +342,102 @@ defmodule EctoLibSql.PragmaTest do
     end
   end
 
+  describe "schema-qualified pragmas" do
+    setup %{state: state} do
+      archive_db = "z_ecto_libsql_test-pragma_archive_#{:erlang.unique_integer([:positive])}.db"
+
+      {:ok, _query, _result, state} =
+        EctoLibSql.handle_execute("ATTACH DATABASE '#{archive_db}' AS archive", [], [], state)
+
+      {:ok, _query, _result, state} =
+        EctoLibSql.handle_execute("CREATE TABLE archive.x (id INTEGER, note TEXT)", [], [], state)
+
+      on_exit(fn -> EctoLibSql.TestHelpers.cleanup_db_files(archive_db) end)
+
+      {:ok, state: state}
+    end
+
+    test "PRAGMA schema.name(arg) returns rows", %{state: state} do
+      assert {:ok, _query, result, _state} =
+               EctoLibSql.handle_execute("PRAGMA archive.table_info(x)", [], [], state)
+
+      assert Enum.map(result.rows, &Enum.at(&1, 1)) == ["id", "note"]
+    end
+
+    test "PRAGMA schema.name = value executes", %{state: state} do
+      assert {:ok, _query, _result, state} =
+               EctoLibSql.handle_execute("PRAGMA archive.foreign_keys = ON", [], [], state)
+
+      assert {:ok, %{rows: [[1]]}} = Pragma.query(state, "PRAGMA foreign_keys")
+    end
+  end
+
+  describe "incremental_vacuum" do
+    test "reclaims free pages in incremental auto-vacuum mode", %{state: state} do
+      # auto_vacuum must be chosen before the first table is created
+      {:ok, _} = Pragma.query(state, "PRAGMA auto_vacuum = INCREMENTAL")
+
+      {:ok, _results} =
+        EctoLibSql.Native.execute_batch_sql(state, """
+        CREATE TABLE blobs (id INTEGER PRIMARY KEY, data BLOB);
+        WITH RECURSIVE n(i) AS (SELECT 1 UNION ALL SELECT i + 1 FROM n WHERE i < 200)
+        INSERT INTO blobs (data) SELECT randomblob(4096) FROM n;
+        DELETE FROM blobs;
+        """)
+
+      {:ok, %{rows: [[free_before]]}} = Pragma.query(state, "PRAGMA freelist_count")
+      assert free_before > 0
+
+      assert :ok = Pragma.incremental_vacuum(state, 10)
+      {:ok, %{rows: [[free_partial]]}} = Pragma.query(state, "PRAGMA freelist_count")
+      assert free_partial == free_before - 10
+
+      assert :ok = Pragma.incremental_vacuum(state)
+      {:ok, %{rows: [[free_after]]}} = Pragma.query(state, "PRAGMA freelist_count")
+      assert free_after == 0
+    end
+
+    test "errors clearly when auto_vacuum is not incremental", %{state: state} do
+      {:ok, %{rows: [[0]]}} = Pragma.query(state, "PRAGMA auto_vacuum")
+
+      assert {:error, {:auto_vacuum_not_incremental, :none}} =
+               Pragma.incremental_vacuum(state)
+    end
+  end
+
+  describe "read-only connections" do
+    setup %{state: state, database: database} do
+      {:ok, _query, _result, _state} =
+        EctoLibSql.handle_execute("CREATE TABLE ro_items (id INTEGER, name TEXT)", [], [], state)
+
+      {:ok, ro_state} = EctoLibSql.connect(database: database, read_only: true)
+
+      on_exit(fn -> EctoLibSql.disconnect([], ro_state) end)
+
+      {:ok, ro_state: ro_state}
+    end
+
+    test "allows introspection pragmas", %{ro_state: ro_state} do
+      assert {:ok, result} = Pragma.table_info(ro_state, "ro_items")
+      assert length(result.rows) == 2
+
+      assert {:ok, %{rows: [[0]]}} = Pragma.user_version(ro_state)
+    end
+
+    test "rejects state-changing pragmas", %{ro_state: ro_state} do
+      assert {:error, :read_only} = Pragma.query(ro_state, "PRAGMA writable_schema=ON")
+      assert {:error, :read_only} = Pragma.set_user_version(ro_state, 7)
+
+      assert {:error, %EctoLibSql.Error{sqlite: %{code: :read_only}}, _state} =
+               EctoLibSql.handle_execute("PRAGMA writable_schema = ON", [], [], ro_state)
+    end
+
+    test "still rejects ordinary writes", %{ro_state: ro_state} do
+      assert {:error, %EctoLibSql.Error{}, _state} =
+               EctoLibSql.handle_execute("INSERT INTO ro_items VALUES (1, 'a')", [], [], ro_state)
+    end
+  end
+
   describe "integration" do
     test "multiple PRAGMAs can be set in sequence", %{state: state} do
       # Set multiple PRAGMAs
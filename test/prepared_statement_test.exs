@@ -422,6 +422,61 @@ defmodule EctoLibSql.PreparedStatementTest do
     end
   end
 
+  describe "schema version and stale statement invalidation" do
+    test "schema version changes after CREATE TABLE", %{state: state} do
+      {:ok, before} = Native.get_schema_version(state)
+      assert is_integer(before)
+
+      {:ok, _query, _result, state} =
+        exec_sql(state, "CREATE TABLE schema_probe (id INTEGER PRIMARY KEY)")
+
+      {:ok, after_create} = Native.get_schema_version(state)
+      assert after_create > before
+    end
+
+    test "schema version is stable without DDL", %{state: state} do
+      {:ok, before} = Native.get_schema_version(state)
+
+      {:ok, _query, _result, state} =
+        exec_sql(state, "INSERT INTO users (name, email) VALUES (?, ?)", ["Alice", "a@x.com"])
+
+      assert {:ok, ^before} = Native.get_schema_version(state)
+    end
+
+    test "cached statement metadata is refreshed after a schema change", %{state: state} do
+      {:ok, stmt_id} = Native.prepare(state, "SELECT * FROM users")
+      assert {:ok, 3} = Native.stmt_column_count(state, stmt_id)
+
+      {:ok, _query, _result, state} =
+        exec_sql(state, "ALTER TABLE users ADD COLUMN age INTEGER")
+
+      # The statement was compiled against the old schema; it should be re-prepared.
+      assert {:ok, 4} = Native.stmt_column_count(state, stmt_id)
+      {:ok, columns} = Native.get_stmt_columns(state, stmt_id)
+      assert Enum.map(columns, &elem(&1, 0)) == ["id", "name", "email", "age"]
+
+      # The re-prepared statement still executes under the same statement ID.
+      {:ok, _query, _result, state} =
+        exec_sql(state, "INSERT INTO users (name, email, age) VALUES (?, ?, ?)", ["Bo", "b@x", 30])
+
+      {:ok, result} = Native.query_stmt(state, stmt_id, [])
+      assert result.columns == ["id", "name", "email", "age"]
+
+      Native.close_stmt(stmt_id)
+    end
+
+    test "schema changes before the first metadata read are seen", %{state: state} do
+      {:ok, stmt_id} = Native.prepare(state, "SELECT * FROM users")
+
+      {:ok, _query, _result, state} =
+        exec_sql(state, "ALTER TABLE users ADD COLUMN age INTEGER")
+
+      assert {:ok, 4} = Native.stmt_column_count(state, stmt_id)
+
+      Native.close_stmt(stmt_id)
+    end
+  end
+
   describe "statement get_stmt_columns - full metadata" do
     test "get_stmt_columns returns column metadata", %{state: state} do
       {:ok, stmt_id} = Native.prepare(state, "SELECT * FROM users WHERE id = ?")
@@ -0,0 +1,86 @@
+defmodule EctoLibSql.QueryTimingTest do
+  use ExUnit.Case
+
+  setup do
+    test_db = "z_ecto_libsql_test-query_timing_#{:erlang.unique_integer([:positive])}.db"
+
+    {:ok, state} = EctoLibSql.connect(database: test_db)
+
+    {:ok, _query, _result, state} =
+      EctoLibSql.handle_execute(
+        "CREATE TABLE items (id INTEGER PRIMARY KEY, name TEXT)",
+        [],
+        [],
+        state
+      )
+
+    {:ok, _query, _result, state} =
+      EctoLibSql.handle_execute(
+        "INSERT INTO items (name) VALUES ('a'), ('b'), ('c')",
+        [],
+        [],
+        state
+      )
+
+    on_exit(fn ->
+      EctoLibSql.disconnect([], state)
+      EctoLibSql.TestHelpers.cleanup_db_files(test_db)
+    end)
+
+    {:ok, state: state}
+  end
+
+  test "reports a breakdown that sums to no more than the total", %{state: state} do
+    assert {:ok, _query, result, _state} =
+             EctoLibSql.handle_execute(
+               "SELECT id, name FROM items WHERE id >= ? ORDER BY id",
+               [2],
+               [timing: true],
+               state
+             )
+
+    assert result.rows == [[2, "b"], [3, "c"]]
+    assert result.num_rows == 2
+
+    assert %{prepare_us: prepare, execute_us: execute, fetch_us: fetch, total_us: total} =
+             result.timing
+
+    for value <- [prepare, execute, fetch, total], do: assert(is_integer(value) and value >= 0)
+    assert prepare + execute + fetch <= total
+  end
+
+  test "is off by default", %{state: state} do
+    assert {:ok, _query, result, _state} =
+             EctoLibSql.handle_execute("SELECT id FROM items", [], [], state)
+
+    assert result.timing == nil
+  end
+
+  test "times writes inside a transaction", %{state: state} do
+    {:ok, _, trx_state} = EctoLibSql.handle_begin([], state)
+
+    assert {:ok, _query, result, trx_state} =
+             EctoLibSql.handle_execute(
+               "INSERT INTO items (name) VALUES (?)",
+               ["d"],
+               [timing: true],
+               trx_state
+             )
+
+    assert result.command == :insert
+    assert result.num_rows == 1
+    assert %{fetch_us: 0, total_us: total} = result.timing
+    assert is_integer(total)
+
+    {:ok, _, state} = EctoLibSql.handle_commit([], trx_state)
+
+    assert {:ok, 4} = EctoLibSql.Native.query_scalar(state, "SELECT count(*) FROM items")
+  end
+
+  test "returns errors as usual", %{state: state} do
+    assert {:error, %EctoLibSql.Error{message: message}, _state} =
+             EctoLibSql.handle_execute("SELECT * FROM missing", [], [timing: true], state)
+
+    assert message =~ "no such table"
+  end
+end
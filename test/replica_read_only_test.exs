@@ -0,0 +1,73 @@
+defmodule EctoLibSql.ReplicaReadOnlyTest do
+  use ExUnit.Case
+
+  # A local replica has no primary, so libsql has nowhere to forward writes.
+  # Run just these with `mix test --only replica`.
+  @moduletag :replica
+
+  # local_replica is only accepted by builds with the test-hooks feature
+  @moduletag skip:
+               System.get_env("ECTO_LIBSQL_TEST_HOOKS") not in ["1", "true"] &&
+                 "requires a build with ECTO_LIBSQL_TEST_HOOKS=1"
+
+  setup do
+    test_db = "z_ecto_libsql_test-replica_read_only_#{:erlang.unique_integer([:positive])}.db"
+
+    {:ok, state} = EctoLibSql.connect(database: test_db, local_replica: true)
+
+    on_exit(fn ->
+      EctoLibSql.disconnect([], state)
+      EctoLibSql.TestHelpers.cleanup_db_files(test_db)
+    end)
+
+    {:ok, state: state}
+  end
+
+  test "writes fail with :replica_read_only", %{state: state} do
+    # Writes are rejected before they reach SQLite, so the table need not exist
+    sql = "INSERT INTO users (name) VALUES (?)"
+
+    assert {:error, %EctoLibSql.Error{sqlite: %{code: :replica_read_only}} = error, _state} =
+             EctoLibSql.handle_execute(sql, ["Alice"], [], state)
+
+    assert error.message =~ "replica without a primary"
+
+    assert {:error, %EctoLibSql.Error{sqlite: %{code: :replica_read_only}}, _state} =
+             EctoLibSql.handle_execute("INSERT INTO users (name) VALUES ('Bob')", [], [], state)
+
+    # RETURNING goes through the row-returning path
+    returning_sql = "INSERT INTO users (name) VALUES ('Carol') RETURNING id"
+
+    assert {:error, %EctoLibSql.Error{sqlite: %{code: :replica_read_only}}, _state} =
+             EctoLibSql.handle_execute(returning_sql, [], [], state)
+  end
+
+  test "prepared statements, batches and pipelines report :replica_read_only", %{
+    state: state
+  } do
+    sql = "INSERT INTO users (name) VALUES ('Bob')"
+
+    assert {:error, :replica_read_only} = EctoLibSql.Native.prepare(state, sql)
+    assert {:error, :replica_read_only} = EctoLibSql.Native.execute_batch_sql(state, sql)
+
+    assert {:error, {0, :replica_read_only}} =
+             EctoLibSql.Native.query_pipeline(state, [{sql, []}])
+  end
+
+  test "reads are served locally", %{state: state} do
+    assert {:ok, _query, %EctoLibSql.Result{rows: [[1]]}, _state} =
+             EctoLibSql.handle_execute("SELECT 1", [], [], state)
+  end
+
+  test "rejects invalid local_replica options" do
+    assert {:error, "local_replica must be a boolean"} =
+             EctoLibSql.connect(database: "unused.db", local_replica: :yes)
+
+    assert {:error, "encryption_key is not supported for local_replica connections"} =
+             EctoLibSql.connect(
+               database: "unused.db",
+               local_replica: true,
+               encryption_key: String.duplicate("k", 32)
+             )
+  end
+end
@@ -320,6 +320,37 @@ defmodule EctoLibSql.SavepointTest do
     end
   end
 
+  describe "savepoint depth" do
+    test "tracks creation, release and rollback", %{state: state} do
+      {:ok, trx_state} = Native.begin(state)
+      assert {:ok, 0} = Native.savepoint_depth(trx_state)
+
+      :ok = Native.create_savepoint(trx_state, "sp1")
+      :ok = Native.create_savepoint(trx_state, "sp2")
+      :ok = Native.create_savepoint(trx_state, "sp3")
+      assert {:ok, 3} = Native.savepoint_depth(trx_state)
+
+      :ok = Native.release_savepoint_by_name(trx_state, "sp3")
+      assert {:ok, 2} = Native.savepoint_depth(trx_state)
+
+      # Rolling back keeps the savepoint open but discards those opened after it
+      :ok = Native.create_savepoint(trx_state, "sp3")
+      :ok = Native.rollback_to_savepoint_by_name(trx_state, "sp1")
+      assert {:ok, 1} = Native.savepoint_depth(trx_state)
+
+      # Releasing an outer savepoint releases everything nested in it
+      :ok = Native.create_savepoint(trx_state, "sp2")
+      :ok = Native.release_savepoint_by_name(trx_state, "SP1")
+      assert {:ok, 0} = Native.savepoint_depth(trx_state)
+
+      {:ok, _committed_state} = Native.commit(trx_state)
+    end
+
+    test "is unavailable outside a transaction", %{state: state} do
+      assert {:error, "No active transaction"} = Native.savepoint_depth(state)
+    end
+  end
+
   describe "error scenarios" do
     test "error in savepoint can be rolled back", %{state: state} do
       {:ok, trx_state} = Native.begin(state)
@@ -0,0 +1,88 @@
+defmodule EctoLibSql.SnapshotTransactionTest do
+  use ExUnit.Case
+
+  alias EctoLibSql.Native
+  alias EctoLibSql.Pragma
+
+  setup do
+    test_db = "z_ecto_libsql_test-snapshot_#{:erlang.unique_integer([:positive])}.db"
+
+    {:ok, writer} = EctoLibSql.connect(database: test_db)
+    {:ok, reader} = EctoLibSql.connect(database: test_db)
+
+    {:ok, _} = Pragma.set_journal_mode(writer, :wal)
+
+    {:ok, _query, _result, writer} =
+      EctoLibSql.handle_execute(
+        "CREATE TABLE ledger (id INTEGER PRIMARY KEY, amount INTEGER)",
+        [],
+        [],
+        writer
+      )
+
+    {:ok, _query, _result, writer} =
+      EctoLibSql.handle_execute("INSERT INTO ledger (amount) VALUES (10), (20)", [], [], writer)
+
+    on_exit(fn ->
+      EctoLibSql.disconnect([], reader)
+      EctoLibSql.disconnect([], writer)
+      EctoLibSql.TestHelpers.cleanup_db_files(test_db)
+    end)
+
+    {:ok, writer: writer, reader: reader}
+  end
+
+  defp totals(state) do
+    {:ok, _query, result, _state} =
+      EctoLibSql.handle_execute("SELECT COUNT(*), SUM(amount) FROM ledger", [], [], state)
+
+    result.rows
+  end
+
+  test "snapshot keeps seeing pre-write data while another connection writes", %{
+    writer: writer,
+    reader: reader
+  } do
+    {:ok, snapshot} = Native.begin_snapshot(reader)
+    assert is_binary(snapshot.trx_id)
+
+    # The writer is not blocked by the held read snapshot in WAL mode.
+    {:ok, _query, _result, _writer} =
+      EctoLibSql.handle_execute("INSERT INTO ledger (amount) VALUES (30)", [], [], writer)
+
+    {:ok, _query, _result, _writer} =
+      EctoLibSql.handle_execute("UPDATE ledger SET amount = 0 WHERE id = 1", [], [], writer)
+
+    assert totals(snapshot) == [[2, 30]]
+    assert totals(writer) == [[3, 50]]
+
+    # Still consistent on repeated reads within the snapshot.
+    assert totals(snapshot) == [[2, 30]]
+
+    assert {:ok, _} = Native.rollback(snapshot)
+
+    # Once released, the reader sees the committed writes.
+    assert totals(reader) == [[3, 50]]
+  end
+
+  test "snapshot is established at begin, not at first user query", %{
+    writer: writer,
+    reader: reader
+  } do
+    {:ok, snapshot} = Native.begin_snapshot(reader)
+
+    # Write before the reader runs any query of its own.
+    {:ok, _query, _result, _writer} =
+      EctoLibSql.handle_execute("INSERT INTO ledger (amount) VALUES (5)", [], [], writer)
+
+    assert totals(snapshot) == [[2, 30]]
+    assert {:ok, _} = Native.commit(snapshot)
+  end
+
+  test "released snapshot no longer holds a transaction", %{reader: reader} do
+    {:ok, snapshot} = Native.begin_snapshot(reader)
+    assert {:ok, _} = Native.rollback(snapshot)
+
+    assert Native.get_is_autocommit(reader) == true
+  end
+end
@@ -0,0 +1,72 @@
+defmodule EctoLibSql.TraceIdTest do
+  use ExUnit.Case
+
+  setup do
+    test_db = "z_ecto_libsql_test-trace_id_#{:erlang.unique_integer([:positive])}.db"
+
+    {:ok, state} = EctoLibSql.connect(database: test_db)
+
+    handler_id = "trace-id-test-#{:erlang.unique_integer([:positive])}"
+    test_pid = self()
+
+    :ok =
+      :telemetry.attach(
+        handler_id,
+        [:ecto_libsql, :query],
+        fn _event, measurements, metadata, _config ->
+          # Handlers run in the calling process; ignore queries from concurrent tests
+          if self() == test_pid, do: send(test_pid, {:query_event, measurements, metadata})
+        end,
+        nil
+      )
+
+    on_exit(fn ->
+      :telemetry.detach(handler_id)
+      EctoLibSql.disconnect([], state)
+      EctoLibSql.TestHelpers.cleanup_db_files(test_db)
+    end)
+
+    {:ok, state: state}
+  end
+
+  test "telemetry event carries the trace id and label", %{state: state} do
+    assert {:ok, _query, result, _state} =
+             EctoLibSql.handle_execute(
+               "SELECT 1",
+               [],
+               [trace_id: "req-123", label: "health_check"],
+               state
+             )
+
+    assert result.rows == [[1]]
+
+    assert_receive {:query_event, %{duration: duration},
+                    %{statement: "SELECT 1", result: :ok, trace_id: "req-123", label: "health_check"}}
+
+    assert is_integer(duration)
+  end
+
+  test "error carries the trace id and label", %{state: state} do
+    assert {:error, %EctoLibSql.Error{} = error, _state} =
+             EctoLibSql.handle_execute(
+               "SELECT * FROM missing_table",
+               [],
+               [trace_id: "req-456", label: :lookup],
+               state
+             )
+
+    assert error.trace_id == "req-456"
+    assert error.label == :lookup
+
+    assert_receive {:query_event, _measurements,
+                    %{result: :error, trace_id: "req-456", label: :lookup}}
+  end
+
+  test "options are optional and do not change results", %{state: state} do
+    assert {:ok, _query, result, _state} = EctoLibSql.handle_execute("SELECT 2", [], [], state)
+    assert result.rows == [[2]]
+
+    assert_receive {:query_event, _measurements,
+                    %{statement: "SELECT 2", trace_id: nil, label: nil}}
+  end
+end
@@ -76,6 +76,19 @@ defmodule TursoRemoteTest do
     {:error, :sync_timeout}
   end
 
+  # Fetch every batch of a remote cursor, checking that only the batch being
+  # fetched was ever read from the server
+  defp fetch_remote_batches(state, cursor, query, acc) do
+    case EctoLibSql.handle_fetch(query, cursor, [max_rows: 500], state) do
+      {:cont, result, state} ->
+        assert {:ok, 0} = EctoLibSql.Native.cursor_buffer_size(state, cursor.ref)
+        fetch_remote_batches(state, cursor, query, [result.num_rows | acc])
+
+      {:halt, _result, _state} ->
+        Enum.reverse(acc)
+    end
+  end
+
   setup do
     # Each test uses unique table names to avoid conflicts
     table_name = "test_#{:erlang.unique_integer([:positive])}"
@@ -552,6 +565,81 @@ defmodule TursoRemoteTest do
     end
   end
 
+  describe "remote cursor streaming" do
+    test "reads rows from the server batch by batch", %{table_name: table} do
+      {:ok, state} = EctoLibSql.connect(uri: @turso_uri, auth_token: @turso_token)
+
+      {:ok, _, _, state} =
+        EctoLibSql.handle_execute(
+          "CREATE TABLE IF NOT EXISTS #{table} (id INTEGER PRIMARY KEY, payload TEXT)",
+          [],
+          [],
+          state
+        )
+
+      {:ok, _, _, state} =
+        EctoLibSql.handle_execute(
+          """
+          WITH RECURSIVE n(i) AS (SELECT 1 UNION ALL SELECT i + 1 FROM n WHERE i < 5000)
+          INSERT INTO #{table} (id, payload) SELECT i, hex(randomblob(32)) FROM n
+          """,
+          [],
+          [],
+          state
+        )
+
+      query = %EctoLibSql.Query{statement: "SELECT id, payload FROM #{table} ORDER BY id"}
+      {:ok, ^query, cursor, state} = EctoLibSql.handle_declare(query, [], [], state)
+
+      # Nothing is buffered until the first fetch asks for rows
+      assert {:ok, 0} = EctoLibSql.Native.cursor_buffer_size(state, cursor.ref)
+
+      batch_sizes = fetch_remote_batches(state, cursor, query, [])
+
+      assert length(batch_sizes) == 10
+      assert Enum.all?(batch_sizes, &(&1 == 500))
+      assert Enum.sum(batch_sizes) == 5000
+
+      EctoLibSql.disconnect([], state)
+    end
+  end
+
+  describe "auto_reconnect" do
+    # simulate_connection_loss/1 only exists in builds with the test-hooks feature
+    @describetag skip:
+                   System.get_env("ECTO_LIBSQL_TEST_HOOKS") not in ["1", "true"] &&
+                     "requires a build with ECTO_LIBSQL_TEST_HOOKS=1"
+
+    test "the next query transparently reconnects after the connection drops", %{
+      table_name: _table
+    } do
+      {:ok, state} =
+        EctoLibSql.connect(uri: @turso_uri, auth_token: @turso_token, auto_reconnect: true)
+
+      :ok = EctoLibSql.Native.simulate_connection_loss(state.conn_id)
+
+      assert {:ok, _, %{rows: [[1]]}, state} =
+               EctoLibSql.handle_execute("SELECT 1", [], [], state)
+
+      # The rebuilt connection stays in place for later queries
+      assert {:ok, _, %{rows: [[2]]}, state} =
+               EctoLibSql.handle_execute("SELECT 2", [], [], state)
+
+      EctoLibSql.disconnect([], state)
+    end
+
+    test "without the option the dropped connection surfaces an error", %{table_name: _table} do
+      {:ok, state} = EctoLibSql.connect(uri: @turso_uri, auth_token: @turso_token)
+
+      :ok = EctoLibSql.Native.simulate_connection_loss(state.conn_id)
+
+      assert {:error, %EctoLibSql.Error{}, state} =
+               EctoLibSql.handle_execute("SELECT 1", [], [], state)
+
+      EctoLibSql.disconnect([], state)
+    end
+  end
+
   describe "remote error handling" do
     test "invalid SQL returns proper error", %{table_name: _table} do
       {:ok, state} = EctoLibSql.connect(uri: @turso_uri, auth_token: @turso_token)
@@ -834,6 +922,101 @@ defmodule TursoRemoteTest do
       EctoLibSql.disconnect([], remote_state)
     end
 
+    test "replica metrics track syncs over time", %{table_name: table} do
+      local_db = "z_ecto_libsql_test-replica_metrics_#{:erlang.unique_integer([:positive])}.db"
+
+      on_exit(fn ->
+        cleanup_local_db(local_db)
+      end)
+
+      {:ok, replica_state} =
+        EctoLibSql.connect(
+          database: local_db,
+          uri: @turso_uri,
+          auth_token: @turso_token,
+          sync: false
+        )
+
+      {:ok, before} = EctoLibSql.Native.get_replica_metrics(replica_state)
+
+      {:ok, _, _, replica_state} =
+        EctoLibSql.handle_execute(
+          "CREATE TABLE IF NOT EXISTS #{table} (id INTEGER PRIMARY KEY, data TEXT)",
+          [],
+          [],
+          replica_state
+        )
+
+      {:ok, "success sync"} = EctoLibSql.Native.sync(replica_state)
+      {:ok, first} = EctoLibSql.Native.get_replica_metrics(replica_state)
+
+      assert first.total_syncs == before.total_syncs + 1
+      assert first.total_frames_applied >= before.total_frames_applied
+      assert %DateTime{} = first.last_sync_at
+
+      # Timestamps have millisecond resolution
+      Process.sleep(10)
+
+      {:ok, "success sync"} = EctoLibSql.Native.sync(replica_state)
+      {:ok, second} = EctoLibSql.Native.get_replica_metrics(replica_state)
+
+      assert second.total_syncs == first.total_syncs + 1
+      assert second.total_frames_applied >= first.total_frames_applied
+      assert DateTime.compare(second.last_sync_at, first.last_sync_at) == :gt
+      assert second.failed_syncs == 0
+
+      EctoLibSql.disconnect([], replica_state)
+    end
+
+    test "wait_for_frame blocks until the replica catches up", %{table_name: table} do
+      writer_db = "z_ecto_libsql_test-wait_writer_#{:erlang.unique_integer([:positive])}.db"
+      reader_db = "z_ecto_libsql_test-wait_reader_#{:erlang.unique_integer([:positive])}.db"
+
+      on_exit(fn ->
+        cleanup_local_db(writer_db)
+        cleanup_local_db(reader_db)
+      end)
+
+      replica_opts = [uri: @turso_uri, auth_token: @turso_token, sync: false]
+      {:ok, writer} = EctoLibSql.connect([database: writer_db] ++ replica_opts)
+      {:ok, reader} = EctoLibSql.connect([database: reader_db] ++ replica_opts)
+
+      # Writes on a replica go to the primary
+      {:ok, _, _, writer} =
+        EctoLibSql.handle_execute(
+          "CREATE TABLE IF NOT EXISTS #{table} (id INTEGER PRIMARY KEY, value TEXT)",
+          [],
+          [],
+          writer
+        )
+
+      {:ok, _, _, writer} =
+        EctoLibSql.handle_execute(
+          "INSERT INTO #{table} (id, value) VALUES (?, ?)",
+          [1, "written_on_primary"],
+          [],
+          writer
+        )
+
+      {:ok, frame} = EctoLibSql.Native.get_max_write_frame(writer.conn_id)
+      assert frame > 0
+
+      # The reader does not sync on its own, so waiting alone times out
+      assert {:error, :wait_timeout} = EctoLibSql.Native.wait_for_frame(reader, frame, 200)
+
+      waiter = Task.async(fn -> EctoLibSql.Native.wait_for_frame(reader, frame, 10_000) end)
+      {:ok, _} = EctoLibSql.Native.sync(reader)
+      assert :ok = Task.await(waiter, 15_000)
+
+      {:ok, _, result, _} =
+        EctoLibSql.handle_execute("SELECT value FROM #{table} WHERE id = ?", [1], [], reader)
+
+      assert result.rows == [["written_on_primary"]]
+
+      EctoLibSql.disconnect([], writer)
+      EctoLibSql.disconnect([], reader)
+    end
+
     test "replica provides fast local reads", %{table_name: table} do
       local_db = "z_ecto_libsql_test-fast_read_#{:erlang.unique_integer([:positive])}.db"
 
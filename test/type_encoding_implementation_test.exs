@@ -585,6 +585,89 @@ defmodule EctoLibSql.TypeEncodingImplementationTest do
       [[stored]] = result.rows
       assert stored == whitespace
     end
+
+    test "tagged charlist is bound as TEXT" do
+      result =
+        SQL.query!(TestRepo, "INSERT INTO test_types (text_col) VALUES (?)", [
+          {:charlist, ~c"hello"}
+        ])
+
+      assert result.num_rows == 1
+
+      result =
+        SQL.query!(TestRepo, "SELECT text_col, typeof(text_col) FROM test_types WHERE id = ?", [
+          result.last_insert_id
+        ])
+
+      assert [["hello", "text"]] = result.rows
+    end
+
+    test "charlist 'hello' reads back as \"hello\"" do
+      # Untagged, the bytes are stored as a BLOB, which reads back as the same binary
+      SQL.query!(TestRepo, "INSERT INTO test_types (blob_col) VALUES (?)", [~c"hello"])
+
+      result =
+        SQL.query!(
+          TestRepo,
+          "SELECT blob_col, typeof(blob_col) FROM test_types ORDER BY id DESC LIMIT 1"
+        )
+
+      assert [["hello", "blob"]] = result.rows
+
+      # Tagged, the same charlist is stored as TEXT
+      SQL.query!(TestRepo, "INSERT INTO test_types (text_col) VALUES (?)", [
+        {:charlist, ~c"hello"}
+      ])
+
+      result =
+        SQL.query!(
+          TestRepo,
+          "SELECT text_col, typeof(text_col) FROM test_types ORDER BY id DESC LIMIT 1"
+        )
+
+      assert [["hello", "text"]] = result.rows
+    end
+
+    test "tagged unicode charlist is bound as TEXT" do
+      SQL.query!(TestRepo, "INSERT INTO test_types (text_col) VALUES (?)", [
+        {:charlist, ~c"héllo 你好"}
+      ])
+
+      result = SQL.query!(TestRepo, "SELECT text_col FROM test_types ORDER BY id DESC LIMIT 1")
+      assert [["héllo 你好"]] = result.rows
+    end
+
+    test "printable integer list is still bound as a BLOB" do
+      SQL.query!(TestRepo, "INSERT INTO test_types (blob_col) VALUES (?)", [[65, 66, 67]])
+
+      result =
+        SQL.query!(
+          TestRepo,
+          "SELECT blob_col, typeof(blob_col) FROM test_types ORDER BY id DESC LIMIT 1"
+        )
+
+      assert [["ABC", "blob"]] = result.rows
+    end
+
+    test "non-printable integer list is bound as a BLOB" do
+      SQL.query!(TestRepo, "INSERT INTO test_types (blob_col) VALUES (?)", [[1, 2, 3]])
+
+      result =
+        SQL.query!(
+          TestRepo,
+          "SELECT blob_col, typeof(blob_col) FROM test_types ORDER BY id DESC LIMIT 1"
+        )
+
+      assert [[<<1, 2, 3>>, "blob"]] = result.rows
+    end
+
+    test "tagged list that is not a charlist is rejected" do
+      assert_raise EctoLibSql.Error, ~r/Invalid charlist/, fn ->
+        SQL.query!(TestRepo, "INSERT INTO test_types (text_col) VALUES (?)", [
+          {:charlist, [104, 105, 0]}
+        ])
+      end
+    end
   end
 
   describe "binary encoding edge cases" do
@@ -647,7 +730,8 @@ defmodule EctoLibSql.TypeEncodingImplementationTest do
         id INTEGER PRIMARY KEY AUTOINCREMENT,
         int_col INTEGER,
         real_col REAL,
-        text_col TEXT
+        text_col TEXT,
+        numeric_col NUMERIC
       )
       """)
 
@@ -658,6 +742,39 @@ defmodule EctoLibSql.TypeEncodingImplementationTest do
       :ok
     end
 
+    test "NUMERIC affinity decodes to the storage class of each row" do
+      SQL.query!(TestRepo, "INSERT INTO test_types (numeric_col) VALUES (?)", [1])
+      SQL.query!(TestRepo, "INSERT INTO test_types (numeric_col) VALUES (?)", [1.5])
+      # NUMERIC affinity stores a real with no fractional part as an integer
+      SQL.query!(TestRepo, "INSERT INTO test_types (numeric_col) VALUES (?)", [2.0])
+
+      result =
+        SQL.query!(
+          TestRepo,
+          "SELECT numeric_col, typeof(numeric_col) FROM test_types ORDER BY id"
+        )
+
+      assert [[1, "integer"], [1.5, "real"], [2, "integer"]] = result.rows
+      assert [[int_value, _], [real_value, _], _] = result.rows
+      assert is_integer(int_value)
+      assert is_float(real_value)
+    end
+
+    test "range tuple binds both ends of BETWEEN" do
+      for n <- [5, 10, 15, 20, 25] do
+        SQL.query!(TestRepo, "INSERT INTO test_types (int_col) VALUES (?)", [n])
+      end
+
+      result =
+        SQL.query!(
+          TestRepo,
+          "SELECT int_col FROM test_types WHERE int_col BETWEEN ? AND ? ORDER BY int_col",
+          [{10, 20}]
+        )
+
+      assert result.rows == [[10], [15], [20]]
+    end
+
     test "very large integer" do
       large_int = 9_223_372_036_854_775_807
 
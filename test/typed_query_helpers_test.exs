@@ -0,0 +1,305 @@
+defmodule EctoLibSql.TypedQueryHelpersTest do
+  use ExUnit.Case
+
+  alias EctoLibSql.Native
+
+  setup do
+    test_db = "z_ecto_libsql_test-typed_query_#{:erlang.unique_integer([:positive])}.db"
+
+    {:ok, state} = EctoLibSql.connect(database: test_db)
+
+    {:ok, _query, _result, state} =
+      EctoLibSql.handle_execute(
+        "CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT, age INTEGER)",
+        [],
+        [],
+        state
+      )
+
+    {:ok, _query, _result, state} =
+      EctoLibSql.handle_execute(
+        "INSERT INTO users (name, age) VALUES ('Alice', 30), ('Bob', 25), ('Carol', 41)",
+        [],
+        [],
+        state
+      )
+
+    on_exit(fn ->
+      EctoLibSql.disconnect([], state)
+      EctoLibSql.TestHelpers.cleanup_db_files(test_db)
+    end)
+
+    {:ok, state: state}
+  end
+
+  describe "query_one/3" do
+    test "returns the single matching row", %{state: state} do
+      assert {:ok, [1, "Alice"]} =
+               Native.query_one(state, "SELECT id, name FROM users WHERE id = ?", [1])
+    end
+
+    test "reports no rows", %{state: state} do
+      assert {:error, {:no_rows, %{expected: 1, actual: 0}}} =
+               Native.query_one(state, "SELECT id FROM users WHERE id = ?", [999])
+    end
+
+    test "reports too many rows with the actual count", %{state: state} do
+      assert {:error, {:too_many_rows, %{expected: 1, actual: 2}}} =
+               Native.query_one(state, "SELECT id FROM users WHERE age >= ?", [30])
+    end
+  end
+
+  describe "query_scalar/3" do
+    test "returns the single value", %{state: state} do
+      assert {:ok, "Bob"} = Native.query_scalar(state, "SELECT name FROM users WHERE id = ?", [2])
+      assert {:ok, 41} = Native.query_scalar(state, "SELECT max(age) FROM users")
+    end
+
+    test "reports no rows", %{state: state} do
+      assert {:error, {:no_rows, %{expected: 1, actual: 0}}} =
+               Native.query_scalar(state, "SELECT name FROM users WHERE id = ?", [999])
+    end
+
+    test "reports too many rows with the actual count", %{state: state} do
+      assert {:error, {:too_many_rows, %{expected: 1, actual: 3}}} =
+               Native.query_scalar(state, "SELECT name FROM users")
+    end
+
+    test "reports too many columns with the actual count", %{state: state} do
+      assert {:error, {:too_many_columns, %{expected: 1, actual: 3}}} =
+               Native.query_scalar(state, "SELECT id, name, age FROM users WHERE id = ?", [1])
+    end
+
+    test "passes query errors through", %{state: state} do
+      assert {:error, %EctoLibSql.Error{}} =
+               Native.query_scalar(state, "SELECT nope FROM missing_table")
+    end
+  end
+
+  describe "query_reduce/4" do
+    test "sum matches SQL SUM", %{state: state} do
+      {:ok, _query, _result, state} =
+        EctoLibSql.handle_execute(
+          """
+          WITH RECURSIVE seq(i) AS (SELECT 1 UNION ALL SELECT i + 1 FROM seq WHERE i < 5000)
+          INSERT INTO users (name, age) SELECT 'user' || i, i % 97 FROM seq
+          """,
+          [],
+          [],
+          state
+        )
+
+      assert {:ok, expected} = Native.query_scalar(state, "SELECT SUM(age) FROM users")
+      assert {:ok, ^expected} =
+               Native.query_reduce(state, "SELECT age FROM users", [], {:sum, "age"})
+
+      assert {:ok, 96} =
+               Native.query_reduce(state, "SELECT * FROM users WHERE age < ?", [97], {:max, 2})
+    end
+
+    test "min, max and distinct", %{state: state} do
+      sql = "SELECT name, age FROM users ORDER BY id"
+
+      assert {:ok, 25} = Native.query_reduce(state, sql, [], {:min, "AGE"})
+      assert {:ok, "Carol"} = Native.query_reduce(state, sql, [], {:max, "name"})
+
+      {:ok, _query, _result, state} =
+        EctoLibSql.handle_execute(
+          "INSERT INTO users (name, age) VALUES ('Alice', NULL)",
+          [],
+          [],
+          state
+        )
+
+      assert {:ok, ["Alice", "Bob", "Carol"]} =
+               Native.query_reduce(state, sql, [], {:distinct, "name"})
+
+      null_ages = "SELECT age FROM users WHERE age IS NULL"
+      assert {:ok, nil} = Native.query_reduce(state, null_ages, [], {:sum, 0})
+    end
+
+    test "reports unknown reducers and columns", %{state: state} do
+      assert {:error, "Column 'missing' not found in query results"} =
+               Native.query_reduce(state, "SELECT age FROM users", [], {:sum, "missing"})
+
+      assert {:error, "sum requires numeric values, got text"} =
+               Native.query_reduce(state, "SELECT name FROM users", [], {:sum, "name"})
+
+      assert {:error, "Invalid reducer: " <> _} =
+               Native.query_reduce(state, "SELECT age FROM users", [], {:avg, "age"})
+    end
+  end
+
+  describe "table_exists/2 and table_schema/2" do
+    test "report an existing table and its CREATE statement", %{state: state} do
+      assert {:ok, true} = Native.table_exists(state, "users")
+      assert {:ok, true} = Native.table_exists(state, :USERS)
+
+      assert {:ok, "CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT, age INTEGER)"} =
+               Native.table_schema(state, "users")
+    end
+
+    test "report a missing table", %{state: state} do
+      assert {:ok, false} = Native.table_exists(state, "missing")
+      assert {:ok, nil} = Native.table_schema(state, "missing")
+    end
+
+    test "treat the name as data, not SQL", %{state: state} do
+      assert {:ok, false} = Native.table_exists(state, "users' OR '1'='1")
+    end
+
+    test "ignore views with the same name", %{state: state} do
+      {:ok, _query, _result, _state} =
+        EctoLibSql.handle_execute("CREATE VIEW adults AS SELECT * FROM users", [], [], state)
+
+      assert {:ok, false} = Native.table_exists(state, "adults")
+    end
+  end
+
+  describe "list_objects/3" do
+    setup %{state: state} do
+      {:ok, _results} =
+        Native.execute_batch_sql(state, """
+        CREATE TABLE events (id INTEGER PRIMARY KEY AUTOINCREMENT, user_id INTEGER);
+        CREATE VIEW adults AS SELECT * FROM users WHERE age >= 18;
+        CREATE INDEX idx_users_age ON users (age);
+        CREATE TRIGGER users_touch AFTER UPDATE ON users BEGIN SELECT 1; END;
+        """)
+
+      :ok
+    end
+
+    test "lists every kind with its type and SQL", %{state: state} do
+      assert {:ok, objects} = Native.list_objects(state)
+
+      assert [
+               {"idx_users_age", :index},
+               {"events", :table},
+               {"users", :table},
+               {"users_touch", :trigger},
+               {"adults", :view}
+             ] = Enum.map(objects, &{&1.name, &1.type})
+
+      assert %{sql: "CREATE VIEW adults AS SELECT * FROM users WHERE age >= 18"} =
+               Enum.find(objects, &(&1.name == "adults"))
+
+      assert %{sql: "CREATE INDEX idx_users_age ON users (age)"} =
+               Enum.find(objects, &(&1.name == "idx_users_age"))
+    end
+
+    test "filters by kind", %{state: state} do
+      assert {:ok, [%{name: "adults", type: :view}]} = Native.list_objects(state, :view)
+      assert {:ok, [%{name: "idx_users_age"}]} = Native.list_objects(state, :index)
+      assert {:ok, tables} = Native.list_objects(state, :table)
+      assert Enum.map(tables, & &1.name) == ["events", "users"]
+    end
+
+    test "includes sqlite_* objects only when asked", %{state: state} do
+      assert {:ok, tables} = Native.list_objects(state, :table, include_internal: true)
+      assert "sqlite_sequence" in Enum.map(tables, & &1.name)
+    end
+
+    test "rejects an unknown kind", %{state: state} do
+      assert {:error, "Unknown object kind: :column"} = Native.list_objects(state, :column)
+    end
+  end
+
+  describe "ensure_schema/2" do
+    @schema [
+      "CREATE TABLE IF NOT EXISTS users (id INTEGER PRIMARY KEY, name TEXT, age INTEGER)",
+      "CREATE TABLE IF NOT EXISTS \"audit log\" (id INTEGER PRIMARY KEY, entry TEXT)",
+      "CREATE UNIQUE INDEX IF NOT EXISTS idx_users_name ON users (name)"
+    ]
+
+    test "creates missing objects and is a no-op the second time", %{state: state} do
+      assert {:ok, %{created: ["audit log", "idx_users_name"], existing: ["users"]}} =
+               Native.ensure_schema(state, @schema)
+
+      assert {:ok, %{created: [], existing: ["users", "audit log", "idx_users_name"]}} =
+               Native.ensure_schema(state, @schema)
+    end
+
+    test "applies nothing when a statement fails", %{state: state} do
+      statements = [
+        "CREATE TABLE IF NOT EXISTS events (id INTEGER PRIMARY KEY)",
+        "CREATE INDEX IF NOT EXISTS idx_missing ON no_such_table (id)"
+      ]
+
+      assert {:error, _reason} = Native.ensure_schema(state, statements)
+      assert {:ok, false} = Native.table_exists(state, "events")
+    end
+
+    test "rejects statements that are not CREATE", %{state: state} do
+      assert {:error, "ensure_schema only accepts CREATE statements: DROP TABLE users"} =
+               Native.ensure_schema(state, ["DROP TABLE users"])
+
+      assert {:ok, true} = Native.table_exists(state, "users")
+    end
+  end
+
+  describe "drop_temp_objects/1" do
+    test "drops temp triggers, views and tables but leaves the main schema", %{state: state} do
+      for sql <- [
+            "CREATE TEMP TABLE \"scratch pad\" (id INTEGER PRIMARY KEY, note TEXT)",
+            "CREATE INDEX temp.idx_scratch_note ON \"scratch pad\" (note)",
+            "CREATE TEMPORARY VIEW recent AS SELECT * FROM \"scratch pad\"",
+            "CREATE TEMP TRIGGER audit_users AFTER INSERT ON main.users BEGIN SELECT 1; END"
+          ] do
+        assert {:ok, _query, _result, _state} = EctoLibSql.handle_execute(sql, [], [], state)
+      end
+
+      assert {:ok, ["audit_users", "recent", "scratch pad"]} = Native.drop_temp_objects(state)
+      assert {:error, _reason} = Native.count(state, "scratch pad")
+      assert {:ok, 3} = Native.count(state, "users")
+
+      assert {:ok, []} = Native.drop_temp_objects(state)
+    end
+  end
+
+  describe "affected_keys/3" do
+    test "returns the ids of every updated row", %{state: state} do
+      assert {:ok, ids} =
+               Native.affected_keys(
+                 state,
+                 "UPDATE users SET age = age + 1 WHERE age >= ? RETURNING id",
+                 [30]
+               )
+
+      assert Enum.sort(ids) == [1, 3]
+      assert {:ok, 31} = Native.query_scalar(state, "SELECT age FROM users WHERE id = 1")
+    end
+
+    test "returns an empty list when nothing matches", %{state: state} do
+      assert {:ok, []} =
+               Native.affected_keys(
+                 state,
+                 "UPDATE users SET age = 0 WHERE age > ? RETURNING id",
+                 [100]
+               )
+    end
+
+    test "returns a list per row for composite keys", %{state: state} do
+      assert {:ok, [[2, "Bob"]]} =
+               Native.affected_keys(
+                 state,
+                 "UPDATE users SET age = 26 WHERE id = ? RETURNING id, name",
+                 [2]
+               )
+    end
+
+    test "requires a RETURNING clause", %{state: state} do
+      assert {:error, :missing_returning} =
+               Native.affected_keys(state, "UPDATE users SET age = 0", [])
+
+      # Nothing was written
+      assert {:ok, 41} = Native.query_scalar(state, "SELECT age FROM users WHERE id = 3")
+    end
+
+    test "ignores returning inside a string literal", %{state: state} do
+      assert {:error, :missing_returning} =
+               Native.affected_keys(state, "UPDATE users SET name = 'x returning y'", [])
+
+      assert {:ok, "Alice"} = Native.query_scalar(state, "SELECT name FROM users WHERE id = 1")
+    end
+  end
+end